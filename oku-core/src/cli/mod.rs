@@ -8,8 +8,15 @@ use iroh_docs::NamespaceId;
 use iroh_tickets::Ticket;
 use log::{info, LevelFilter};
 use miette::{miette, IntoDiagnostic};
-use oku_core::config::OkuFsConfig;
-use oku_core::database::core::OkuDatabase;
+use oku_core::config::{ContentWarningBehaviour, OkuFsConfig};
+use oku_core::database::core::{OkuDatabase, DATABASE};
+use oku_core::database::mutes::OkuMuteRule;
+use oku_core::database::posts::core::{OkuTimelineCursor, OkuVisibility};
+use oku_core::database::posts::search::{OkuPostSearch, DEFAULT_SEARCH_PAGE_SIZE};
+use oku_core::database::posts::statistics::OkuTimeWindow;
+use oku_core::database::posts::syndication::{OkuFeedFormat, OkuFeedSource};
+use oku_core::fs::net::core::DEFAULT_TIMELINE_PAGE_SIZE;
+use oku_core::fs::util::parse_date;
 use oku_core::fs::OkuFs;
 use rayon::iter::FromParallelIterator;
 use rayon::iter::IntoParallelIterator;
@@ -122,6 +129,21 @@ enum NetCommands {
         /// The new display name to use.
         display_name: String,
     },
+    /// Sets the current user's biography.
+    SetBio {
+        #[arg(value_name = "BIO")]
+        /// The new biography to use. If none is specified, the current user's biography will be removed.
+        bio: Option<String>,
+    },
+    /// Show known users, or search them by display name, biography, or author ID.
+    Users {
+        #[arg(short, long, value_name = "QUERY")]
+        /// The search query. If none is specified, all known users will be shown.
+        search: Option<String>,
+        #[arg(short, long, default_value_t = 10)]
+        /// The maximum number of search results to show.
+        limit: usize,
+    },
     /// Follow a user.
     Follow {
         #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
@@ -134,11 +156,20 @@ enum NetCommands {
         /// The ID of the author to unfollow.
         author_id: AuthorId,
     },
-    /// Show a user's list of followers.
+    /// Show the users a user follows.
     Following {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author whose followed users should be shown. If none is specified, the current user's followed users will be shown.
+        author_id: Option<AuthorId>,
+    },
+    /// Show the known users following a user.
+    Followers {
         #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
         /// The ID of the author whose followers should be shown. If none is specified, the current user's followers will be shown.
         author_id: Option<AuthorId>,
+        #[arg(short, long, default_value_t = false)]
+        /// Whether to only show followers the author follows in return.
+        mutual: bool,
     },
     /// Block a user.
     Block {
@@ -157,6 +188,33 @@ enum NetCommands {
         #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
         /// The ID of the author whose blocked list should be shown. If none is specified, the current user's blocked list will be shown.
         author_id: Option<AuthorId>,
+        #[arg(short, long, default_value_t = false)]
+        /// Whether to include authors blocked through subscribed blocked lists; only applies to the current user.
+        all: bool,
+    },
+    /// Block every author blocked by another user.
+    SubscribeBlocklist {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author whose blocked list should be applied.
+        author_id: AuthorId,
+    },
+    /// Stop applying another user's blocked list.
+    UnsubscribeBlocklist {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author whose blocked list should no longer be applied.
+        author_id: AuthorId,
+    },
+    /// Exempt a user from subscribed blocked lists.
+    Allow {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author to allow.
+        author_id: AuthorId,
+    },
+    /// Stop exempting a user from subscribed blocked lists.
+    Disallow {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author to no longer allow.
+        author_id: AuthorId,
     },
     /// View posts in chronological order.
     Timeline {
@@ -166,6 +224,12 @@ enum NetCommands {
         #[arg(short, long, value_name = "TAG")]
         /// The optional tags to filter posts by.
         tags: Option<Vec<String>>,
+        #[arg(short, long, value_parser = parse_cursor, value_name = "CURSOR")]
+        /// Only show posts older than this cursor, as printed after a page of posts, or a time in microseconds since the Unix epoch.
+        before: Option<OkuTimelineCursor>,
+        #[arg(short, long, default_value_t = DEFAULT_TIMELINE_PAGE_SIZE)]
+        /// The maximum number of posts to show.
+        limit: usize,
     },
     /// Create a post.
     Post {
@@ -175,9 +239,18 @@ enum NetCommands {
         #[arg(short, long, value_name = "TITLE")]
         /// The title of the post.
         title: Option<String>,
-        #[arg(short, long, value_name = "TAGS")]
+        #[arg(short = 'T', long, value_name = "TAGS")]
         /// The tags of the post.
         tags: Option<Vec<String>>,
+        #[arg(short = 'w', long, value_name = "CONTENT_WARNING")]
+        /// A warning to show in place of the post's body until it is revealed.
+        content_warning: Option<String>,
+        #[arg(short, long, default_value_t = false)]
+        /// Whether the post contains sensitive content.
+        sensitive: bool,
+        #[arg(short, long, value_parser = parse_visibility, default_value = "public", value_name = "VISIBILITY")]
+        /// Who is able to read the post; one of 'public', 'followers', or 'circle:…'.
+        visibility: OkuVisibility,
         #[arg(value_name = "BODY")]
         /// The body of the post.
         body: String,
@@ -190,21 +263,259 @@ enum NetCommands {
         #[arg(short, long, value_name = "POST_PATH")]
         /// The path to the post in the author's home replica.
         post_path: PathBuf,
+        #[arg(long, default_value_t = false)]
+        /// Whether to also show prior versions of the post.
+        history: bool,
     },
     /// Search posts.
     Search {
         #[arg(value_name = "QUERY")]
-        /// The search query.
+        /// The search query; if empty, all posts matching the filters are shown.
         query: String,
-        #[arg(default_value_t = 10)]
+        #[arg(default_value_t = DEFAULT_SEARCH_PAGE_SIZE)]
         /// The maximum number of results to show.
         result_limit: usize,
+        #[arg(short, long, value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// Only show posts by these users.
+        author: Vec<AuthorId>,
+        #[arg(short, long, value_name = "TAG")]
+        /// Only show posts with all of these tags.
+        tag: Vec<String>,
+        #[arg(short, long, value_name = "DOMAIN")]
+        /// Only show posts about URLs on this domain, or its subdomains.
+        domain: Option<String>,
+        #[arg(long, value_parser = parse_date, value_name = "DATE")]
+        /// Only show posts written at or after this date or RFC 3339 timestamp.
+        after: Option<u64>,
+        #[arg(long, value_parser = parse_date, value_name = "DATE")]
+        /// Only show posts written before this date or RFC 3339 timestamp.
+        before: Option<u64>,
+        #[arg(short, long, default_value_t = 1)]
+        /// The page of results to show.
+        page: usize,
+        #[arg(short, long, default_value_t = false)]
+        /// Whether to order results from newest to oldest, rather than by relevance.
+        newest: bool,
+        #[arg(short, long, default_value_t = false)]
+        /// Whether to show how many results have each tag and author.
+        facets: bool,
+    },
+    /// View posts regarding a URL.
+    About {
+        #[arg(value_name = "URL")]
+        /// The URL the posts regard.
+        url: Url,
+    },
+    /// Find posts similar to a URL or some text.
+    Similar {
+        #[arg(value_name = "URL_OR_TEXT")]
+        /// A URL that has been posted about, or otherwise some text.
+        url_or_text: String,
+        #[arg(default_value_t = 10)]
+        /// The maximum number of posts to show.
+        result_limit: usize,
     },
     /// View all tags used in at least one post.
     Tags {
         #[arg(default_value_t = false)]
         /// Whether to count the number of posts per tag.
         count: bool,
+        #[arg(short, long, value_parser = parse_since, value_name = "SINCE")]
+        /// Show the tags and URLs most posted about, and the most active authors, since a date, an RFC 3339 timestamp, or the start of the last 'day', 'week', 'month', or 'year'.
+        since: Option<u64>,
+        #[arg(short, long, default_value_t = 10)]
+        /// The maximum number of tags, URLs, and authors to show when showing activity since some time.
+        limit: usize,
+    },
+    /// Write posts as an Atom or JSON Feed document, for reading in feed readers.
+    Feed {
+        #[arg(short, long, value_parser = parse_author_id, value_name = "AUTHOR_ID", conflicts_with_all = ["tag", "query"])]
+        /// Include the posts by this user. If no user, tag, or query is specified, the local user's posts are included.
+        author: Option<AuthorId>,
+        #[arg(short, long, value_name = "TAG", conflicts_with = "query")]
+        /// Include the posts with this tag.
+        tag: Option<String>,
+        #[arg(short, long, value_name = "QUERY")]
+        /// Include the posts matching this search query.
+        query: Option<String>,
+        #[arg(short, long, value_parser = parse_feed_format, default_value = "atom", value_name = "FORMAT")]
+        /// The format of the feed; either 'atom' or 'json'.
+        format: OkuFeedFormat,
+        #[arg(short, long, default_value_t = 20)]
+        /// The maximum number of posts in the feed.
+        limit: usize,
+    },
+    /// Import an RSS or Atom feed from outside OkuNet; its items are shown alongside posts in the home timeline and search results.
+    Import {
+        #[arg(value_name = "SOURCE")]
+        /// The HTTP(S) URL or file path of the feed.
+        source: String,
+    },
+    /// Stop showing the items of an imported feed.
+    Unimport {
+        #[arg(value_name = "SOURCE")]
+        /// The HTTP(S) URL or file path of a previously-imported feed.
+        source: String,
+    },
+    /// List the feeds imported from outside OkuNet.
+    Imports,
+    /// Retrieve the latest content from followed users, and the users they follow.
+    Refresh {
+        #[arg(short, long, default_value_t = false)]
+        /// Whether to retrieve content regardless of when it was last retrieved.
+        force: bool,
+    },
+    /// Hide posts matching a rule from the timeline, tag pages, and search results.
+    Mute {
+        #[arg(value_parser = parse_mute_rule, value_name = "RULE")]
+        /// A rule of the form 'keyword:…', 'regex:…', 'tag:…', or 'domain:…'.
+        rule: OkuMuteRule,
+        #[arg(short, long, value_parser = humantime::parse_duration, value_name = "DURATION")]
+        /// How long the mute should apply for; if unspecified, the mute applies until removed.
+        duration: Option<Duration>,
+    },
+    /// Stop hiding posts matching a rule.
+    Unmute {
+        #[arg(value_parser = parse_mute_rule, value_name = "RULE")]
+        /// A previously-muted rule.
+        rule: OkuMuteRule,
+    },
+    /// List the rules currently muting posts.
+    Mutes,
+    /// Manage unpublished posts.
+    Draft {
+        #[command(subcommand)]
+        draft_commands: DraftCommands,
+    },
+    /// Send and read end-to-end encrypted direct messages.
+    Dm {
+        #[command(subcommand)]
+        dm_commands: DmCommands,
+    },
+    /// Manage circles of users able to read restricted posts.
+    Circle {
+        #[command(subcommand)]
+        circle_commands: CircleCommands,
+    },
+    /// Manage private petnames and notes for users.
+    Contact {
+        #[command(subcommand)]
+        contact_commands: ContactCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ContactCommands {
+    /// Give a user a petname, shown in place of their self-chosen display name.
+    Petname {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author to name.
+        author_id: AuthorId,
+        #[arg(value_name = "PETNAME")]
+        /// The petname to give the user. If none is specified, the user's petname will be removed.
+        petname: Option<String>,
+    },
+    /// Replace your notes about a user.
+    Notes {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author the notes are about.
+        author_id: AuthorId,
+        #[arg(value_name = "NOTES")]
+        /// The notes about the user. If none are specified, the notes will be removed.
+        notes: Option<String>,
+    },
+    /// List all users with petnames or notes.
+    List,
+}
+
+#[derive(Subcommand)]
+enum CircleCommands {
+    /// Add users to a circle, creating it if it does not exist.
+    Add {
+        #[arg(value_name = "NAME")]
+        /// The name of the circle.
+        name: String,
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The IDs of the authors to add.
+        author_ids: Vec<AuthorId>,
+    },
+    /// Remove users from a circle.
+    Remove {
+        #[arg(value_name = "NAME")]
+        /// The name of the circle.
+        name: String,
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The IDs of the authors to remove.
+        author_ids: Vec<AuthorId>,
+    },
+    /// Delete a circle.
+    Delete {
+        #[arg(value_name = "NAME")]
+        /// The name of the circle.
+        name: String,
+    },
+    /// List all circles and their members.
+    List,
+}
+
+#[derive(Subcommand)]
+enum DmCommands {
+    /// Send a direct message to a user.
+    Send {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author to send the message to.
+        author_id: AuthorId,
+        #[arg(value_name = "BODY")]
+        /// The body of the message.
+        body: String,
+    },
+    /// List conversations, or the messages exchanged with a user.
+    List {
+        #[arg(value_parser = parse_author_id, value_name = "AUTHOR_ID")]
+        /// The ID of the author whose conversation you wish to view.
+        author_id: Option<AuthorId>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DraftCommands {
+    /// Create or modify a draft.
+    Save {
+        #[arg(short, long, value_name = "URL")]
+        /// The URL of the draft.
+        url: Url,
+        #[arg(short, long, value_name = "TITLE")]
+        /// The title of the draft.
+        title: Option<String>,
+        #[arg(short = 'T', long, value_name = "TAGS")]
+        /// The tags of the draft.
+        tags: Option<Vec<String>>,
+        #[arg(short = 'w', long, value_name = "CONTENT_WARNING")]
+        /// A warning to show in place of the draft's body until it is revealed.
+        content_warning: Option<String>,
+        #[arg(short, long, default_value_t = false)]
+        /// Whether the draft contains sensitive content.
+        sensitive: bool,
+        #[arg(short, long, value_parser = parse_visibility, default_value = "public", value_name = "VISIBILITY")]
+        /// Who is able to read the draft; one of 'public', 'followers', or 'circle:…'.
+        visibility: OkuVisibility,
+        #[arg(value_name = "BODY")]
+        /// The body of the draft.
+        body: String,
+    },
+    /// List all drafts.
+    List,
+    /// Publish a draft to the OkuNet.
+    Publish {
+        #[arg(value_name = "URL")]
+        /// The URL of the draft.
+        url: Url,
+    },
+    /// Discard a draft without publishing it.
+    Discard {
+        #[arg(value_name = "URL")]
+        /// The URL of the draft.
+        url: Url,
     },
 }
 
@@ -371,6 +682,36 @@ enum FsCommands {
         /// The initial delay before publishing content to the Mainline DHT; if unspecified, defaults to half a second.
         initial_publish_delay: Option<Duration>,
     },
+    /// Set how many levels of the social graph are crawled when refreshing OkuNet users.
+    SetCrawlDepth {
+        #[arg(value_name = "CRAWL_DEPTH")]
+        /// The number of levels to crawl; if unspecified, defaults to two (followed users, and the users they follow).
+        crawl_depth: Option<usize>,
+    },
+    /// Set the maximum number of users crawled from each user's followed list, per level of the social graph.
+    SetCrawlFanOut {
+        #[arg(value_name = "CRAWL_FAN_OUT")]
+        /// The limits for each level, starting with the current user's followed list; levels without a limit are unlimited.
+        crawl_fan_out: Option<Vec<usize>>,
+    },
+    /// Set the maximum number of users crawled when refreshing OkuNet users.
+    SetCrawlUserCap {
+        #[arg(value_name = "CRAWL_USER_CAP")]
+        /// The maximum number of users; if unspecified, there is no limit.
+        crawl_user_cap: Option<usize>,
+    },
+    /// Set the maximum number of OkuNet users fetched at once.
+    SetCrawlParallelism {
+        #[arg(value_name = "CRAWL_PARALLELISM")]
+        /// The maximum number of users fetched at once; if unspecified, defaults to eight.
+        crawl_parallelism: Option<usize>,
+    },
+    /// Set how OkuNet posts with content warnings are presented.
+    SetContentWarningBehaviour {
+        #[arg(value_parser = parse_content_warning_behaviour, value_name = "BEHAVIOUR")]
+        /// One of 'collapse', 'expand', or 'hide'; if unspecified, defaults to 'collapse'.
+        content_warning_behaviour: Option<ContentWarningBehaviour>,
+    },
 }
 
 fn parse_namespace_id(value: &str) -> miette::Result<NamespaceId> {
@@ -381,6 +722,49 @@ fn parse_author_id(value: &str) -> miette::Result<AuthorId> {
     oku_core::fs::util::parse_array_hex_or_base32(value).map(AuthorId::from)
 }
 
+fn parse_since(value: &str) -> miette::Result<u64> {
+    match OkuTimeWindow::from_str(value) {
+        Ok(window) => Ok(window.start().unwrap_or_default()),
+        Err(_) => parse_date(value),
+    }
+}
+
+fn parse_content_warning_behaviour(value: &str) -> miette::Result<ContentWarningBehaviour> {
+    match value.to_lowercase().as_str() {
+        "collapse" => Ok(ContentWarningBehaviour::Collapse),
+        "expand" => Ok(ContentWarningBehaviour::Expand),
+        "hide" => Ok(ContentWarningBehaviour::Hide),
+        _ => Err(miette!("Unexpected content warning behaviour: {value}")),
+    }
+}
+
+fn parse_feed_format(value: &str) -> miette::Result<OkuFeedFormat> {
+    OkuFeedFormat::from_str(value)
+}
+
+fn parse_mute_rule(value: &str) -> miette::Result<OkuMuteRule> {
+    OkuMuteRule::from_str(value)
+}
+
+fn parse_visibility(value: &str) -> miette::Result<OkuVisibility> {
+    OkuVisibility::from_str(value)
+}
+
+fn parse_cursor(value: &str) -> miette::Result<OkuTimelineCursor> {
+    OkuTimelineCursor::from_str(value)
+}
+
+fn load_config() -> miette::Result<OkuFsConfig> {
+    cfg_select! {
+        feature = "persistent" => {
+            OkuFsConfig::load_or_create_config()
+        },
+        _ => {
+            Ok(OkuFsConfig::default())
+        }
+    }
+}
+
 #[tokio::main(flavor = "multi_thread")]
 pub async fn main() -> miette::Result<()> {
     miette::set_panic_hook();
@@ -615,6 +999,49 @@ pub async fn main() -> miette::Result<()> {
                     humantime::format_duration(config.get_initial_publish_delay())
                 );
             }
+            FsCommands::SetCrawlDepth { crawl_depth } => {
+                let config = load_config()?;
+                config.set_crawl_depth(&crawl_depth)?;
+                #[cfg(feature = "persistent")]
+                config.save()?;
+                info!("Set crawl depth to {}.", config.get_crawl_depth());
+            }
+            FsCommands::SetCrawlFanOut { crawl_fan_out } => {
+                let config = load_config()?;
+                config.set_crawl_fan_out(&crawl_fan_out)?;
+                #[cfg(feature = "persistent")]
+                config.save()?;
+                info!("Set crawl fan-out to {:?}.", config.get_crawl_fan_out());
+            }
+            FsCommands::SetCrawlUserCap { crawl_user_cap } => {
+                let config = load_config()?;
+                config.set_crawl_user_cap(&crawl_user_cap)?;
+                #[cfg(feature = "persistent")]
+                config.save()?;
+                info!("Set crawl user cap to {:?}.", config.get_crawl_user_cap());
+            }
+            FsCommands::SetCrawlParallelism { crawl_parallelism } => {
+                let config = load_config()?;
+                config.set_crawl_parallelism(&crawl_parallelism)?;
+                #[cfg(feature = "persistent")]
+                config.save()?;
+                info!(
+                    "Set crawl parallelism to {}.",
+                    config.get_crawl_parallelism()
+                );
+            }
+            FsCommands::SetContentWarningBehaviour {
+                content_warning_behaviour,
+            } => {
+                let config = load_config()?;
+                config.set_content_warning_behaviour(&content_warning_behaviour)?;
+                #[cfg(feature = "persistent")]
+                config.save()?;
+                info!(
+                    "Set content warning behaviour to {:?}.",
+                    config.get_content_warning_behaviour()
+                );
+            }
         },
         Some(Commands::Net(Net {
             net_commands: command,
@@ -641,6 +1068,22 @@ pub async fn main() -> miette::Result<()> {
                 node.set_display_name(&display_name).await?;
                 println!("Display name set to {:?} … ", display_name);
             }
+            NetCommands::SetBio { bio } => {
+                node.set_bio(&bio).await?;
+                match bio {
+                    Some(bio) => println!("Biography set to {:?} … ", bio),
+                    None => println!("Biography removed … "),
+                }
+            }
+            NetCommands::Users { search, limit } => {
+                let users = match search {
+                    Some(query) => OkuDatabase::search_users(&query, &Some(limit))?,
+                    None => DATABASE.get_users()?,
+                };
+                for user in users {
+                    println!("{}", util::user(&user));
+                }
+            }
             NetCommands::Follow { author_id } => {
                 node.follow(&author_id).await?;
                 println!("Now following {} … ", util::name(&node, &author_id).await);
@@ -657,10 +1100,21 @@ pub async fn main() -> miette::Result<()> {
                 body,
                 title,
                 tags,
+                content_warning,
+                sensitive,
+                visibility,
             } => {
                 let tags = tags.unwrap_or_default().into_par_iter().collect();
                 let (home_replica_id, post_path, _hash) = node
-                    .create_or_modify_post(&url, &title.unwrap_or_default(), &body, &tags)
+                    .create_or_modify_post(
+                        &url,
+                        &title.unwrap_or_default(),
+                        &body,
+                        &tags,
+                        &content_warning,
+                        sensitive,
+                        &visibility,
+                    )
                     .await?;
                 println!(
                     "{:#?}",
@@ -688,24 +1142,59 @@ pub async fn main() -> miette::Result<()> {
                     println!("{}", util::name(&node, &user).await);
                 }
             }
-            NetCommands::Timeline { author_id, tags } => {
-                let mut posts = match author_id {
-                    None => Vec::from_par_iter(node.all_posts().await),
-                    Some(id) => node
-                        .posts_from_user(&node.get_or_fetch_user(&id).await?)
-                        .await
-                        .unwrap_or_default(),
+            NetCommands::Followers { author_id, mutual } => {
+                let author_id = author_id.unwrap_or(node.default_author().await);
+                let list = match mutual {
+                    true => node.mutuals(&author_id).await?,
+                    false => node.followers_of(&author_id).await,
                 };
-                match tags {
-                    None => (),
-                    Some(tags) => {
-                        let tag_set: HashSet<_> = tags.into_par_iter().collect();
-                        posts = node.posts_with_tags(&posts, &tag_set).await;
-                    }
+                for user in list {
+                    println!("{}", util::name(&node, &user).await);
                 }
-                posts.par_sort_unstable_by_key(|x| Reverse(x.entry.timestamp()));
+            }
+            NetCommands::Timeline {
+                author_id,
+                tags,
+                before,
+                limit,
+            } => {
+                let posts = match (author_id, tags) {
+                    (None, None) => node.timeline(&before, limit).await?,
+                    (author_id, tags) => {
+                        let mut posts = match author_id {
+                            None => Vec::from_par_iter(node.all_posts().await),
+                            Some(id) => node
+                                .posts_from_user(&node.get_or_fetch_user(&id).await?)
+                                .await
+                                .unwrap_or_default(),
+                        };
+                        if let Some(tags) = tags {
+                            let tag_set: HashSet<_> = tags.into_par_iter().collect();
+                            posts = node.posts_with_tags(&posts, &tag_set).await;
+                        }
+                        if let Some(before) = &before {
+                            posts.retain(|x| x.cursor() < *before);
+                        }
+                        posts =
+                            DATABASE.filter_muted_posts(node.without_blocked_posts(posts).await);
+                        posts.par_sort_unstable_by_key(|x| Reverse(x.cursor()));
+                        posts.truncate(limit);
+                        posts
+                    }
+                };
+                let cursor = match posts.len() == limit {
+                    true => posts.last().map(|x| x.cursor()),
+                    false => None,
+                };
+                let content_warning_behaviour = load_config()?.get_content_warning_behaviour();
                 for post in posts {
-                    println!("⮞ {}", util::post(&post).await);
+                    if let Some(post) = util::timeline_post(&post, content_warning_behaviour).await
+                    {
+                        println!("⮞ {}", post);
+                    }
+                }
+                if let Some(cursor) = cursor {
+                    println!("Older posts can be shown with --before {} … ", cursor);
                 }
             }
             NetCommands::Block { author_id } => {
@@ -719,8 +1208,9 @@ pub async fn main() -> miette::Result<()> {
                     util::name(&node, &author_id).await
                 );
             }
-            NetCommands::Blocked { author_id } => {
+            NetCommands::Blocked { author_id, all } => {
                 let list = match author_id {
+                    None if all => node.blocked_users().await,
                     None => node.identity().await.map(|x| x.blocked).unwrap_or_default(),
                     Some(id) => node
                         .get_or_fetch_user(&id)
@@ -733,30 +1223,342 @@ pub async fn main() -> miette::Result<()> {
                     println!("{}", util::name(&node, &user).await);
                 }
             }
+            NetCommands::SubscribeBlocklist { author_id } => {
+                node.subscribe_to_blocklist(&author_id).await?;
+                println!(
+                    "Now applying the blocked list of {} … ",
+                    util::name(&node, &author_id).await
+                );
+            }
+            NetCommands::UnsubscribeBlocklist { author_id } => {
+                node.unsubscribe_from_blocklist(&author_id).await?;
+                println!(
+                    "No longer applying the blocked list of {} … ",
+                    util::name(&node, &author_id).await
+                );
+            }
+            NetCommands::Allow { author_id } => {
+                node.allow(&author_id).await?;
+                println!(
+                    "{} is now exempt from subscribed blocked lists … ",
+                    util::name(&node, &author_id).await
+                );
+            }
+            NetCommands::Disallow { author_id } => {
+                node.disallow(&author_id).await?;
+                println!(
+                    "{} is no longer exempt from subscribed blocked lists … ",
+                    util::name(&node, &author_id).await
+                );
+            }
             NetCommands::View {
                 author_id,
                 post_path,
+                history,
             } => {
                 let post = match author_id {
                     None => node.post(&post_path).await?,
                     Some(id) => node.get_or_fetch_post(&id, &post_path).await?,
                 };
-                println!("{}", util::post(&post).await)
+                println!("{}", util::post(&post).await);
+                if history {
+                    for revision in node.post_history(&post).await? {
+                        println!("↺ {}", util::post(&revision).await);
+                    }
+                }
             }
             NetCommands::Search {
                 query,
                 result_limit,
+                author,
+                tag,
+                domain,
+                after,
+                before,
+                page,
+                newest,
+                facets,
             } => {
-                let posts =
-                    OkuDatabase::search_posts(&query, &Some(result_limit)).unwrap_or_default();
+                let content_warning_behaviour = load_config()?.get_content_warning_behaviour();
+                let search = OkuPostSearch {
+                    query,
+                    authors: author.into_iter().collect(),
+                    tags: tag.into_iter().collect(),
+                    domain,
+                    after,
+                    before,
+                    mutes: Vec::new(),
+                    hide_flagged: content_warning_behaviour == ContentWarningBehaviour::Hide,
+                    newest_first: newest,
+                    offset: page.saturating_sub(1) * result_limit,
+                    limit: result_limit,
+                };
+                let results = node.search_posts(&search).await?;
+                for result in results.results.iter() {
+                    if let Some(post) =
+                        util::timeline_post(&result.post, content_warning_behaviour).await
+                    {
+                        println!("⮞ {}", post);
+                        if !result.post.note.is_flagged() && !result.highlights.is_empty() {
+                            println!("Matched: {}", util::snippet(result));
+                        }
+                    }
+                }
+                let shown = search.offset + results.results.len();
+                println!(
+                    "Showing results {}–{} of {}.",
+                    (search.offset + 1).min(shown),
+                    shown,
+                    results.total
+                );
+                if shown < results.total {
+                    println!("For more results, use `--page {}`.", page.max(1) + 1);
+                }
+                if facets {
+                    println!("Tags:");
+                    for (tag, count) in results.tag_counts {
+                        println!("  {tag}: {count}");
+                    }
+                    println!("Authors:");
+                    for (author_id, count) in results.author_counts {
+                        println!(
+                            "  {} ({}): {count}",
+                            util::name(&node, &author_id).await,
+                            oku_core::fs::util::fmt(author_id)
+                        );
+                    }
+                }
+            }
+            NetCommands::About { url } => {
+                let posts = node.posts_about(&url).await?;
+                let content_warning_behaviour = load_config()?.get_content_warning_behaviour();
+                for post in posts {
+                    if let Some(post) = util::timeline_post(&post, content_warning_behaviour).await
+                    {
+                        println!("⮞ {}", post);
+                    }
+                }
+            }
+            NetCommands::Similar {
+                url_or_text,
+                result_limit,
+            } => {
+                let posts = node.similar_posts(&url_or_text, result_limit).await?;
+                let content_warning_behaviour = load_config()?.get_content_warning_behaviour();
                 for post in posts {
-                    println!("⮞ {}", util::post(&post).await);
+                    if let Some(post) = util::timeline_post(&post, content_warning_behaviour).await
+                    {
+                        println!("⮞ {}", post);
+                    }
+                }
+            }
+            NetCommands::Tags {
+                count,
+                since: Some(since),
+                limit,
+            } => {
+                let statistics = node.activity_statistics(&Some(since)).await?;
+                println!("{} posts written.", statistics.posts);
+                println!("Tags:");
+                for (tag, tag_count) in statistics.tags.into_iter().take(limit) {
+                    match count {
+                        true => println!("  #{tag}: {tag_count}"),
+                        false => println!("  #{tag}"),
+                    }
+                }
+                println!("URLs:");
+                for (url, url_count) in statistics.urls.into_iter().take(limit) {
+                    println!("  {url}: {url_count}");
+                }
+                println!("Authors:");
+                for (author_id, author_count) in statistics.authors.into_iter().take(limit) {
+                    println!(
+                        "  {} ({}): {author_count}",
+                        util::name(&node, &author_id).await,
+                        oku_core::fs::util::fmt(author_id)
+                    );
                 }
             }
-            NetCommands::Tags { count } => match count {
+            NetCommands::Tags {
+                count, since: None, ..
+            } => match count {
                 false => println!("{:?}", node.all_tags(&node.all_posts().await).await),
                 true => println!("{:#?}", node.count_tags(&node.all_posts().await).await),
             },
+            NetCommands::Feed {
+                author,
+                tag,
+                query,
+                format,
+                limit,
+            } => {
+                let source = match (author, tag, query) {
+                    (Some(author_id), _, _) => OkuFeedSource::User(author_id),
+                    (None, Some(tag), _) => OkuFeedSource::Tag(tag),
+                    (None, None, Some(query)) => OkuFeedSource::Search(OkuPostSearch {
+                        query,
+                        ..Default::default()
+                    }),
+                    (None, None, None) => OkuFeedSource::User(node.default_author().await),
+                };
+                let feed = node.syndicate(&source, limit).await?;
+                print!("{}", feed.render(format)?);
+            }
+            NetCommands::Import { source } => {
+                let (feed, item_count) = node.import_external_feed(&source).await?;
+                println!("Imported {} items from {} … ", item_count, feed.title);
+            }
+            NetCommands::Unimport { source } => match node.remove_external_feed(&source).await? {
+                Some(feed) => println!("{} is no longer imported … ", feed.title),
+                None => println!("{} was not imported … ", source),
+            },
+            NetCommands::Imports => {
+                for feed in node.external_feeds().await {
+                    println!("⮞ {} ({})", feed.title, feed.source);
+                }
+            }
+            NetCommands::Refresh { force } => {
+                let report = match force {
+                    true => node.fetch_users().await?,
+                    false => node.refresh_users().await?,
+                };
+                println!("Recorded {} users … ", report.users.len());
+                for (author_id, e) in report.failures {
+                    println!(
+                        "Could not retrieve {}: {}",
+                        oku_core::fs::util::fmt(author_id),
+                        e
+                    );
+                }
+            }
+            NetCommands::Mute { rule, duration } => {
+                node.mute(&rule, &duration).await?;
+                println!("Muted posts matching {} … ", rule);
+            }
+            NetCommands::Unmute { rule } => match node.unmute(&rule).await? {
+                Some(_) => println!("Posts matching {} are no longer muted … ", rule),
+                None => println!("Posts matching {} were not muted … ", rule),
+            },
+            NetCommands::Mutes => {
+                for mute in node.mutes().await {
+                    println!("⮞ {}", util::mute(&mute));
+                }
+            }
+            NetCommands::Draft { draft_commands } => match draft_commands {
+                DraftCommands::Save {
+                    url,
+                    title,
+                    tags,
+                    content_warning,
+                    sensitive,
+                    visibility,
+                    body,
+                } => {
+                    let tags = tags.unwrap_or_default().into_par_iter().collect();
+                    node.save_draft(
+                        &url,
+                        &title.unwrap_or_default(),
+                        &body,
+                        &tags,
+                        &content_warning,
+                        sensitive,
+                        &visibility,
+                    )
+                    .await?;
+                    if let Some(draft) = node.draft(&url).await {
+                        println!("{}", util::draft(&draft));
+                    }
+                }
+                DraftCommands::List => {
+                    for draft in node.drafts().await {
+                        println!("⮞ {}", util::draft(&draft));
+                    }
+                }
+                DraftCommands::Publish { url } => {
+                    let (home_replica_id, post_path, _hash) = node.publish_draft(&url).await?;
+                    println!(
+                        "{:#?}",
+                        node.read_file(&home_replica_id, &post_path, &None, &None)
+                            .await
+                            .ok()
+                            .map(|x| String::from_utf8_lossy(&x).to_string())
+                    );
+                }
+                DraftCommands::Discard { url } => {
+                    let draft = node.discard_draft(&url).await?;
+                    println!("Discarded draft regarding {} … ", draft.note.url);
+                }
+            },
+            NetCommands::Dm { dm_commands } => match dm_commands {
+                DmCommands::Send { author_id, body } => {
+                    let message = node.send_message(&author_id, &body).await?;
+                    println!("{}", util::message(&node, &message).await);
+                }
+                DmCommands::List { author_id } => match author_id {
+                    Some(author_id) => {
+                        let ticket = node
+                            .resolve_author_id(&author_id)
+                            .await
+                            .map_err(|e| miette!("{}", e))?;
+                        node.fetch_messages(&ticket).await?;
+                        for message in node.conversation(&author_id).await? {
+                            println!("⮞ {}", util::message(&node, &message).await);
+                        }
+                    }
+                    None => {
+                        let me = node.default_author().await;
+                        for message in node.conversations().await? {
+                            println!(
+                                "⮞ {} ({}):\n{}",
+                                util::name(&node, &message.correspondent(&me)).await,
+                                oku_core::fs::util::fmt(message.correspondent(&me)),
+                                util::message(&node, &message).await
+                            );
+                        }
+                    }
+                },
+            },
+            NetCommands::Circle { circle_commands } => match circle_commands {
+                CircleCommands::Add { name, author_ids } => {
+                    let circle = node
+                        .add_to_circle(&name, &author_ids.into_iter().collect())
+                        .await?;
+                    println!("{}", util::circle(&node, &circle).await);
+                }
+                CircleCommands::Remove { name, author_ids } => {
+                    match node
+                        .remove_from_circle(&name, &author_ids.into_iter().collect())
+                        .await?
+                    {
+                        Some(circle) => println!("{}", util::circle(&node, &circle).await),
+                        None => println!("No circle named {} … ", name),
+                    }
+                }
+                CircleCommands::Delete { name } => match node.delete_circle(&name).await? {
+                    Some(circle) => println!("Deleted circle {} … ", circle.name),
+                    None => println!("No circle named {} … ", name),
+                },
+                CircleCommands::List => {
+                    for circle in node.circles().await {
+                        println!("⮞ {}", util::circle(&node, &circle).await);
+                    }
+                }
+            },
+            NetCommands::Contact { contact_commands } => match contact_commands {
+                ContactCommands::Petname { author_id, petname } => {
+                    let contact = node.set_petname(&author_id, &petname).await?;
+                    println!("{}", util::contact(&contact));
+                }
+                ContactCommands::Notes { author_id, notes } => {
+                    let contact = node.set_contact_notes(&author_id, &notes).await?;
+                    println!("{}", util::contact(&contact));
+                }
+                ContactCommands::List => {
+                    for contact in node.contacts().await {
+                        println!("⮞ {}", util::contact(&contact));
+                    }
+                }
+            },
         },
         None => {
             let default_author_id = node.default_author().await;
@@ -5,7 +5,17 @@ use jiff::{
     Timestamp,
 };
 use oku_core::{
-    database::{posts::core::OkuPost, users::OkuUser},
+    config::ContentWarningBehaviour,
+    database::{
+        circles::OkuCircle,
+        contacts::OkuContact,
+        core::DATABASE,
+        drafts::OkuDraft,
+        messages::OkuMessage,
+        mutes::OkuMute,
+        posts::{core::OkuPost, search::OkuPostSearchResult},
+        users::OkuUser,
+    },
     fs::OkuFs,
 };
 use rayon::slice::ParallelSliceMut;
@@ -13,6 +23,10 @@ use std::cmp::Reverse;
 
 pub async fn print_profile(node: &OkuFs, profile: &OkuUser) -> miette::Result<()> {
     let display_name = &profile.identity.clone().map(|x| x.name);
+    let bio = &profile.identity.clone().and_then(|x| x.bio);
+    let contact = node.contact(&profile.author_id).await;
+    let petname = &contact.clone().and_then(|x| x.petname);
+    let notes = &contact.and_then(|x| x.notes);
     let following = &profile
         .identity
         .clone()
@@ -23,21 +37,31 @@ pub async fn print_profile(node: &OkuFs, profile: &OkuUser) -> miette::Result<()
         .clone()
         .map(|x| x.blocked)
         .unwrap_or_default();
+    let followers = node.followers_of(&profile.author_id).await;
     let mut following_names = Vec::new();
+    let mut follower_names = Vec::new();
     let mut blocked_names = Vec::new();
 
     for author_id in following {
         following_names.push(name(node, author_id).await);
     }
+    for author_id in followers.iter() {
+        follower_names.push(name(node, author_id).await);
+    }
     for author_id in blocked {
         blocked_names.push(name(node, author_id).await);
     }
 
     println!(
-        "Author ID: {}\nDisplay name: {:?}\nFollowing: {:?}\nBlocked: {:?}\n",
+        "Author ID: {}\nDisplay name: {:?}\nPetname: {:?}\nNotes: {:?}\nBiography: {:?}\nFollowing: {:?}\nFollowed by: {:?}\nFollows you: {}\nBlocked: {:?}\n",
         oku_core::fs::util::fmt(profile.author_id),
         display_name,
+        petname,
+        notes,
+        bio,
         following_names,
+        follower_names,
+        node.follows_me(&profile.author_id).await,
         blocked_names
     );
 
@@ -50,21 +74,27 @@ pub async fn print_profile(node: &OkuFs, profile: &OkuUser) -> miette::Result<()
 }
 
 pub async fn name(node: &OkuFs, author_id: &AuthorId) -> String {
-    let identity_name = node
-        .get_or_fetch_user(author_id)
-        .await
-        .ok()
-        .and_then(|user| user.identity.map(|identity| identity.name));
-    match identity_name {
-        Some(name) => name,
-        None => oku_core::fs::util::fmt(author_id),
+    match node.get_or_fetch_user(author_id).await {
+        Ok(user) => user_name(&user),
+        Err(_) => DATABASE
+            .get_petname(author_id)
+            .unwrap_or(oku_core::fs::util::fmt(author_id)),
     }
 }
 
 pub fn user_name(user: &OkuUser) -> String {
-    match &user.identity {
-        Some(identity) => identity.name.to_owned(),
-        None => oku_core::fs::util::fmt(user.author_id),
+    let name = DATABASE.display_name(user);
+    match DATABASE.impersonated_contact(user).and_then(|x| x.petname) {
+        Some(petname) => format!("{name} (⚠ not your contact '{petname}')"),
+        None => name,
+    }
+}
+
+pub fn user(user: &OkuUser) -> String {
+    let author_id = oku_core::fs::util::fmt(user.author_id);
+    match user.identity.as_ref().and_then(|x| x.bio.as_ref()) {
+        Some(bio) => format!("{} ({}): {}", user_name(user), author_id, bio),
+        None => format!("{} ({})", user_name(user), author_id),
     }
 }
 
@@ -99,14 +129,139 @@ pub async fn post(post: &OkuPost) -> String {
         .spacing(Spacing::BetweenUnitsAndDesignators)
         .comma_after_designator(true)
         .designator(Designator::Verbose);
+    let edited = match DATABASE.is_post_edited(post) {
+        true => ", edited",
+        false => "",
+    };
+    let visibility = match post.note.is_restricted() {
+        true => format!(", visible to {}", post.note.visibility),
+        false => String::new(),
+    };
+    let content_warning = post
+        .note
+        .warning()
+        .map(|x| format!("CW: {x}\n"))
+        .unwrap_or_default();
     format!(
-        "'{}' ({}) by {} (posted {}, at {}):\n{}\nTags: {:?}",
+        "'{}' ({}) by {} (posted {}, at {}{}{}):\n{}{}\nTags: {:?}",
         post.note.title,
         post.note.url,
         user_name(&user),
         timestamp_printer.span_to_string(&span),
         timestamp_string,
+        edited,
+        visibility,
+        content_warning,
         post.note.body,
         post.note.tags
     )
 }
+
+pub fn snippet(result: &OkuPostSearchResult) -> String {
+    let mut snippet = String::new();
+    let mut end = 0;
+    for highlight in result.highlights.iter() {
+        snippet.push_str(&result.snippet[end..highlight.start]);
+        snippet.push_str(&format!(
+            "\x1b[1m{}\x1b[0m",
+            &result.snippet[highlight.clone()]
+        ));
+        end = highlight.end;
+    }
+    snippet.push_str(&result.snippet[end..]);
+    snippet
+}
+
+pub async fn timeline_post(
+    post_entry: &OkuPost,
+    content_warning_behaviour: ContentWarningBehaviour,
+) -> Option<String> {
+    match (post_entry.note.is_flagged(), content_warning_behaviour) {
+        (false, _) | (true, ContentWarningBehaviour::Expand) => Some(post(post_entry).await),
+        (true, ContentWarningBehaviour::Hide) => None,
+        (true, ContentWarningBehaviour::Collapse) => {
+            let mut collapsed_post = post_entry.clone();
+            collapsed_post.note.body = format!(
+                "[Hidden; view with `oku-core net view -a {} -p {}`]",
+                oku_core::fs::util::fmt(post_entry.entry.author()),
+                oku_core::fs::util::entry_key_to_path(post_entry.entry.key())
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            );
+            Some(post(&collapsed_post).await)
+        }
+    }
+}
+
+pub fn draft(draft: &OkuDraft) -> String {
+    let timestamp = Timestamp::try_from(draft.last_modified).unwrap_or(Timestamp::UNIX_EPOCH);
+    let timestamp_string = jiff::fmt::rfc2822::DateTimePrinter::new()
+        .timestamp_to_string(&timestamp)
+        .unwrap_or(format!("{timestamp:.0}"));
+    format!(
+        "'{}' ({}) (saved at {}, visible to {}):\n{}\nTags: {:?}",
+        draft.note.title,
+        draft.note.url,
+        timestamp_string,
+        draft.note.visibility,
+        draft.note.body,
+        draft.note.tags
+    )
+}
+
+pub fn mute(mute: &OkuMute) -> String {
+    match mute.expiry {
+        Some(expiry) => {
+            let timestamp = Timestamp::try_from(expiry).unwrap_or(Timestamp::UNIX_EPOCH);
+            let timestamp_string = jiff::fmt::rfc2822::DateTimePrinter::new()
+                .timestamp_to_string(&timestamp)
+                .unwrap_or(format!("{timestamp:.0}"));
+            format!("{} (until {})", mute.rule, timestamp_string)
+        }
+        None => format!("{} (indefinitely)", mute.rule),
+    }
+}
+
+pub async fn message(node: &OkuFs, message: &OkuMessage) -> String {
+    let timestamp_microseconds = message.entry.timestamp();
+    let timestamp = Timestamp::from_microsecond(
+        timestamp_microseconds
+            .try_into()
+            .unwrap_or(timestamp_microseconds as i64),
+    )
+    .unwrap_or(Timestamp::UNIX_EPOCH);
+    let timestamp_string = jiff::fmt::rfc2822::DateTimePrinter::new()
+        .timestamp_to_string(&timestamp)
+        .unwrap_or(format!("{timestamp:.0}"));
+    format!(
+        "{} (sent at {}): {}",
+        name(node, &message.sender()).await,
+        timestamp_string,
+        message.body
+    )
+}
+
+pub fn contact(contact: &OkuContact) -> String {
+    let author_id = oku_core::fs::util::fmt(contact.author_id);
+    let name = match &contact.petname {
+        Some(petname) => format!("{} ({})", petname, author_id),
+        None => author_id,
+    };
+    match &contact.notes {
+        Some(notes) => format!("{}: {}", name, notes),
+        None => name,
+    }
+}
+
+pub async fn circle(node: &OkuFs, circle: &OkuCircle) -> String {
+    let mut member_names = Vec::new();
+    for member in circle.members.iter() {
+        member_names.push(format!(
+            "{} ({})",
+            name(node, member).await,
+            oku_core::fs::util::fmt(member)
+        ));
+    }
+    member_names.par_sort_unstable();
+    format!("{}: {:#?}", circle.name, member_names)
+}
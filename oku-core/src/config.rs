@@ -1,4 +1,5 @@
 use crate::discovery::{DEFAULT_INITIAL_PUBLISH_DELAY, DEFAULT_REPUBLISH_DELAY};
+use crate::fs::net::core::{DEFAULT_CRAWL_DEPTH, DEFAULT_CRAWL_PARALLELISM};
 #[cfg(feature = "persistent")]
 use crate::fs::FS_PATH;
 #[cfg(feature = "persistent")]
@@ -18,6 +19,18 @@ use std::{
 pub(crate) static CONFIG_PATH: LazyLock<PathBuf> =
     LazyLock::new(|| PathBuf::from(FS_PATH).join("config.toml"));
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// How OkuNet posts with a content warning, or marked as sensitive, are presented.
+pub enum ContentWarningBehaviour {
+    #[default]
+    /// Flagged posts are shown with their bodies collapsed.
+    Collapse,
+    /// Flagged posts are shown in full.
+    Expand,
+    /// Flagged posts are not shown.
+    Hide,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 /// Configuration of an Oku file system node.
 pub struct OkuFsConfig {
@@ -25,6 +38,21 @@ pub struct OkuFsConfig {
     republish_delay: Arc<Mutex<Option<Duration>>>,
     /// The initial delay before publishing content to the Mainline DHT (defaults to [`crate::discovery::DEFAULT_INITIAL_PUBLISH_DELAY`]).
     initial_publish_delay: Arc<Mutex<Option<Duration>>>,
+    #[serde(default)]
+    /// How posts with content warnings are presented (defaults to [`ContentWarningBehaviour::Collapse`]).
+    content_warning_behaviour: Arc<Mutex<Option<ContentWarningBehaviour>>>,
+    #[serde(default)]
+    /// How many levels of the social graph to crawl when refreshing OkuNet users (defaults to [`crate::fs::net::core::DEFAULT_CRAWL_DEPTH`]).
+    crawl_depth: Arc<Mutex<Option<usize>>>,
+    #[serde(default)]
+    /// The maximum number of users to crawl from each user's followed list, per level of the social graph (unlimited by default).
+    crawl_fan_out: Arc<Mutex<Option<Vec<usize>>>>,
+    #[serde(default)]
+    /// The maximum number of users to crawl in total (unlimited by default).
+    crawl_user_cap: Arc<Mutex<Option<usize>>>,
+    #[serde(default)]
+    /// The maximum number of users to fetch at once (defaults to [`crate::fs::net::core::DEFAULT_CRAWL_PARALLELISM`]).
+    crawl_parallelism: Arc<Mutex<Option<usize>>>,
 }
 
 impl Default for OkuFsConfig {
@@ -32,6 +60,11 @@ impl Default for OkuFsConfig {
         Self {
             republish_delay: Arc::new(Mutex::new(None)),
             initial_publish_delay: Arc::new(Mutex::new(None)),
+            content_warning_behaviour: Arc::new(Mutex::new(None)),
+            crawl_depth: Arc::new(Mutex::new(None)),
+            crawl_fan_out: Arc::new(Mutex::new(None)),
+            crawl_user_cap: Arc::new(Mutex::new(None)),
+            crawl_parallelism: Arc::new(Mutex::new(None)),
         }
     }
 }
@@ -126,4 +159,147 @@ impl OkuFsConfig {
             .map_err(|e| miette!("{}", e))? = *initial_publish_delay;
         Ok(())
     }
+
+    /// Gets [`OkuFsConfig::content_warning_behaviour`].
+    ///
+    /// # Returns
+    ///
+    /// [`OkuFsConfig::content_warning_behaviour`] if set, or [`ContentWarningBehaviour::Collapse`] otherwise.
+    pub fn get_content_warning_behaviour(&self) -> ContentWarningBehaviour {
+        self.content_warning_behaviour
+            .try_lock()
+            .ok()
+            .and_then(|x| x.to_owned())
+            .unwrap_or_default()
+    }
+
+    /// Sets [`OkuFsConfig::content_warning_behaviour`].
+    ///
+    /// # Arguments
+    ///
+    /// * `content_warning_behaviour` - An optional behaviour for posts with content warnings; if unspecified, the default will be used.
+    pub fn set_content_warning_behaviour(
+        &self,
+        content_warning_behaviour: &Option<ContentWarningBehaviour>,
+    ) -> miette::Result<()> {
+        *self
+            .content_warning_behaviour
+            .try_lock()
+            .map_err(|e| miette!("{}", e))? = *content_warning_behaviour;
+        Ok(())
+    }
+
+    /// Gets [`OkuFsConfig::crawl_depth`].
+    ///
+    /// # Returns
+    ///
+    /// [`OkuFsConfig::crawl_depth`] if set, or [`crate::fs::net::core::DEFAULT_CRAWL_DEPTH`] otherwise.
+    pub fn get_crawl_depth(&self) -> usize {
+        self.crawl_depth
+            .try_lock()
+            .ok()
+            .and_then(|x| x.to_owned())
+            .unwrap_or(DEFAULT_CRAWL_DEPTH)
+    }
+
+    /// Sets [`OkuFsConfig::crawl_depth`].
+    ///
+    /// # Arguments
+    ///
+    /// * `crawl_depth` - An optional number of social graph levels to crawl; if unspecified, the default will be used.
+    pub fn set_crawl_depth(&self, crawl_depth: &Option<usize>) -> miette::Result<()> {
+        *self.crawl_depth.try_lock().map_err(|e| miette!("{}", e))? = *crawl_depth;
+        Ok(())
+    }
+
+    /// Gets [`OkuFsConfig::crawl_fan_out`].
+    ///
+    /// # Returns
+    ///
+    /// [`OkuFsConfig::crawl_fan_out`] if set, or an empty list (imposing no limits) otherwise.
+    pub fn get_crawl_fan_out(&self) -> Vec<usize> {
+        self.crawl_fan_out
+            .try_lock()
+            .ok()
+            .and_then(|x| x.to_owned())
+            .unwrap_or_default()
+    }
+
+    /// Gets the maximum number of users to crawl from each user's followed list at a level of the social graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - A level of the social graph, where `0` is the local user's followed list.
+    ///
+    /// # Returns
+    ///
+    /// The fan-out limit at the given level, if there is one.
+    pub fn get_crawl_fan_out_at(&self, level: usize) -> Option<usize> {
+        self.get_crawl_fan_out().get(level).copied()
+    }
+
+    /// Sets [`OkuFsConfig::crawl_fan_out`].
+    ///
+    /// # Arguments
+    ///
+    /// * `crawl_fan_out` - An optional list of fan-out limits, one per level of the social graph; levels without a limit are unlimited.
+    pub fn set_crawl_fan_out(&self, crawl_fan_out: &Option<Vec<usize>>) -> miette::Result<()> {
+        *self
+            .crawl_fan_out
+            .try_lock()
+            .map_err(|e| miette!("{}", e))? = crawl_fan_out.clone();
+        Ok(())
+    }
+
+    /// Gets [`OkuFsConfig::crawl_user_cap`].
+    ///
+    /// # Returns
+    ///
+    /// [`OkuFsConfig::crawl_user_cap`] if set, or no limit otherwise.
+    pub fn get_crawl_user_cap(&self) -> Option<usize> {
+        self.crawl_user_cap
+            .try_lock()
+            .ok()
+            .and_then(|x| x.to_owned())
+    }
+
+    /// Sets [`OkuFsConfig::crawl_user_cap`].
+    ///
+    /// # Arguments
+    ///
+    /// * `crawl_user_cap` - An optional maximum number of users to crawl; if unspecified, there will be no limit.
+    pub fn set_crawl_user_cap(&self, crawl_user_cap: &Option<usize>) -> miette::Result<()> {
+        *self
+            .crawl_user_cap
+            .try_lock()
+            .map_err(|e| miette!("{}", e))? = *crawl_user_cap;
+        Ok(())
+    }
+
+    /// Gets [`OkuFsConfig::crawl_parallelism`].
+    ///
+    /// # Returns
+    ///
+    /// [`OkuFsConfig::crawl_parallelism`] if set, or [`crate::fs::net::core::DEFAULT_CRAWL_PARALLELISM`] otherwise.
+    pub fn get_crawl_parallelism(&self) -> usize {
+        self.crawl_parallelism
+            .try_lock()
+            .ok()
+            .and_then(|x| x.to_owned())
+            .unwrap_or(DEFAULT_CRAWL_PARALLELISM)
+            .max(1)
+    }
+
+    /// Sets [`OkuFsConfig::crawl_parallelism`].
+    ///
+    /// # Arguments
+    ///
+    /// * `crawl_parallelism` - An optional maximum number of users to fetch at once; if unspecified, the default will be used.
+    pub fn set_crawl_parallelism(&self, crawl_parallelism: &Option<usize>) -> miette::Result<()> {
+        *self
+            .crawl_parallelism
+            .try_lock()
+            .map_err(|e| miette!("{}", e))? = *crawl_parallelism;
+        Ok(())
+    }
 }
@@ -0,0 +1,94 @@
+use super::core::*;
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 8, version = 1)]
+#[native_db(
+    primary_key(primary_key -> String)
+)]
+/// A named group of OkuNet users, kept only on this node, able to be made the only readers of a post.
+pub struct OkuCircle {
+    /// The name of the circle.
+    pub name: String,
+    /// The content authorship IDs of the users in the circle.
+    pub members: HashSet<AuthorId>,
+}
+
+impl OkuCircle {
+    pub(crate) fn primary_key(&self) -> String {
+        self.name.to_owned()
+    }
+}
+
+impl OkuDatabase {
+    /// Insert or update a circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `circle` - A circle to upsert.
+    ///
+    /// # Returns
+    ///
+    /// The previous version of the circle, if one existed.
+    pub fn upsert_circle(&self, circle: &OkuCircle) -> miette::Result<Option<OkuCircle>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<OkuCircle> = rw.upsert(circle.to_owned()).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        Ok(old_value)
+    }
+
+    /// Delete a circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the circle to delete.
+    ///
+    /// # Returns
+    ///
+    /// The deleted circle, if one existed.
+    pub fn delete_circle(&self, name: &String) -> miette::Result<Option<OkuCircle>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let circle: Option<OkuCircle> = rw.get().primary(name.to_owned()).into_diagnostic()?;
+        let removed_circle = match circle {
+            Some(circle) => Some(rw.remove(circle).into_diagnostic()?),
+            None => None,
+        };
+        rw.commit().into_diagnostic()?;
+        Ok(removed_circle)
+    }
+
+    /// Retrieves all circles saved on this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of all circles saved on this node.
+    pub fn get_circles(&self) -> miette::Result<Vec<OkuCircle>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves a circle by its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the circle.
+    ///
+    /// # Returns
+    ///
+    /// The circle with the given name, if one exists.
+    pub fn get_circle(&self, name: &String) -> miette::Result<Option<OkuCircle>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.get().primary(name.to_owned()).into_diagnostic()
+    }
+}
@@ -0,0 +1,169 @@
+use super::core::*;
+use super::users::OkuUser;
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 9, version = 1)]
+#[native_db(
+    primary_key(primary_key -> Vec<u8>),
+    secondary_key(petname_key -> String)
+)]
+/// What the local user knows about an OkuNet user, kept only on this node.
+pub struct OkuContact {
+    /// The content authorship ID of the user.
+    pub author_id: AuthorId,
+    /// A private nickname for the user, shown in place of their self-chosen display name.
+    pub petname: Option<String>,
+    /// Free-form notes about the user.
+    pub notes: Option<String>,
+}
+
+impl OkuContact {
+    pub(crate) fn primary_key(&self) -> Vec<u8> {
+        self.author_id.as_bytes().to_vec()
+    }
+
+    /// The petname as compared when looking for impersonation, or an empty string if there is none.
+    pub(crate) fn petname_key(&self) -> String {
+        self.petname
+            .as_ref()
+            .map(|x| x.trim().to_lowercase())
+            .unwrap_or_default()
+    }
+}
+
+impl OkuDatabase {
+    /// Insert or update a contact.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact` - A contact to upsert.
+    ///
+    /// # Returns
+    ///
+    /// The previous version of the contact, if one existed.
+    pub fn upsert_contact(&self, contact: &OkuContact) -> miette::Result<Option<OkuContact>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<OkuContact> = rw.upsert(contact.to_owned()).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        Ok(old_value)
+    }
+
+    /// Delete a contact.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the contact to delete.
+    ///
+    /// # Returns
+    ///
+    /// The deleted contact, if one existed.
+    pub fn delete_contact(&self, author_id: &AuthorId) -> miette::Result<Option<OkuContact>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let contact: Option<OkuContact> = rw
+            .get()
+            .primary(author_id.as_bytes().to_vec())
+            .into_diagnostic()?;
+        let removed_contact = match contact {
+            Some(contact) => Some(rw.remove(contact).into_diagnostic()?),
+            None => None,
+        };
+        rw.commit().into_diagnostic()?;
+        Ok(removed_contact)
+    }
+
+    /// Retrieves all contacts saved on this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of all contacts saved on this node.
+    pub fn get_contacts(&self) -> miette::Result<Vec<OkuContact>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves a contact by its content authorship ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - A content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The contact with the given content authorship ID, if one exists.
+    pub fn get_contact(&self, author_id: &AuthorId) -> miette::Result<Option<OkuContact>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.get()
+            .primary(author_id.as_bytes().to_vec())
+            .into_diagnostic()
+    }
+
+    /// Retrieves the petname given to a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - A content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The user's petname, if they have been given one.
+    pub fn get_petname(&self, author_id: &AuthorId) -> Option<String> {
+        self.get_contact(author_id)
+            .ok()
+            .flatten()
+            .and_then(|x| x.petname)
+    }
+
+    /// The name a user should be shown with.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - An OkuNet user.
+    ///
+    /// # Returns
+    ///
+    /// The user's petname if they have one, otherwise their self-chosen display name, otherwise their content authorship ID.
+    pub fn display_name(&self, user: &OkuUser) -> String {
+        self.get_petname(&user.author_id)
+            .or(user.identity.as_ref().map(|x| x.name.to_owned()))
+            .unwrap_or(crate::fs::util::fmt(user.author_id))
+    }
+
+    /// Determine whether a user's self-chosen display name is a petname given to another user.
+    ///
+    /// Users who have been given a petname are shown by it, and so are never considered to be impersonating anyone.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - An OkuNet user.
+    ///
+    /// # Returns
+    ///
+    /// The contact whose petname the user's display name collides with, if any.
+    pub fn impersonated_contact(&self, user: &OkuUser) -> Option<OkuContact> {
+        let name = user.identity.as_ref()?.name.trim().to_lowercase();
+        if name.is_empty() || self.get_petname(&user.author_id).is_some() {
+            return None;
+        }
+        let r = self.database.r_transaction().ok()?;
+        let contacts: Vec<OkuContact> = r
+            .scan()
+            .secondary(OkuContactKey::petname_key)
+            .ok()?
+            .range(name.clone()..=name)
+            .ok()?
+            .filter_map(|x| x.ok())
+            .collect();
+        contacts.into_iter().find(|x| x.author_id != user.author_id)
+    }
+}
@@ -1,30 +1,82 @@
+use super::circles::OkuCircle;
+use super::contacts::OkuContact;
 use super::dht::*;
+use super::drafts::OkuDraft;
+#[cfg(feature = "persistent")]
+use super::external_feeds::EXTERNAL_POST_INDEX_READER;
+use super::external_feeds::{OkuExternalFeed, OkuExternalPost};
+use super::messages::OkuMessage;
+use super::migrations::*;
+use super::mutes::OkuMute;
 use super::posts::core::OkuPost;
+#[cfg(feature = "persistent")]
+use super::posts::core::POST_INDEX_READER;
+use super::posts::embeddings::OkuPostEmbedding;
+#[cfg(feature = "persistent")]
+use super::posts::embeddings::POST_VECTOR_INDEX_READER;
+use super::posts::history::OkuPostRevision;
+use super::posts::statistics::OkuActivityDay;
 use super::users::*;
 #[cfg(feature = "persistent")]
 use crate::fs::FS_PATH;
+use dashmap::DashMap;
+use iroh_docs::AuthorId;
 use miette::IntoDiagnostic;
 use native_db::*;
+use std::collections::HashSet;
 #[cfg(feature = "persistent")]
 use std::path::PathBuf;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
+use tantivy::IndexWriter;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 #[cfg(feature = "persistent")]
 pub(crate) static DATABASE_PATH: LazyLock<PathBuf> =
     LazyLock::new(|| PathBuf::from(FS_PATH).join("OKU_FS_DATABASE"));
 /// An Oku node's database.
 pub static DATABASE: LazyLock<OkuDatabase> = LazyLock::new(|| OkuDatabase::new().unwrap());
+
+/// Waits for exclusive use of a search index's writer.
+///
+/// Indices are written to after the database has committed, so a write must not be abandoned merely because another write to the same index is underway.
+///
+/// # Arguments
+///
+/// * `index_writer` - The writer of a search index.
+///
+/// # Returns
+///
+/// The index writer, held until the guard is dropped.
+pub(crate) fn lock_index_writer(
+    index_writer: &Arc<Mutex<IndexWriter>>,
+) -> OwnedMutexGuard<IndexWriter> {
+    futures::executor::block_on(index_writer.clone().lock_owned())
+}
 pub(crate) static MODELS: LazyLock<Models> = LazyLock::new(|| {
     let mut models = Models::new();
+    models.define::<OkuUserV1>().unwrap();
     models.define::<OkuUser>().unwrap();
+    models.define::<OkuPostV2>().unwrap();
     models.define::<OkuPost>().unwrap();
     models.define::<ReplicaAnnouncement>().unwrap();
+    models.define::<OkuPostRevision>().unwrap();
+    models.define::<OkuDraft>().unwrap();
+    models.define::<OkuMute>().unwrap();
+    models.define::<OkuMessage>().unwrap();
+    models.define::<OkuCircle>().unwrap();
+    models.define::<OkuContact>().unwrap();
+    models.define::<OkuPostEmbedding>().unwrap();
+    models.define::<OkuExternalFeed>().unwrap();
+    models.define::<OkuExternalPost>().unwrap();
+    models.define::<OkuActivityDay>().unwrap();
     models
 });
 
 /// The database used by Oku's protocol.
 pub struct OkuDatabase {
     pub(crate) database: Database<'static>,
+    /// The known followers of each user, derived from the identities of crawled users.
+    pub(crate) follower_index: DashMap<AuthorId, HashSet<AuthorId>>,
 }
 
 impl OkuDatabase {
@@ -36,26 +88,64 @@ impl OkuDatabase {
     pub fn new() -> miette::Result<Self> {
         cfg_select! {
             feature = "persistent" => {
-                Ok(Self {
-                        database: native_db::Builder::new()
-                            .create(&MODELS, &*DATABASE_PATH)
-                            .into_diagnostic()?,
-                    })
-                },
+                let database = Self {
+                    database: native_db::Builder::new()
+                        .create(&MODELS, &*DATABASE_PATH)
+                        .into_diagnostic()?,
+                    follower_index: DashMap::new(),
+                };
+                database.migrate()?;
+                database.rebuild_follower_index()?;
+                if let Ok(posts) = database.get_posts() {
+                    if posts.len() as u64 != POST_INDEX_READER.searcher().num_docs() {
+                        database.rebuild_post_index()?;
+                    }
+                    if posts.len() != database.get_post_embeddings()?.len() {
+                        database.rebuild_post_embeddings()?;
+                    } else if posts.len() as u64 != POST_VECTOR_INDEX_READER.searcher().num_docs() {
+                        database.rebuild_post_vector_index()?;
+                    }
+                    if posts.len() != database.count_post_activity()? {
+                        database.rebuild_post_activity()?;
+                    }
+                }
+                if let Ok(external_posts) = database.get_external_posts() {
+                    if external_posts.len() as u64 != EXTERNAL_POST_INDEX_READER.searcher().num_docs() {
+                        database.rebuild_external_post_index()?;
+                    }
+                }
+                if let Ok(users) = database.get_users() {
+                    if users.len() as u64 != USER_INDEX_READER.searcher().num_docs() {
+                        database.rebuild_user_index()?;
+                    }
+                }
+                Ok(database)
+            },
             _ => {
                 Ok(Self{
-                    database: native_db::Builder::new().create_in_memory(&MODELS).into_diagnostic()?
+                    database: native_db::Builder::new().create_in_memory(&MODELS).into_diagnostic()?,
+                    follower_index: DashMap::new(),
                 })
             }
         }
     }
 
-    /// Perform a database migration.
+    /// Migrate records stored by earlier versions of Oku to the current version of their models.
     pub fn migrate(&self) -> miette::Result<()> {
         let rw = self.database.rw_transaction().into_diagnostic()?;
         rw.migrate::<OkuUser>().into_diagnostic()?;
         rw.migrate::<OkuPost>().into_diagnostic()?;
         rw.migrate::<ReplicaAnnouncement>().into_diagnostic()?;
+        rw.migrate::<OkuPostRevision>().into_diagnostic()?;
+        rw.migrate::<OkuDraft>().into_diagnostic()?;
+        rw.migrate::<OkuMute>().into_diagnostic()?;
+        rw.migrate::<OkuMessage>().into_diagnostic()?;
+        rw.migrate::<OkuCircle>().into_diagnostic()?;
+        rw.migrate::<OkuContact>().into_diagnostic()?;
+        rw.migrate::<OkuPostEmbedding>().into_diagnostic()?;
+        rw.migrate::<OkuExternalFeed>().into_diagnostic()?;
+        rw.migrate::<OkuExternalPost>().into_diagnostic()?;
+        rw.migrate::<OkuActivityDay>().into_diagnostic()?;
         rw.commit().into_diagnostic()
     }
 }
@@ -0,0 +1,91 @@
+use super::core::*;
+use super::posts::core::OkuNote;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use url::Url;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 5, version = 1)]
+#[native_db(
+    primary_key(primary_key -> String)
+)]
+/// An unpublished OkuNet post, kept only on this node.
+pub struct OkuDraft {
+    /// The content of the draft.
+    pub note: OkuNote,
+    /// When the draft was last saved.
+    pub last_modified: SystemTime,
+}
+
+impl OkuDraft {
+    pub(crate) fn primary_key(&self) -> String {
+        self.note.url.to_string()
+    }
+}
+
+impl OkuDatabase {
+    /// Insert or update a draft.
+    ///
+    /// # Arguments
+    ///
+    /// * `draft` - A draft to upsert.
+    ///
+    /// # Returns
+    ///
+    /// The previous version of the draft, if one existed.
+    pub fn upsert_draft(&self, draft: &OkuDraft) -> miette::Result<Option<OkuDraft>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<OkuDraft> = rw.upsert(draft.to_owned()).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        Ok(old_value)
+    }
+
+    /// Delete a draft.
+    ///
+    /// # Arguments
+    ///
+    /// * `draft` - A draft to delete.
+    ///
+    /// # Returns
+    ///
+    /// The deleted draft.
+    pub fn delete_draft(&self, draft: &OkuDraft) -> miette::Result<OkuDraft> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let removed_draft = rw.remove(draft.to_owned()).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        Ok(removed_draft)
+    }
+
+    /// Retrieves all drafts saved on this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of all drafts saved on this node.
+    pub fn get_drafts(&self) -> miette::Result<Vec<OkuDraft>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves a draft by the URL it is regarding.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the draft is regarding.
+    ///
+    /// # Returns
+    ///
+    /// The draft regarding the given URL, if one exists.
+    pub fn get_draft(&self, url: &Url) -> miette::Result<Option<OkuDraft>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.get().primary(url.to_string()).into_diagnostic()
+    }
+}
@@ -0,0 +1,582 @@
+use super::core::*;
+use super::posts::core::{OkuNote, OkuPost, OkuTimelineCursor};
+#[cfg(feature = "persistent")]
+use crate::fs::FS_PATH;
+use log::error;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistent")]
+use std::path::PathBuf;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tantivy::{
+    collector::TopDocs,
+    query::QueryParser,
+    schema::{Field, Schema, Value, STORED, STRING, TEXT},
+    Index, IndexReader, IndexWriter, TantivyDocument, Term,
+};
+#[cfg(feature = "persistent")]
+use tantivy::{directory::MmapDirectory, Directory};
+use tokio::sync::Mutex;
+use url::Url;
+
+#[cfg(feature = "persistent")]
+pub(crate) static EXTERNAL_POST_INDEX_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| PathBuf::from(FS_PATH).join("EXTERNAL_POST_INDEX"));
+pub(crate) static EXTERNAL_POST_SCHEMA: LazyLock<(Schema, HashMap<&str, Field>)> =
+    LazyLock::new(|| {
+        let mut schema_builder = Schema::builder();
+        let fields = HashMap::from([
+            ("id", schema_builder.add_text_field("id", STRING | STORED)),
+            (
+                "source",
+                schema_builder.add_text_field("source", TEXT | STORED),
+            ),
+            ("url", schema_builder.add_text_field("url", TEXT | STORED)),
+            (
+                "title",
+                schema_builder.add_text_field("title", TEXT | STORED),
+            ),
+            ("body", schema_builder.add_text_field("body", TEXT | STORED)),
+            ("tag", schema_builder.add_text_field("tag", TEXT | STORED)),
+        ]);
+        let schema = schema_builder.build();
+        (schema, fields)
+    });
+pub(crate) static EXTERNAL_POST_INDEX: LazyLock<Index> = LazyLock::new(|| {
+    cfg_select! {
+        feature = "persistent" => {
+            if let Err(e) = std::fs::create_dir_all(&*EXTERNAL_POST_INDEX_PATH) {
+                error!("{e}");
+            }
+            let mmap_directory: Box<dyn Directory> =
+                Box::new(MmapDirectory::open(&*EXTERNAL_POST_INDEX_PATH).unwrap());
+            match Index::open_or_create(mmap_directory, EXTERNAL_POST_SCHEMA.0.clone()) {
+                Ok(index) => index,
+                Err(e) => {
+                    // The schema has changed; the index is recreated and later rebuilt from the database
+                    error!("{e}");
+                    if let Err(e) = std::fs::remove_dir_all(&*EXTERNAL_POST_INDEX_PATH)
+                        .and_then(|_| std::fs::create_dir_all(&*EXTERNAL_POST_INDEX_PATH))
+                    {
+                        error!("{e}");
+                    }
+                    let mmap_directory: Box<dyn Directory> =
+                        Box::new(MmapDirectory::open(&*EXTERNAL_POST_INDEX_PATH).unwrap());
+                    Index::open_or_create(mmap_directory, EXTERNAL_POST_SCHEMA.0.clone()).unwrap()
+                }
+            }
+        }
+        _ => {
+            Index::create_in_ram(EXTERNAL_POST_SCHEMA.0.clone())
+        }
+    }
+});
+pub(crate) static EXTERNAL_POST_INDEX_READER: LazyLock<IndexReader> =
+    LazyLock::new(|| EXTERNAL_POST_INDEX.reader().unwrap());
+pub(crate) static EXTERNAL_POST_INDEX_WRITER: LazyLock<Arc<Mutex<IndexWriter>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(EXTERNAL_POST_INDEX.writer(50_000_000).unwrap())));
+
+/// Markup tags, removed from the text of imported items.
+static MARKUP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[native_model(id = 11, version = 1)]
+#[native_db]
+/// An RSS or Atom feed from outside OkuNet, whose items are shown alongside OkuNet posts.
+pub struct OkuExternalFeed {
+    #[primary_key]
+    /// The URL or file path the feed is read from.
+    pub source: String,
+    /// The title of the feed.
+    pub title: String,
+    /// The website the feed belongs to, if known.
+    pub site: Option<Url>,
+    /// When the feed was last read.
+    pub last_fetched: SystemTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[native_model(id = 12, version = 1)]
+#[native_db(
+    primary_key(primary_key -> String),
+    secondary_key(timestamp -> u64),
+    secondary_key(source -> String)
+)]
+/// A read-only item from an RSS or Atom feed, kept apart from OkuNet posts.
+pub struct OkuExternalPost {
+    /// The URL or file path of the feed the item is from.
+    pub feed_source: String,
+    /// The title of the feed the item is from.
+    pub feed_title: String,
+    /// The identifier of the item within its feed.
+    pub item_id: String,
+    /// The URL of the item.
+    pub url: Url,
+    /// The title of the item.
+    pub title: String,
+    /// The text of the item, without markup.
+    pub body: String,
+    /// The categories of the item.
+    pub tags: HashSet<String>,
+    /// When the item was published, in microseconds since the Unix epoch.
+    pub published: u64,
+}
+
+impl OkuExternalPost {
+    pub(crate) fn primary_key(&self) -> String {
+        format!("{}\n{}", self.feed_source, self.item_id)
+    }
+
+    pub(crate) fn timestamp(&self) -> u64 {
+        self.published
+    }
+
+    /// The position of this item in a timeline.
+    pub fn cursor(&self) -> OkuTimelineCursor {
+        OkuTimelineCursor {
+            timestamp: self.published,
+            key: self.primary_key().into_bytes(),
+        }
+    }
+
+    pub(crate) fn source(&self) -> String {
+        self.feed_source.clone()
+    }
+
+    pub(crate) fn index_term(&self) -> Term {
+        Term::from_field_text(EXTERNAL_POST_SCHEMA.1["id"], &self.primary_key())
+    }
+
+    /// The item as an OkuNet note, so that mute rules and search filters can be applied to it.
+    pub fn note(&self) -> OkuNote {
+        OkuNote {
+            url: self.url.clone(),
+            title: self.title.clone(),
+            body: self.body.clone(),
+            tags: self.tags.clone(),
+            content_warning: None,
+            sensitive: false,
+            visibility: Default::default(),
+        }
+    }
+}
+
+impl From<OkuExternalPost> for TantivyDocument {
+    fn from(value: OkuExternalPost) -> Self {
+        let mut doc = TantivyDocument::default();
+        doc.add_text(EXTERNAL_POST_SCHEMA.1["id"], value.primary_key());
+        doc.add_text(EXTERNAL_POST_SCHEMA.1["source"], value.feed_title);
+        doc.add_text(EXTERNAL_POST_SCHEMA.1["url"], &value.url);
+        doc.add_text(EXTERNAL_POST_SCHEMA.1["title"], value.title);
+        doc.add_text(EXTERNAL_POST_SCHEMA.1["body"], value.body);
+        for tag in value.tags {
+            doc.add_text(EXTERNAL_POST_SCHEMA.1["tag"], tag);
+        }
+        doc
+    }
+}
+
+/// Remove markup from the text of an imported item.
+fn strip_markup(text: &str) -> String {
+    MARKUP
+        .replace_all(text, " ")
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl OkuExternalFeed {
+    /// Parse an RSS or Atom document.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The URL or file path the document was read from.
+    ///
+    /// * `bytes` - The content of the document.
+    ///
+    /// # Returns
+    ///
+    /// The feed, and its items; items without a link are skipped, and items without a date are dated to when the feed was read.
+    pub fn parse(source: &str, bytes: &[u8]) -> miette::Result<(Self, Vec<OkuExternalPost>)> {
+        let parsed = feed_rs::parser::parse(bytes).into_diagnostic()?;
+        let site = parsed
+            .links
+            .iter()
+            .filter(|x| x.rel.as_deref().is_none_or(|y| y == "alternate"))
+            .find_map(|x| Url::parse(&x.href).ok());
+        let title = parsed
+            .title
+            .map(|x| strip_markup(&x.content))
+            .filter(|x| !x.is_empty())
+            .or(site
+                .as_ref()
+                .and_then(|x| x.host_str().map(|y| y.to_owned())))
+            .unwrap_or(source.to_owned());
+        let now = SystemTime::now();
+        let now_micros = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let posts = parsed
+            .entries
+            .into_iter()
+            .filter_map(|entry| {
+                let url = entry
+                    .links
+                    .iter()
+                    .filter(|x| x.rel.as_deref().is_none_or(|y| y == "alternate"))
+                    .find_map(|x| Url::parse(&x.href).ok())
+                    .or(Url::parse(&entry.id).ok())?;
+                let body = entry
+                    .content
+                    .and_then(|x| x.body)
+                    .or(entry.summary.map(|x| x.content))
+                    .map(|x| strip_markup(&x))
+                    .unwrap_or_default();
+                let item_title = entry
+                    .title
+                    .map(|x| strip_markup(&x.content))
+                    .filter(|x| !x.is_empty())
+                    .unwrap_or(url.to_string());
+                let published = entry
+                    .published
+                    .or(entry.updated)
+                    .and_then(|x| x.timestamp_micros().try_into().ok())
+                    .unwrap_or(now_micros);
+                Some(OkuExternalPost {
+                    feed_source: source.to_owned(),
+                    feed_title: title.clone(),
+                    item_id: match entry.id.is_empty() {
+                        true => url.to_string(),
+                        false => entry.id,
+                    },
+                    url,
+                    title: item_title,
+                    body,
+                    tags: entry
+                        .categories
+                        .into_iter()
+                        .map(|x| x.label.unwrap_or(x.term))
+                        .collect(),
+                    published,
+                })
+            })
+            .collect();
+        Ok((
+            Self {
+                source: source.to_owned(),
+                title,
+                site,
+                last_fetched: now,
+            },
+            posts,
+        ))
+    }
+}
+
+impl OkuDatabase {
+    /// Insert or update an imported feed and its items.
+    ///
+    /// # Arguments
+    ///
+    /// * `feed` - An imported feed.
+    ///
+    /// * `posts` - The items of the feed.
+    ///
+    /// # Returns
+    ///
+    /// The previous version of the feed, if one existed.
+    pub fn upsert_external_feed(
+        &self,
+        feed: &OkuExternalFeed,
+        posts: &[OkuExternalPost],
+    ) -> miette::Result<Option<OkuExternalFeed>> {
+        let fetched = feed
+            .last_fetched
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<OkuExternalFeed> = rw.upsert(feed.clone()).into_diagnostic()?;
+        // Items without a date keep the time they were first seen, rather than the time the feed was last read
+        let posts: Vec<_> = posts
+            .iter()
+            .map(|post| {
+                let first_seen = match post.published == fetched {
+                    true => rw
+                        .get()
+                        .primary::<OkuExternalPost>(post.primary_key())
+                        .ok()
+                        .flatten()
+                        .map(|x| x.published),
+                    false => None,
+                };
+                OkuExternalPost {
+                    published: first_seen.unwrap_or(post.published),
+                    ..post.clone()
+                }
+            })
+            .collect();
+        let old_posts: Vec<_> = posts
+            .iter()
+            .filter_map(|post| rw.upsert(post.clone()).ok().flatten())
+            .collect();
+        rw.commit().into_diagnostic()?;
+
+        let mut index_writer = lock_index_writer(&EXTERNAL_POST_INDEX_WRITER);
+        old_posts.par_iter().for_each(|old_post: &OkuExternalPost| {
+            index_writer.delete_term(old_post.index_term());
+        });
+        posts.par_iter().for_each(|post| {
+            if let Err(e) = index_writer.add_document(post.clone().into()) {
+                error!("{e}");
+            }
+        });
+        index_writer.commit().into_diagnostic()?;
+
+        Ok(old_value)
+    }
+
+    /// Delete an imported feed and its items.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The URL or file path the feed is read from.
+    ///
+    /// # Returns
+    ///
+    /// The deleted feed, if it was imported.
+    pub fn delete_external_feed(&self, source: &str) -> miette::Result<Option<OkuExternalFeed>> {
+        let posts = self.get_external_posts_from(source)?;
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let feed: Option<OkuExternalFeed> =
+            rw.get().primary(source.to_owned()).into_diagnostic()?;
+        let removed_feed = match feed {
+            Some(feed) => Some(rw.remove(feed).into_diagnostic()?),
+            None => None,
+        };
+        let removed_posts: Vec<_> = posts
+            .into_iter()
+            .filter_map(|post| rw.remove(post).ok())
+            .collect();
+        rw.commit().into_diagnostic()?;
+
+        let mut index_writer = lock_index_writer(&EXTERNAL_POST_INDEX_WRITER);
+        removed_posts.par_iter().for_each(|removed_post| {
+            index_writer.delete_term(removed_post.index_term());
+        });
+        index_writer.commit().into_diagnostic()?;
+
+        Ok(removed_feed)
+    }
+
+    /// Retrieves the imported feeds.
+    ///
+    /// # Returns
+    ///
+    /// A list of the feeds imported from outside OkuNet.
+    pub fn get_external_feeds(&self) -> miette::Result<Vec<OkuExternalFeed>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves the items of an imported feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The URL or file path the feed is read from.
+    ///
+    /// # Returns
+    ///
+    /// A list of the feed's items.
+    pub fn get_external_posts_from(&self, source: &str) -> miette::Result<Vec<OkuExternalPost>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .secondary::<OkuExternalPost>(OkuExternalPostKey::source)
+            .into_diagnostic()?
+            .range(source.to_owned()..=source.to_owned())
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves all items of imported feeds.
+    ///
+    /// # Returns
+    ///
+    /// A list of the items of every imported feed.
+    pub fn get_external_posts(&self) -> miette::Result<Vec<OkuExternalPost>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves a page of the items of imported feeds, newest first, excluding muted items.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - A cursor; if specified, only items positioned before it in the timeline are retrieved.
+    ///
+    /// * `page_size` - The maximum number of items to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A list of items of imported feeds, ordered from newest to oldest.
+    pub fn get_external_timeline(
+        &self,
+        before: &Option<OkuTimelineCursor>,
+        page_size: usize,
+    ) -> miette::Result<Vec<OkuExternalPost>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let scan = r
+            .scan()
+            .secondary::<OkuExternalPost>(OkuExternalPostKey::timestamp)
+            .into_diagnostic()?;
+        let posts: Box<dyn Iterator<Item = OkuExternalPost> + '_> = match before {
+            Some(before) => Box::new(
+                scan.range(..=before.timestamp)
+                    .into_diagnostic()?
+                    .rev()
+                    .filter_map(|x| x.ok())
+                    .filter(move |x| x.cursor() < *before),
+            ),
+            None => Box::new(scan.all().into_diagnostic()?.rev().filter_map(|x| x.ok())),
+        };
+        let mut candidates = posts.peekable();
+        let mut timeline = Vec::with_capacity(page_size);
+        // Muted items are filtered out a page at a time, so that the page is filled if possible
+        while timeline.len() < page_size {
+            let page: Vec<_> = candidates
+                .by_ref()
+                .take(page_size - timeline.len())
+                .collect();
+            if page.is_empty() {
+                break;
+            }
+            timeline.extend(self.filter_muted_external_posts(page));
+        }
+        // Items published at the same time are not read in order of their keys, so all of those published when the last item was are considered
+        if let Some(last_timestamp) = timeline.last().map(|x: &OkuExternalPost| x.published) {
+            let ties: Vec<_> =
+                std::iter::from_fn(|| candidates.next_if(|x| x.published == last_timestamp))
+                    .collect();
+            timeline.extend(self.filter_muted_external_posts(ties));
+        }
+        timeline.sort_unstable_by_key(|x| Reverse(x.cursor()));
+        timeline.truncate(page_size);
+        Ok(timeline)
+    }
+
+    /// Search the items of imported feeds with a query string.
+    ///
+    /// # Arguments
+    ///
+    /// * `query_string` - The string used to query for items.
+    ///
+    /// * `result_limit` - The maximum number of results to get (defaults to 10).
+    ///
+    /// # Returns
+    ///
+    /// A list of items of imported feeds, excluding muted items.
+    pub fn search_external_posts(
+        &self,
+        query_string: &str,
+        result_limit: &Option<usize>,
+    ) -> miette::Result<Vec<OkuExternalPost>> {
+        let searcher = EXTERNAL_POST_INDEX_READER.searcher();
+        let query_parser = QueryParser::for_index(
+            &EXTERNAL_POST_INDEX,
+            vec![
+                EXTERNAL_POST_SCHEMA.1["source"],
+                EXTERNAL_POST_SCHEMA.1["title"],
+                EXTERNAL_POST_SCHEMA.1["body"],
+                EXTERNAL_POST_SCHEMA.1["tag"],
+            ],
+        );
+        let query = query_parser.parse_query(query_string).into_diagnostic()?;
+        let top_docs = searcher
+            .search(
+                &query,
+                &TopDocs::with_limit(result_limit.unwrap_or(10)).order_by_score(),
+            )
+            .into_diagnostic()?;
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let posts = top_docs
+            .iter()
+            .filter_map(|x| searcher.doc::<TantivyDocument>(x.1).ok())
+            .filter_map(|x| {
+                x.get_first(EXTERNAL_POST_SCHEMA.1["id"])
+                    .and_then(|y| y.as_str().map(|z| z.to_owned()))
+            })
+            .filter_map(|x| r.get().primary::<OkuExternalPost>(x).ok().flatten())
+            .collect();
+        Ok(self.filter_muted_external_posts(posts))
+    }
+
+    /// Rebuilds the search index of the items of imported feeds from the database.
+    pub fn rebuild_external_post_index(&self) -> miette::Result<()> {
+        let mut index_writer = lock_index_writer(&EXTERNAL_POST_INDEX_WRITER);
+        index_writer.delete_all_documents().into_diagnostic()?;
+        self.get_external_posts()?.into_par_iter().for_each(|post| {
+            if let Err(e) = index_writer.add_document(post.into()) {
+                error!("{e}");
+            }
+        });
+        index_writer.commit().into_diagnostic()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An entry in the home timeline; either an OkuNet post, or an item of an imported feed.
+pub enum OkuTimelineItem {
+    /// An OkuNet post.
+    Post(OkuPost),
+    /// An item of a feed imported from outside OkuNet.
+    External(OkuExternalPost),
+}
+
+impl OkuTimelineItem {
+    /// When the entry was written, in microseconds since the Unix epoch.
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            OkuTimelineItem::Post(post) => post.entry.timestamp(),
+            OkuTimelineItem::External(post) => post.published,
+        }
+    }
+
+    /// The position of the entry in a timeline.
+    pub fn cursor(&self) -> OkuTimelineCursor {
+        match self {
+            OkuTimelineItem::Post(post) => post.cursor(),
+            OkuTimelineItem::External(post) => post.cursor(),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::external_feeds::OkuExternalFeed;
+    use std::{
+        collections::HashSet,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+    use url::Url;
+
+    const RSS_FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+    <channel>
+        <title>Example &amp; Co.</title>
+        <link>https://example.com/</link>
+        <description>An example feed</description>
+        <item>
+            <title>First item</title>
+            <link>https://example.com/first</link>
+            <guid>https://example.com/first</guid>
+            <description>&lt;p&gt;Hello, &lt;b&gt;World&lt;/b&gt;&lt;/p&gt;</description>
+            <category>news</category>
+            <pubDate>Mon, 06 Sep 2021 16:45:00 +0000</pubDate>
+        </item>
+        <item>
+            <title>Undated item</title>
+            <link>https://example.com/undated</link>
+            <guid isPermaLink="false">undated-item</guid>
+        </item>
+        <item>
+            <title>Unlinked item</title>
+            <guid isPermaLink="false">unlinked-item</guid>
+            <pubDate>Mon, 06 Sep 2021 16:45:00 +0000</pubDate>
+        </item>
+    </channel>
+</rss>"#;
+
+    const ATOM_FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+    <title>Atom Example</title>
+    <link href="https://atom.example.com/"/>
+    <link rel="self" href="https://atom.example.com/feed.xml"/>
+    <id>https://atom.example.com/feed.xml</id>
+    <updated>2021-09-07T10:00:00Z</updated>
+    <entry>
+        <title type="html">An &lt;em&gt;Atom&lt;/em&gt; entry</title>
+        <link href="https://atom.example.com/entry"/>
+        <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+        <updated>2021-09-07T10:00:00Z</updated>
+        <summary>A summary</summary>
+        <content type="html">&lt;p&gt;Some &amp;amp; content&lt;/p&gt;</content>
+        <category term="tech" label="Technology"/>
+    </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_rss() -> miette::Result<()> {
+        let (feed, posts) =
+            OkuExternalFeed::parse("https://example.com/rss.xml", RSS_FEED.as_bytes())?;
+        assert_eq!("https://example.com/rss.xml", feed.source);
+        assert_eq!("Example & Co.", feed.title);
+        assert_eq!(Url::parse("https://example.com/").ok(), feed.site);
+
+        let post = posts
+            .iter()
+            .find(|x| x.title == "First item")
+            .ok_or(miette::miette!("RSS item was not parsed"))?;
+        assert_eq!("https://example.com/rss.xml", post.feed_source);
+        assert_eq!("Example & Co.", post.feed_title);
+        assert_eq!("https://example.com/first", post.item_id);
+        assert_eq!("https://example.com/first", post.url.as_str());
+        // Markup is removed from the text of items
+        assert_eq!("Hello, World", post.body);
+        assert_eq!(HashSet::from(["news".to_string()]), post.tags);
+        assert_eq!(1_630_946_700_000_000, post.published);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_atom() -> miette::Result<()> {
+        let (feed, posts) =
+            OkuExternalFeed::parse("https://atom.example.com/feed.xml", ATOM_FEED.as_bytes())?;
+        assert_eq!("Atom Example", feed.title);
+        // The feed's own address is not mistaken for its website
+        assert_eq!(Url::parse("https://atom.example.com/").ok(), feed.site);
+
+        assert_eq!(1, posts.len());
+        let post = &posts[0];
+        assert_eq!("An Atom entry", post.title);
+        assert_eq!(
+            "urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a",
+            post.item_id
+        );
+        assert_eq!("https://atom.example.com/entry", post.url.as_str());
+        // Content is preferred over the summary
+        assert_eq!("Some & content", post.body);
+        assert_eq!(HashSet::from(["Technology".to_string()]), post.tags);
+        // Items without a publication date are dated to when they were last updated
+        assert_eq!(1_631_008_800_000_000, post.published);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_missing_link() -> miette::Result<()> {
+        let (_, posts) =
+            OkuExternalFeed::parse("https://example.com/rss.xml", RSS_FEED.as_bytes())?;
+
+        // Items without a link are skipped
+        assert_eq!(2, posts.len());
+        assert!(!posts.iter().any(|x| x.title == "Unlinked item"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_missing_date() -> miette::Result<()> {
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let (feed, posts) =
+            OkuExternalFeed::parse("https://example.com/rss.xml", RSS_FEED.as_bytes())?;
+        let after = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+
+        // Items without a date are dated to when the feed was read
+        let post = posts
+            .iter()
+            .find(|x| x.title == "Undated item")
+            .ok_or(miette::miette!("Undated RSS item was not parsed"))?;
+        assert_eq!("undated-item", post.item_id);
+        assert!((before..=after).contains(&post.published));
+        assert_eq!(
+            feed.last_fetched
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+            post.published
+        );
+
+        Ok(())
+    }
+}
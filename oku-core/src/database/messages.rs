@@ -0,0 +1,163 @@
+use super::core::*;
+use iroh_docs::{sync::Entry, AuthorId};
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 7, version = 1)]
+#[native_db(
+    primary_key(primary_key -> (Vec<u8>, Vec<u8>))
+)]
+/// A decrypted direct message between two OkuNet users, kept only on the node of one of them.
+pub struct OkuMessage {
+    /// A record of the encrypted message file in the sender's home replica.
+    pub entry: Entry,
+    /// The content authorship ID of the user the message was sent to.
+    pub recipient: AuthorId,
+    /// The body of the message.
+    pub body: String,
+}
+
+impl OkuMessage {
+    pub(crate) fn primary_key(&self) -> (Vec<u8>, Vec<u8>) {
+        (
+            self.entry.author().as_bytes().to_vec(),
+            self.entry.key().to_vec(),
+        )
+    }
+
+    /// The content authorship ID of the user who sent the message.
+    pub fn sender(&self) -> AuthorId {
+        self.entry.author()
+    }
+
+    /// The other participant in the conversation this message belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `me` - The content authorship ID of the local user.
+    ///
+    /// # Returns
+    ///
+    /// The recipient if the local user sent the message, otherwise the sender.
+    pub fn correspondent(&self, me: &AuthorId) -> AuthorId {
+        match self.sender() == *me {
+            true => self.recipient,
+            false => self.sender(),
+        }
+    }
+}
+
+impl OkuDatabase {
+    /// Insert or update direct messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - A list of direct messages to upsert.
+    ///
+    /// # Returns
+    ///
+    /// A list containing the previous version of each message, if one existed.
+    pub fn upsert_messages(
+        &self,
+        messages: &Vec<OkuMessage>,
+    ) -> miette::Result<Vec<Option<OkuMessage>>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_messages: Vec<_> = messages
+            .clone()
+            .into_iter()
+            .filter_map(|message| rw.upsert(message).ok())
+            .collect();
+        rw.commit().into_diagnostic()?;
+        Ok(old_messages)
+    }
+
+    /// Delete direct messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - A list of direct messages to delete.
+    ///
+    /// # Returns
+    ///
+    /// A list containing the deleted messages.
+    pub fn delete_messages(&self, messages: &Vec<OkuMessage>) -> miette::Result<Vec<OkuMessage>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let removed_messages: Vec<_> = messages
+            .clone()
+            .into_iter()
+            .filter_map(|message| rw.remove(message).ok())
+            .collect();
+        rw.commit().into_diagnostic()?;
+        Ok(removed_messages)
+    }
+
+    /// Retrieves all direct messages known to this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of all direct messages sent or received by this node's user.
+    pub fn get_messages(&self) -> miette::Result<Vec<OkuMessage>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves the direct messages exchanged with a particular user.
+    ///
+    /// # Arguments
+    ///
+    /// * `me` - The content authorship ID of the local user.
+    ///
+    /// * `correspondent` - The content authorship ID of the other user.
+    ///
+    /// # Returns
+    ///
+    /// The messages exchanged with the other user, oldest first.
+    pub fn get_conversation(
+        &self,
+        me: &AuthorId,
+        correspondent: &AuthorId,
+    ) -> miette::Result<Vec<OkuMessage>> {
+        let mut messages: Vec<_> = self
+            .get_messages()?
+            .into_iter()
+            .filter(|x| x.correspondent(me) == *correspondent)
+            .collect();
+        messages.sort_unstable_by_key(|x| x.entry.timestamp());
+        Ok(messages)
+    }
+
+    /// Retrieves the latest direct message of each conversation.
+    ///
+    /// # Arguments
+    ///
+    /// * `me` - The content authorship ID of the local user.
+    ///
+    /// # Returns
+    ///
+    /// The latest message exchanged with each other user, newest first.
+    pub fn get_conversations(&self, me: &AuthorId) -> miette::Result<Vec<OkuMessage>> {
+        let mut latest_messages: HashMap<AuthorId, OkuMessage> = HashMap::new();
+        for message in self.get_messages()? {
+            let correspondent = message.correspondent(me);
+            match latest_messages.get(&correspondent) {
+                Some(latest) if latest.entry.timestamp() >= message.entry.timestamp() => (),
+                _ => {
+                    latest_messages.insert(correspondent, message);
+                }
+            }
+        }
+        let mut conversations: Vec<_> = latest_messages.into_values().collect();
+        conversations.sort_unstable_by_key(|x| std::cmp::Reverse(x.entry.timestamp()));
+        Ok(conversations)
+    }
+}
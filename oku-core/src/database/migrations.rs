@@ -0,0 +1,128 @@
+use super::posts::core::{OkuNote, OkuPost, OkuVisibility};
+use super::users::{OkuIdentity, OkuUser};
+use iroh_docs::sync::Entry;
+use iroh_docs::AuthorId;
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, time::SystemTime};
+use url::Url;
+
+fn entry_primary_key(entry: &Entry) -> (Vec<u8>, Vec<u8>) {
+    (entry.author().as_bytes().to_vec(), entry.key().to_vec())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// An OkuNet note, before content warnings, sensitive content, and restricted visibility were recorded.
+pub(crate) struct OkuNoteV2 {
+    url: Url,
+    title: String,
+    body: String,
+    tags: HashSet<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 2, version = 2)]
+#[native_db(
+    primary_key(primary_key -> (Vec<u8>, Vec<u8>))
+)]
+/// An OkuNet post, before content warnings, sensitive content, and restricted visibility were recorded.
+pub(crate) struct OkuPostV2 {
+    entry: Entry,
+    note: OkuNoteV2,
+}
+
+impl OkuPostV2 {
+    fn primary_key(&self) -> (Vec<u8>, Vec<u8>) {
+        entry_primary_key(&self.entry)
+    }
+}
+
+impl From<OkuPostV2> for OkuPost {
+    fn from(value: OkuPostV2) -> Self {
+        Self {
+            entry: value.entry,
+            note: OkuNote {
+                url: value.note.url,
+                title: value.note.title,
+                body: value.note.body,
+                tags: value.note.tags,
+                content_warning: None,
+                sensitive: false,
+                visibility: OkuVisibility::Public,
+            },
+        }
+    }
+}
+
+impl From<OkuPost> for OkuPostV2 {
+    fn from(value: OkuPost) -> Self {
+        Self {
+            entry: value.entry,
+            note: OkuNoteV2 {
+                url: value.note.url,
+                title: value.note.title,
+                body: value.note.body,
+                tags: value.note.tags,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// An OkuNet identity, before biographies and blocked list subscriptions were recorded.
+pub(crate) struct OkuIdentityV1 {
+    name: String,
+    following: HashSet<AuthorId>,
+    blocked: HashSet<AuthorId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 1, version = 1)]
+#[native_db(
+    primary_key(author_id -> Vec<u8>)
+)]
+/// An Oku user, before biographies and blocked list subscriptions were recorded.
+pub(crate) struct OkuUserV1 {
+    author_id: AuthorId,
+    last_fetched: SystemTime,
+    posts: Vec<Entry>,
+    identity: Option<OkuIdentityV1>,
+}
+
+impl OkuUserV1 {
+    fn author_id(&self) -> Vec<u8> {
+        self.author_id.as_bytes().to_vec()
+    }
+}
+
+impl From<OkuUserV1> for OkuUser {
+    fn from(value: OkuUserV1) -> Self {
+        Self {
+            author_id: value.author_id,
+            last_fetched: value.last_fetched,
+            posts: value.posts,
+            identity: value.identity.map(|x| OkuIdentity {
+                name: x.name,
+                following: x.following,
+                blocked: x.blocked,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl From<OkuUser> for OkuUserV1 {
+    fn from(value: OkuUser) -> Self {
+        Self {
+            author_id: value.author_id,
+            last_fetched: value.last_fetched,
+            posts: value.posts,
+            identity: value.identity.map(|x| OkuIdentityV1 {
+                name: x.name,
+                following: x.following,
+                blocked: x.blocked,
+            }),
+        }
+    }
+}
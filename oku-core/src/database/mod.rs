@@ -1,8 +1,25 @@
+/// Database functionality relating to circles of OkuNet users.
+pub mod circles;
+/// Database functionality relating to what the local user knows about other OkuNet users.
+pub mod contacts;
 /// Core functionality of an OkuNet node's database.
 pub mod core;
 /// Database functionality relating to the DHT.
 pub mod dht;
+/// Database functionality relating to unpublished OkuNet posts.
+pub mod drafts;
+/// Database functionality relating to RSS and Atom feeds imported from outside OkuNet.
+pub mod external_feeds;
+/// Database functionality relating to direct messages between OkuNet users.
+pub mod messages;
+/// Earlier versions of database models, from which existing databases are migrated.
+pub(crate) mod migrations;
+/// Database functionality relating to muting OkuNet posts.
+pub mod mutes;
 /// Database functionality relating to OkuNet posts.
 pub mod posts;
 /// Database functionality relating to OkuNet users.
 pub mod users;
+
+mod external_feeds_test;
+mod mutes_test;
@@ -0,0 +1,314 @@
+use super::core::*;
+use super::external_feeds::OkuExternalPost;
+use super::posts::core::{OkuNote, OkuPost};
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr, time::SystemTime};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+/// A rule describing which OkuNet posts should be hidden from this node's user.
+pub enum OkuMuteRule {
+    /// Mute posts containing a whole word or phrase in their title, body, or content warning (case-insensitive).
+    Keyword(String),
+    /// Mute posts whose title, body, or content warning match a regular expression.
+    Regex(String),
+    /// Mute posts with a particular tag (case-insensitive).
+    Tag(String),
+    /// Mute posts regarding URLs on a particular domain, or any of its subdomains.
+    Domain(String),
+}
+
+impl Display for OkuMuteRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keyword(keyword) => write!(f, "keyword:{keyword}"),
+            Self::Regex(regex) => write!(f, "regex:{regex}"),
+            Self::Tag(tag) => write!(f, "tag:{tag}"),
+            Self::Domain(domain) => write!(f, "domain:{domain}"),
+        }
+    }
+}
+
+impl FromStr for OkuMuteRule {
+    type Err = miette::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, value) = s.split_once(':').ok_or(miette::miette!(
+            "Mute rules must be of the form 'keyword:…', 'regex:…', 'tag:…', or 'domain:…'."
+        ))?;
+        if value.trim().is_empty() {
+            return Err(miette::miette!("Mute rule {s:?} has nothing to match … "));
+        }
+        let rule = match kind.trim().to_lowercase().as_str() {
+            "keyword" => Self::Keyword(value.trim().to_string()),
+            "regex" => Self::Regex(value.to_string()),
+            "tag" => Self::Tag(value.trim().to_string()),
+            "domain" => Self::Domain(value.trim().trim_start_matches('.').to_lowercase()),
+            _ => return Err(miette::miette!("Unexpected mute rule kind: {kind}")),
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+}
+
+impl OkuMuteRule {
+    /// Ensures the rule can be applied, rejecting malformed regular expressions.
+    pub fn validate(&self) -> miette::Result<()> {
+        if let Self::Regex(regex) = self {
+            Regex::new(regex).into_diagnostic()?;
+        }
+        Ok(())
+    }
+
+    /// Determine whether a note is hidden by this rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `note` - An OkuNet note.
+    ///
+    /// # Returns
+    ///
+    /// Whether the note matches the rule; malformed rules match nothing.
+    pub fn matches(&self, note: &OkuNote) -> bool {
+        self.matcher().is_some_and(|x| x.matches(note))
+    }
+
+    pub(crate) fn matcher(&self) -> Option<MuteMatcher> {
+        match self {
+            Self::Keyword(keyword) => {
+                // Keywords match whole words, so that muting 'cat' does not hide posts about 'education'
+                let is_word_character = |x: char| x.is_alphanumeric() || x == '_';
+                let mut pattern = regex::escape(keyword);
+                if keyword.starts_with(is_word_character) {
+                    pattern = format!(r"\b{pattern}");
+                }
+                if keyword.ends_with(is_word_character) {
+                    pattern = format!(r"{pattern}\b");
+                }
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .ok()
+                    .map(MuteMatcher::Text)
+            }
+            Self::Regex(regex) => Regex::new(regex).ok().map(MuteMatcher::Text),
+            Self::Tag(tag) => Some(MuteMatcher::Tag(tag.to_lowercase())),
+            Self::Domain(domain) => Some(MuteMatcher::Domain(domain.to_lowercase())),
+        }
+    }
+}
+
+/// A mute rule prepared for matching against many posts.
+pub(crate) enum MuteMatcher {
+    Text(Regex),
+    Tag(String),
+    Domain(String),
+}
+
+impl MuteMatcher {
+    pub(crate) fn matches(&self, note: &OkuNote) -> bool {
+        match self {
+            Self::Text(regex) => {
+                regex.is_match(&note.title)
+                    || regex.is_match(&note.body)
+                    || note
+                        .content_warning
+                        .as_ref()
+                        .is_some_and(|x| regex.is_match(x))
+            }
+            Self::Tag(tag) => note.tags.iter().any(|x| x.to_lowercase() == *tag),
+            Self::Domain(domain) => note.url.host_str().is_some_and(|host| {
+                let host = host.to_lowercase();
+                host == *domain || host.ends_with(&format!(".{domain}"))
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 6, version = 1)]
+#[native_db(
+    primary_key(primary_key -> String)
+)]
+/// A mute rule kept on this node, hiding matching OkuNet posts from timelines, tag pages, and search results.
+pub struct OkuMute {
+    /// The rule describing which posts are muted.
+    pub rule: OkuMuteRule,
+    /// When the mute stops applying; if unspecified, the mute applies until removed.
+    pub expiry: Option<SystemTime>,
+}
+
+impl OkuMute {
+    pub(crate) fn primary_key(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Whether the mute no longer applies.
+    pub fn is_expired(&self) -> bool {
+        self.expiry.is_some_and(|x| x <= SystemTime::now())
+    }
+}
+
+impl OkuDatabase {
+    /// Insert or update a mute rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `mute` - A mute rule to upsert.
+    ///
+    /// # Returns
+    ///
+    /// The previous version of the mute rule, if one existed.
+    pub fn upsert_mute(&self, mute: &OkuMute) -> miette::Result<Option<OkuMute>> {
+        mute.rule.validate()?;
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<OkuMute> = rw.upsert(mute.to_owned()).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        Ok(old_value)
+    }
+
+    /// Delete a mute rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule to stop muting posts with.
+    ///
+    /// # Returns
+    ///
+    /// The deleted mute, if one existed.
+    pub fn delete_mute(&self, rule: &OkuMuteRule) -> miette::Result<Option<OkuMute>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let mute: Option<OkuMute> = rw.get().primary(rule.to_string()).into_diagnostic()?;
+        let removed_mute = match mute {
+            Some(mute) => Some(rw.remove(mute).into_diagnostic()?),
+            None => None,
+        };
+        rw.commit().into_diagnostic()?;
+        Ok(removed_mute)
+    }
+
+    /// Deletes mute rules which no longer apply.
+    ///
+    /// # Returns
+    ///
+    /// The deleted mutes.
+    pub fn delete_expired_mutes(&self) -> miette::Result<Vec<OkuMute>> {
+        let expired_mutes: Vec<_> = self
+            .get_all_mutes()?
+            .into_iter()
+            .filter(|x| x.is_expired())
+            .collect();
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let removed_mutes: Vec<_> = expired_mutes
+            .into_iter()
+            .filter_map(|mute| rw.remove(mute).ok())
+            .collect();
+        rw.commit().into_diagnostic()?;
+        Ok(removed_mutes)
+    }
+
+    /// Retrieves all mute rules saved on this node, including those which have expired.
+    ///
+    /// # Returns
+    ///
+    /// A list of all mute rules saved on this node.
+    pub fn get_all_mutes(&self) -> miette::Result<Vec<OkuMute>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Retrieves the mute rules currently applying on this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of unexpired mute rules.
+    pub fn get_mutes(&self) -> miette::Result<Vec<OkuMute>> {
+        Ok(self
+            .get_all_mutes()?
+            .into_iter()
+            .filter(|x| !x.is_expired())
+            .collect())
+    }
+
+    /// Determines whether an OkuNet post is muted.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - An OkuNet post.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the post matches any unexpired mute rule.
+    pub fn is_post_muted(&self, post: &OkuPost) -> bool {
+        let matchers: Vec<_> = self
+            .get_mutes()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|x| x.rule.matcher())
+            .collect();
+        matchers.iter().any(|x| x.matches(&post.note))
+    }
+
+    /// Removes muted posts from a list of OkuNet posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `posts` - A list of OkuNet posts.
+    ///
+    /// # Returns
+    ///
+    /// The posts not matching any unexpired mute rule.
+    pub fn filter_muted_posts(&self, posts: Vec<OkuPost>) -> Vec<OkuPost> {
+        let matchers: Vec<_> = self
+            .get_mutes()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|x| x.rule.matcher())
+            .collect();
+        if matchers.is_empty() {
+            return posts;
+        }
+        posts
+            .into_par_iter()
+            .filter(|post| !matchers.iter().any(|x| x.matches(&post.note)))
+            .collect()
+    }
+
+    /// Removes muted items from a list of items of imported feeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `posts` - A list of items of imported feeds.
+    ///
+    /// # Returns
+    ///
+    /// The items not matching any unexpired mute rule.
+    pub fn filter_muted_external_posts(&self, posts: Vec<OkuExternalPost>) -> Vec<OkuExternalPost> {
+        let matchers: Vec<_> = self
+            .get_mutes()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|x| x.rule.matcher())
+            .collect();
+        if matchers.is_empty() {
+            return posts;
+        }
+        posts
+            .into_par_iter()
+            .filter(|post| {
+                let note = post.note();
+                !matchers.iter().any(|x| x.matches(&note))
+            })
+            .collect()
+    }
+}
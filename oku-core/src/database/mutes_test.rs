@@ -0,0 +1,259 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::core::{OkuDatabase, DATABASE};
+    use crate::database::mutes::{OkuMute, OkuMuteRule};
+    use crate::database::posts::core::{OkuNote, OkuPost, OkuVisibility, POST_INDEX_READER};
+    use crate::database::posts::search::OkuPostSearch;
+    use crate::fs::util::path_to_entry_key;
+    use iroh_blobs::Hash;
+    use iroh_docs::sync::{Entry, Record, RecordIdentifier};
+    use iroh_docs::{Author, NamespaceSecret};
+    use std::{
+        collections::HashSet,
+        path::PathBuf,
+        str::FromStr,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+    use url::Url;
+
+    fn note(url: &str, title: &str, body: &str, tags: &[&str]) -> OkuNote {
+        OkuNote {
+            url: Url::parse(url).unwrap(),
+            title: title.to_string(),
+            body: body.to_string(),
+            tags: tags.iter().map(|x| x.to_string()).collect(),
+            content_warning: None,
+            sensitive: false,
+            visibility: OkuVisibility::Public,
+        }
+    }
+
+    fn post(author: &Author, path: &str, note: OkuNote) -> OkuPost {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        OkuPost {
+            entry: Entry::new(
+                RecordIdentifier::new(
+                    NamespaceSecret::from_bytes(&[0; 32]).id(),
+                    author.id(),
+                    path_to_entry_key(&PathBuf::from(path)),
+                ),
+                Record::new(Hash::new(path), 0, timestamp),
+            ),
+            note,
+        }
+    }
+
+    #[test]
+    fn test_keyword_mute() -> miette::Result<()> {
+        let rule = OkuMuteRule::from_str("keyword: cat ")?;
+        assert_eq!(OkuMuteRule::Keyword("cat".to_string()), rule);
+
+        // Keywords match whole words in the title, body, or content warning, regardless of case
+        assert!(rule.matches(&note("https://example.com/", "", "A cat sat", &[])));
+        assert!(rule.matches(&note("https://example.com/", "CAT", "", &[])));
+        let mut warned = note("https://example.com/", "", "", &[]);
+        warned.content_warning = Some("Cats, and one cat".to_string());
+        assert!(rule.matches(&warned));
+        assert!(!rule.matches(&note("https://example.com/", "", "On education", &[])));
+        assert!(!rule.matches(&note("https://example.com/", "", "Cats", &["cat"])));
+
+        // Punctuation in keywords is matched exactly
+        let rule = OkuMuteRule::from_str("keyword:C++")?;
+        assert!(rule.matches(&note("https://example.com/", "", "I write c++ daily", &[])));
+        assert!(!rule.matches(&note("https://example.com/", "", "c is a letter", &[])));
+        assert!(!rule.matches(&note("https://example.com/", "", "I write C#", &[])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_regex_mute() -> miette::Result<()> {
+        let rule = OkuMuteRule::from_str("regex:^Breaking")?;
+        assert!(rule.matches(&note("https://example.com/", "Breaking news", "", &[])));
+        assert!(!rule.matches(&note("https://example.com/", "", "Not breaking", &[])));
+
+        // Malformed expressions are rejected, and match nothing if saved regardless
+        assert!(OkuMuteRule::from_str("regex:(").is_err());
+        assert!(!OkuMuteRule::Regex("(".to_string()).matches(&note(
+            "https://example.com/",
+            "(",
+            "(",
+            &[]
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_mute() -> miette::Result<()> {
+        let rule = OkuMuteRule::from_str("tag:Spoilers")?;
+        assert!(rule.matches(&note("https://example.com/", "", "", &["spoilers"])));
+        assert!(rule.matches(&note("https://example.com/", "", "", &["news", "SPOILERS"])));
+        assert!(!rule.matches(&note("https://example.com/", "", "", &["spoiler"])));
+        assert!(!rule.matches(&note("https://example.com/", "Spoilers", "Spoilers", &[])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_domain_mute() -> miette::Result<()> {
+        let rule = OkuMuteRule::from_str("domain:.Example.com")?;
+        assert_eq!(OkuMuteRule::Domain("example.com".to_string()), rule);
+
+        // Domains match themselves and their subdomains
+        assert!(rule.matches(&note("https://example.com/", "", "", &[])));
+        assert!(rule.matches(&note("https://news.EXAMPLE.com/a", "", "", &[])));
+        assert!(!rule.matches(&note("https://notexample.com/", "", "", &[])));
+        assert!(!rule.matches(&note("https://example.org/", "", "example.com", &[])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mute_rule_from_str_invalid() {
+        assert!(OkuMuteRule::from_str("cat").is_err());
+        assert!(OkuMuteRule::from_str("keyword:  ").is_err());
+        assert!(OkuMuteRule::from_str("user:cat").is_err());
+    }
+
+    #[test]
+    fn test_mute_expiry() -> miette::Result<()> {
+        let expired = OkuMute {
+            rule: OkuMuteRule::Keyword("mute expiry test expired".to_string()),
+            expiry: Some(SystemTime::now() - Duration::from_secs(60)),
+        };
+        let unexpired = OkuMute {
+            rule: OkuMuteRule::Keyword("mute expiry test unexpired".to_string()),
+            expiry: Some(SystemTime::now() + Duration::from_secs(3600)),
+        };
+        let permanent = OkuMute {
+            rule: OkuMuteRule::Keyword("mute expiry test permanent".to_string()),
+            expiry: None,
+        };
+        assert!(expired.is_expired());
+        assert!(!unexpired.is_expired());
+        assert!(!permanent.is_expired());
+
+        for mute in [&expired, &unexpired, &permanent] {
+            DATABASE.upsert_mute(mute)?;
+        }
+
+        // Expired mutes no longer apply, but are kept until deleted
+        let rules: Vec<_> = DATABASE.get_mutes()?.into_iter().map(|x| x.rule).collect();
+        assert!(!rules.contains(&expired.rule));
+        assert!(rules.contains(&unexpired.rule));
+        assert!(rules.contains(&permanent.rule));
+        let muted_post = post(
+            &Author::from_bytes(&[30; 32]),
+            "/posts/expiry.toml",
+            note("https://example.com/", "", "Mute expiry test expired", &[]),
+        );
+        assert!(!DATABASE.is_post_muted(&muted_post));
+        assert!(DATABASE
+            .get_all_mutes()?
+            .iter()
+            .any(|x| x.rule == expired.rule));
+
+        // Deleting expired mutes leaves the others
+        let deleted: Vec<_> = DATABASE
+            .delete_expired_mutes()?
+            .into_iter()
+            .map(|x| x.rule)
+            .collect();
+        assert!(deleted.contains(&expired.rule));
+        assert!(!deleted.contains(&unexpired.rule));
+        let rules: Vec<_> = DATABASE
+            .get_all_mutes()?
+            .into_iter()
+            .map(|x| x.rule)
+            .collect();
+        assert!(!rules.contains(&expired.rule));
+        assert!(rules.contains(&unexpired.rule));
+
+        DATABASE.delete_mute(&unexpired.rule)?;
+        DATABASE.delete_mute(&permanent.rule)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mutes_in_search() -> miette::Result<()> {
+        let author = Author::from_bytes(&[31; 32]);
+        let cpp_post = post(
+            &author,
+            "/posts/cpp.toml",
+            note("https://example.com/cpp", "", "I write C++ every day", &[]),
+        );
+        let letter_post = post(
+            &author,
+            "/posts/letter.toml",
+            note("https://example.com/letter", "", "c is a letter", &[]),
+        );
+        let tagged_post = post(
+            &author,
+            "/posts/tagged.toml",
+            note("https://example.org/tagged", "", "Tagged", &["Muted-Tag"]),
+        );
+        DATABASE.upsert_posts(&vec![
+            cpp_post.clone(),
+            letter_post.clone(),
+            tagged_post.clone(),
+        ])?;
+        POST_INDEX_READER
+            .reload()
+            .map_err(|e| miette::miette!("{e}"))?;
+        let search = |mutes: Vec<OkuMuteRule>| -> miette::Result<HashSet<Url>> {
+            Ok(OkuDatabase::search_posts_faceted(
+                &OkuPostSearch {
+                    authors: HashSet::from([author.id()]),
+                    mutes,
+                    ..Default::default()
+                },
+                &HashSet::new(),
+            )?
+            .results
+            .into_iter()
+            .map(|x| x.post.note.url)
+            .collect())
+        };
+        assert_eq!(3, search(Vec::new())?.len());
+
+        // Keywords only hide posts containing them, even if the index splits them into other words
+        assert_eq!(
+            HashSet::from([letter_post.note.url.clone(), tagged_post.note.url.clone()]),
+            search(vec![OkuMuteRule::Keyword("C++".to_string())])?
+        );
+        assert_eq!(
+            vec![cpp_post.note.url.clone()],
+            OkuDatabase::get_muted_posts_since(
+                &None,
+                &HashSet::new(),
+                &[&OkuMuteRule::Keyword("C++".to_string())]
+            )?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| x.entry.author() == author.id())
+            .map(|x| x.note.url)
+            .collect::<Vec<_>>()
+        );
+
+        // Tags and domains are hidden through the index
+        assert_eq!(
+            HashSet::from([cpp_post.note.url.clone(), letter_post.note.url.clone()]),
+            search(vec![OkuMuteRule::Tag("muted-tag".to_string())])?
+        );
+        assert_eq!(
+            HashSet::from([tagged_post.note.url.clone()]),
+            search(vec![OkuMuteRule::Domain("example.com".to_string())])?
+        );
+        assert_eq!(
+            HashSet::from([cpp_post.note.url.clone(), letter_post.note.url.clone()]),
+            search(vec![OkuMuteRule::Regex("^Tag".to_string())])?
+        );
+
+        DATABASE.delete_posts(&[cpp_post, letter_post, tagged_post])?;
+        Ok(())
+    }
+}
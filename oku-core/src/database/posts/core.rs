@@ -1,5 +1,7 @@
 use super::super::core::*;
+use super::super::migrations::OkuPostV2;
 use super::super::users::*;
+use super::urls::normalise_url;
 #[cfg(feature = "persistent")]
 use crate::fs::FS_PATH;
 use iroh_docs::sync::Entry;
@@ -14,6 +16,7 @@ use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Display,
     str::FromStr,
     sync::{Arc, LazyLock},
     time::SystemTime,
@@ -21,7 +24,10 @@ use std::{
 #[cfg(feature = "persistent")]
 use tantivy::{directory::MmapDirectory, Directory};
 use tantivy::{
-    schema::{Field, Schema, Value, FAST, STORED, TEXT},
+    schema::{
+        DateOptions, DateTimePrecision, Facet, FacetOptions, Field, Schema, Value, INDEXED, STORED,
+        STRING, TEXT,
+    },
     Index, IndexReader, IndexWriter, TantivyDocument, Term,
 };
 use tokio::sync::Mutex;
@@ -46,10 +52,41 @@ pub(crate) static POST_SCHEMA: LazyLock<(Schema, HashMap<&str, Field>)> = LazyLo
         ),
         ("body", schema_builder.add_text_field("body", TEXT | STORED)),
         ("tag", schema_builder.add_text_field("tag", TEXT | STORED)),
+        (
+            "mention",
+            schema_builder.add_text_field("mention", TEXT | STORED),
+        ),
+        (
+            "content_warning",
+            schema_builder.add_text_field("content_warning", TEXT | STORED),
+        ),
+        (
+            "sensitive",
+            schema_builder.add_bool_field("sensitive", INDEXED | STORED),
+        ),
+        ("flagged", schema_builder.add_bool_field("flagged", INDEXED)),
         (
             "timestamp",
-            schema_builder.add_date_field("timestamp", FAST),
+            schema_builder.add_date_field(
+                "timestamp",
+                DateOptions::from(INDEXED)
+                    .set_fast()
+                    .set_precision(DateTimePrecision::Microseconds),
+            ),
+        ),
+        (
+            "authors",
+            schema_builder.add_facet_field("authors", FacetOptions::default()),
+        ),
+        (
+            "tags",
+            schema_builder.add_facet_field("tags", FacetOptions::default()),
         ),
+        (
+            "lowercase_tag",
+            schema_builder.add_text_field("lowercase_tag", STRING),
+        ),
+        ("domain", schema_builder.add_text_field("domain", STRING)),
     ]);
     let schema = schema_builder.build();
     (schema, fields)
@@ -62,7 +99,21 @@ pub(crate) static POST_INDEX: LazyLock<Index> = LazyLock::new(|| {
             }
             let mmap_directory: Box<dyn Directory> =
                 Box::new(MmapDirectory::open(&*POST_INDEX_PATH).unwrap());
-            Index::open_or_create(mmap_directory, POST_SCHEMA.0.clone()).unwrap()
+            match Index::open_or_create(mmap_directory, POST_SCHEMA.0.clone()) {
+                Ok(index) => index,
+                Err(e) => {
+                    // The schema has changed; the index is recreated and later rebuilt from the database
+                    error!("{e}");
+                    if let Err(e) = std::fs::remove_dir_all(&*POST_INDEX_PATH)
+                        .and_then(|_| std::fs::create_dir_all(&*POST_INDEX_PATH))
+                    {
+                        error!("{e}");
+                    }
+                    let mmap_directory: Box<dyn Directory> =
+                        Box::new(MmapDirectory::open(&*POST_INDEX_PATH).unwrap());
+                    Index::open_or_create(mmap_directory, POST_SCHEMA.0.clone()).unwrap()
+                }
+            }
         }
         _ => {
             Index::create_in_ram(POST_SCHEMA.0.clone())
@@ -75,9 +126,11 @@ pub(crate) static POST_INDEX_WRITER: LazyLock<Arc<Mutex<IndexWriter>>> =
     LazyLock::new(|| Arc::new(Mutex::new(POST_INDEX.writer(50_000_000).unwrap())));
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[native_model(id = 2, version = 2)]
+#[native_model(id = 2, version = 3, from = OkuPostV2)]
 #[native_db(
-    primary_key(primary_key -> (Vec<u8>, Vec<u8>))
+    primary_key(primary_key -> (Vec<u8>, Vec<u8>)),
+    secondary_key(timestamp -> u64),
+    secondary_key(normalised_url -> String)
 )]
 /// An OkuNet post.
 pub struct OkuPost {
@@ -99,6 +152,24 @@ impl Hash for OkuPost {
     }
 }
 
+/// The domain of a URL and each domain it is a subdomain of, so that posts can be found by any of them.
+///
+/// # Arguments
+///
+/// * `url` - A URL.
+///
+/// # Returns
+///
+/// The lowercased host of the URL, followed by the host with each of its leading labels removed in turn.
+pub(crate) fn domain_suffixes(url: &Url) -> Vec<String> {
+    let Some(host) = url.host_str() else {
+        return Vec::new();
+    };
+    let host = host.to_lowercase();
+    let labels: Vec<_> = host.split('.').collect();
+    (0..labels.len()).map(|i| labels[i..].join(".")).collect()
+}
+
 impl From<OkuPost> for TantivyDocument {
     fn from(value: OkuPost) -> Self {
         let post_key: [Vec<u8>; 2] = value.primary_key().into();
@@ -110,16 +181,33 @@ impl From<OkuPost> for TantivyDocument {
             POST_SCHEMA.1["author_id"],
             crate::fs::util::fmt(value.entry.author()),
         );
+        doc.add_facet(
+            POST_SCHEMA.1["authors"],
+            Facet::from_path([crate::fs::util::fmt(value.entry.author())]),
+        );
         doc.add_text(
             POST_SCHEMA.1["path"],
             String::from_utf8_lossy(value.entry.key()),
         );
         doc.add_text(POST_SCHEMA.1["url"], &value.note.url);
+        for domain in domain_suffixes(&value.note.url) {
+            doc.add_text(POST_SCHEMA.1["domain"], domain);
+        }
+        for mention in value.note.mentions() {
+            doc.add_text(POST_SCHEMA.1["mention"], crate::fs::util::fmt(mention));
+        }
         doc.add_text(POST_SCHEMA.1["title"], value.note.title);
         doc.add_text(POST_SCHEMA.1["body"], value.note.body);
         for tag in value.note.tags {
+            doc.add_facet(POST_SCHEMA.1["tags"], Facet::from_path([&tag]));
+            doc.add_text(POST_SCHEMA.1["lowercase_tag"], tag.to_lowercase());
             doc.add_text(POST_SCHEMA.1["tag"], tag);
         }
+        doc.add_bool(POST_SCHEMA.1["flagged"], value.note.is_flagged());
+        if let Some(content_warning) = value.note.content_warning {
+            doc.add_text(POST_SCHEMA.1["content_warning"], content_warning);
+        }
+        doc.add_bool(POST_SCHEMA.1["sensitive"], value.note.sensitive);
         doc.add_date(
             POST_SCHEMA.1["timestamp"],
             tantivy::DateTime::from_timestamp_micros(value.entry.timestamp() as i64),
@@ -144,6 +232,8 @@ impl TryFrom<TantivyDocument> for OkuPost {
             .ok_or(anyhow::anyhow!("No path for document in index … "))?
             .as_str()
             .ok_or(anyhow::anyhow!("No path for document in index … "))?
+            // Entry keys are null-terminated, but paths are not
+            .trim_end_matches('\0')
             .to_string();
         DATABASE
             .get_post(&author_id, &path.clone().into())
@@ -165,6 +255,22 @@ impl OkuPost {
         )
     }
 
+    pub(crate) fn timestamp(&self) -> u64 {
+        self.entry.timestamp()
+    }
+
+    /// The position of this post in a timeline.
+    pub fn cursor(&self) -> OkuTimelineCursor {
+        OkuTimelineCursor {
+            timestamp: self.entry.timestamp(),
+            key: [self.entry.author().as_bytes().as_slice(), self.entry.key()].concat(),
+        }
+    }
+
+    pub(crate) fn normalised_url(&self) -> String {
+        normalise_url(&self.note.url)
+    }
+
     pub(crate) fn index_term(&self) -> Term {
         let post_key: [Vec<u8>; 2] = self.primary_key().into();
         let post_key_bytes = post_key.concat();
@@ -185,6 +291,47 @@ impl OkuPost {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A position in a timeline; entries are ordered by when they were written, then by key, so that entries written at the same time are neither skipped nor repeated between pages.
+///
+/// Cursors are written as a timestamp and a Base58-encoded key separated by a full stop; a bare timestamp sorts before every entry written at that time.
+pub struct OkuTimelineCursor {
+    /// When the entry at this position was written, in microseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// A key distinguishing the entry from others written at the same time.
+    pub key: Vec<u8>,
+}
+
+impl Display for OkuTimelineCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.key.is_empty() {
+            true => write!(f, "{}", self.timestamp),
+            false => write!(
+                f,
+                "{}.{}",
+                self.timestamp,
+                bs58::encode(&self.key).into_string()
+            ),
+        }
+    }
+}
+
+impl FromStr for OkuTimelineCursor {
+    type Err = miette::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (timestamp, key) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        Ok(Self {
+            timestamp: timestamp
+                .parse()
+                .map_err(|e| miette::miette!("Unexpected timeline cursor {s:?}: {e}"))?,
+            key: bs58::decode(key)
+                .into_vec()
+                .map_err(|e| miette::miette!("Unexpected timeline cursor {s:?}: {e}"))?,
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 /// A note left by an Oku user regarding some URL-addressed content.
 pub struct OkuNote {
@@ -196,10 +343,86 @@ pub struct OkuNote {
     pub body: String,
     /// A list of tags associated with the note.
     pub tags: HashSet<String>,
+    #[serde(default)]
+    /// A warning shown in place of the note's body until the reader chooses to see it.
+    pub content_warning: Option<String>,
+    #[serde(default)]
+    /// Whether the note regards sensitive content.
+    pub sensitive: bool,
+    #[serde(default)]
+    /// Who is able to read the note.
+    pub visibility: OkuVisibility,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+/// Who is able to read an OkuNet post.
+///
+/// Restricted posts are encrypted to the users able to read them at the time of writing; later followers or circle members cannot read them until the post is saved again.
+/// Their paths do not reveal the URLs they regard, though anyone retrieving the author's home replica can still see when each was written, roughly how long it is, and the content authorship IDs of its readers.
+pub enum OkuVisibility {
+    #[default]
+    /// Anyone who retrieves the author's home replica.
+    Public,
+    /// Only the users following the author.
+    Followers,
+    /// Only the members of one of the author's circles, identified by name.
+    Circle(String),
+}
+
+impl Display for OkuVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Public => write!(f, "public"),
+            Self::Followers => write!(f, "followers"),
+            Self::Circle(name) => write!(f, "circle:{name}"),
+        }
+    }
+}
+
+impl FromStr for OkuVisibility {
+    type Err = miette::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().split_once(':') {
+            Some((kind, name)) if kind.trim().eq_ignore_ascii_case("circle") => {
+                match name.trim().is_empty() {
+                    true => Err(miette::miette!("A circle must be named, as in 'circle:…'.")),
+                    false => Ok(Self::Circle(name.trim().to_string())),
+                }
+            }
+            Some(_) => Err(miette::miette!("Unexpected visibility: {s}")),
+            None => match s.trim().to_lowercase().as_str() {
+                "public" => Ok(Self::Public),
+                "followers" => Ok(Self::Followers),
+                _ => Err(miette::miette!(
+                    "Visibility must be 'public', 'followers', or 'circle:…'."
+                )),
+            },
+        }
+    }
 }
 
 impl OkuNote {
-    /// Generate a post path for the note.
+    /// Whether the note should be collapsed by default, having either a content warning or being marked as sensitive.
+    pub fn is_flagged(&self) -> bool {
+        self.sensitive || self.content_warning.is_some()
+    }
+
+    /// The warning to show in place of the note's body, if it should be collapsed by default.
+    pub fn warning(&self) -> Option<String> {
+        match (&self.content_warning, self.sensitive) {
+            (Some(content_warning), _) => Some(content_warning.to_owned()),
+            (None, true) => Some("Sensitive content".to_string()),
+            (None, false) => None,
+        }
+    }
+
+    /// Whether the note can only be read by some users.
+    pub fn is_restricted(&self) -> bool {
+        self.visibility != OkuVisibility::Public
+    }
+
+    /// Generate a post path for the note, were it public.
     pub fn post_path(&self) -> String {
         Self::post_path_from_url(&self.url.to_string())
     }
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::posts::core::OkuVisibility;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_visibility_from_str() -> miette::Result<()> {
+        assert_eq!(OkuVisibility::from_str("public")?, OkuVisibility::Public);
+        assert_eq!(
+            OkuVisibility::from_str(" Followers ")?,
+            OkuVisibility::Followers
+        );
+        assert_eq!(
+            OkuVisibility::from_str("circle:friends")?,
+            OkuVisibility::Circle("friends".to_string())
+        );
+        assert_eq!(
+            OkuVisibility::from_str("CIRCLE: close friends ")?,
+            OkuVisibility::Circle("close friends".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_visibility_from_str_invalid() {
+        assert!(OkuVisibility::from_str("circle:").is_err());
+        assert!(OkuVisibility::from_str("circle:   ").is_err());
+        assert!(OkuVisibility::from_str("group:friends").is_err());
+        assert!(OkuVisibility::from_str("friends").is_err());
+        assert!(OkuVisibility::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_visibility_display_round_trip() -> miette::Result<()> {
+        for visibility in [
+            OkuVisibility::Public,
+            OkuVisibility::Followers,
+            OkuVisibility::Circle("close friends".to_string()),
+        ] {
+            assert_eq!(
+                OkuVisibility::from_str(&visibility.to_string())?,
+                visibility
+            );
+        }
+        Ok(())
+    }
+}
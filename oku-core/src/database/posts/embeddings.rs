@@ -0,0 +1,436 @@
+use super::super::core::*;
+use super::core::OkuPost;
+use crate::fs::net::core::EmbeddingModality;
+#[cfg(feature = "persistent")]
+use crate::fs::FS_PATH;
+use iroh_docs::AuthorId;
+use log::error;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistent")]
+use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock},
+};
+use tantivy::{
+    collector::TopDocs,
+    query::{BooleanQuery, ConstScoreQuery, Occur, Query, TermQuery},
+    schema::{Field, IndexRecordOption, Schema, Value, INDEXED, STORED, STRING},
+    Index, IndexReader, IndexWriter, TantivyDocument, Term,
+};
+#[cfg(feature = "persistent")]
+use tantivy::{directory::MmapDirectory, Directory};
+use tokio::sync::Mutex;
+use url::Url;
+
+/// The number of dimensions of the vectors text is embedded as.
+pub const EMBEDDING_DIMENSIONS: usize = 512;
+
+/// The number of hash tables in the index of post embeddings.
+const VECTOR_INDEX_TABLES: usize = 32;
+
+/// The number of bits in the hashes of each table in the index of post embeddings.
+const VECTOR_INDEX_BITS: usize = 8;
+
+/// The minimum number of posts sharing buckets with a query whose embeddings are compared to it.
+const VECTOR_INDEX_CANDIDATES: usize = 200;
+
+#[cfg(feature = "persistent")]
+pub(crate) static POST_VECTOR_INDEX_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| PathBuf::from(FS_PATH).join("POST_VECTOR_INDEX"));
+pub(crate) static POST_VECTOR_SCHEMA: LazyLock<(Schema, HashMap<&str, Field>)> =
+    LazyLock::new(|| {
+        let mut schema_builder = Schema::builder();
+        let fields = HashMap::from([
+            ("id", schema_builder.add_bytes_field("id", INDEXED | STORED)),
+            ("bucket", schema_builder.add_text_field("bucket", STRING)),
+        ]);
+        let schema = schema_builder.build();
+        (schema, fields)
+    });
+pub(crate) static POST_VECTOR_INDEX: LazyLock<Index> = LazyLock::new(|| {
+    cfg_select! {
+        feature = "persistent" => {
+            if let Err(e) = std::fs::create_dir_all(&*POST_VECTOR_INDEX_PATH) {
+                error!("{e}");
+            }
+            let mmap_directory: Box<dyn Directory> =
+                Box::new(MmapDirectory::open(&*POST_VECTOR_INDEX_PATH).unwrap());
+            Index::open_or_create(mmap_directory, POST_VECTOR_SCHEMA.0.clone()).unwrap()
+        }
+        _ => {
+            Index::create_in_ram(POST_VECTOR_SCHEMA.0.clone())
+        }
+    }
+});
+pub(crate) static POST_VECTOR_INDEX_READER: LazyLock<IndexReader> =
+    LazyLock::new(|| POST_VECTOR_INDEX.reader().unwrap());
+pub(crate) static POST_VECTOR_INDEX_WRITER: LazyLock<Arc<Mutex<IndexWriter>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(POST_VECTOR_INDEX.writer(15_000_000).unwrap())));
+
+/// Random hyperplanes through the origin, grouped into the tables of the index of post embeddings.
+///
+/// The hyperplanes are derived deterministically, so that the buckets of indexed vectors remain valid across restarts.
+static HYPERPLANES: LazyLock<Vec<Vec<f32>>> = LazyLock::new(|| {
+    let mut reader =
+        blake3::Hasher::new_derive_key("oku post embedding hyperplanes").finalize_xof();
+    (0..VECTOR_INDEX_TABLES * VECTOR_INDEX_BITS)
+        .map(|_| {
+            let mut bytes = [0u8; EMBEDDING_DIMENSIONS];
+            reader.fill(&mut bytes);
+            bytes
+                .into_iter()
+                .map(|x| match x & 1 {
+                    0 => 1.0,
+                    _ => -1.0,
+                })
+                .collect()
+        })
+        .collect()
+});
+
+/// Determine the buckets a vector falls in, one in each table of the index of post embeddings.
+///
+/// Vectors with a small angle between them are likely to share many buckets.
+fn vector_buckets(vector: &[f32]) -> Vec<String> {
+    if vector.iter().all(|x| *x == 0.0) {
+        return Vec::new();
+    }
+    HYPERPLANES
+        .chunks(VECTOR_INDEX_BITS)
+        .enumerate()
+        .map(|(table, hyperplanes)| {
+            let hash = hyperplanes
+                .iter()
+                .enumerate()
+                .filter(|(_, hyperplane)| similarity(vector, hyperplane) >= 0.0)
+                .fold(0u32, |hash, (bit, _)| hash | (1 << bit));
+            format!("{table}:{hash}")
+        })
+        .collect()
+}
+
+/// Common words carrying little meaning, ignored when embedding text.
+const STOP_WORDS: [&str; 32] = [
+    "the", "and", "for", "are", "but", "not", "you", "all", "any", "can", "was", "with", "this",
+    "that", "from", "have", "has", "its", "our", "your", "their", "they", "them", "what", "when",
+    "which", "who", "will", "would", "there", "been", "into",
+];
+
+/// Embed text as a vector, such that texts sharing words have similar vectors.
+///
+/// Words and pairs of adjacent words are hashed into the dimensions of the vector, so no model needs to be downloaded or trained.
+///
+/// # Arguments
+///
+/// * `text` - The text to embed.
+///
+/// # Returns
+///
+/// A vector of unit length, or of zero length if the text has no meaningful words.
+pub fn embed_text(text: &str) -> Vec<f32> {
+    let words: Vec<_> = text
+        .split(|x: char| !x.is_alphanumeric())
+        .map(|x| x.to_lowercase())
+        .filter(|x| x.chars().count() > 2 && !STOP_WORDS.contains(&x.as_str()))
+        .collect();
+    let bigrams: Vec<_> = words
+        .windows(2)
+        .map(|x| format!("{} {}", x[0], x[1]))
+        .collect();
+    let mut vector = vec![0f32; EMBEDDING_DIMENSIONS];
+    let features = words
+        .iter()
+        .map(|x| (x, 1.0))
+        .chain(bigrams.iter().map(|x| (x, 0.5)));
+    for (feature, weight) in features {
+        let hash = blake3::hash(feature.as_bytes());
+        let hash_bytes = hash.as_bytes();
+        let mut index_bytes = [0u8; 8];
+        index_bytes.copy_from_slice(&hash_bytes[..8]);
+        let index = (u64::from_le_bytes(index_bytes) % EMBEDDING_DIMENSIONS as u64) as usize;
+        // Collisions between features cancel out rather than accumulate
+        let sign = match hash_bytes[8] & 1 {
+            0 => 1.0,
+            _ => -1.0,
+        };
+        vector[index] += sign * weight;
+    }
+    // Dampen the influence of words repeated many times
+    vector
+        .iter_mut()
+        .for_each(|x| *x = x.signum() * x.abs().ln_1p());
+    normalise(&mut vector);
+    vector
+}
+
+/// Scale a vector to unit length, unless it has zero length.
+fn normalise(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// The cosine similarity of two vectors of unit length.
+fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 10, version = 1)]
+#[native_db(
+    primary_key(primary_key -> (Vec<u8>, Vec<u8>))
+)]
+/// A vector representing the content of an OkuNet post, used to find similar posts.
+pub struct OkuPostEmbedding {
+    /// The content authorship ID of the post's author.
+    pub author_id: AuthorId,
+    /// The key of the post's entry in its author's home replica.
+    pub key: Vec<u8>,
+    /// The modality of the content the vector was created from.
+    pub modality: EmbeddingModality,
+    /// The vector.
+    pub vector: Vec<f32>,
+}
+
+impl OkuPostEmbedding {
+    pub(crate) fn primary_key(&self) -> (Vec<u8>, Vec<u8>) {
+        (self.author_id.as_bytes().to_vec(), self.key.clone())
+    }
+
+    pub(crate) fn index_term(&self) -> Term {
+        Self::index_term_for(&self.primary_key())
+    }
+
+    pub(crate) fn index_term_for(primary_key: &(Vec<u8>, Vec<u8>)) -> Term {
+        Term::from_field_bytes(
+            POST_VECTOR_SCHEMA.1["id"],
+            &[primary_key.0.as_slice(), primary_key.1.as_slice()].concat(),
+        )
+    }
+}
+
+impl From<&OkuPostEmbedding> for TantivyDocument {
+    fn from(value: &OkuPostEmbedding) -> Self {
+        let primary_key = value.primary_key();
+        let mut doc = TantivyDocument::default();
+        doc.add_bytes(
+            POST_VECTOR_SCHEMA.1["id"],
+            &[primary_key.0, primary_key.1].concat(),
+        );
+        for bucket in vector_buckets(&value.vector) {
+            doc.add_text(POST_VECTOR_SCHEMA.1["bucket"], bucket);
+        }
+        doc
+    }
+}
+
+impl From<&OkuPost> for OkuPostEmbedding {
+    fn from(value: &OkuPost) -> Self {
+        Self {
+            author_id: value.entry.author(),
+            key: value.entry.key().to_vec(),
+            modality: EmbeddingModality::Text,
+            vector: value.embedding(),
+        }
+    }
+}
+
+impl OkuPost {
+    /// Embed the text of this post as a vector.
+    ///
+    /// # Returns
+    ///
+    /// A vector representing the post's title, body, and tags.
+    pub fn embedding(&self) -> Vec<f32> {
+        let mut tags: Vec<_> = self.note.tags.iter().cloned().collect();
+        tags.sort_unstable();
+        embed_text(&format!(
+            "{}\n{}\n{}",
+            self.note.title,
+            self.note.body,
+            tags.join(" ")
+        ))
+    }
+}
+
+impl OkuDatabase {
+    /// Retrieves the embeddings of all known OkuNet posts.
+    ///
+    /// # Returns
+    ///
+    /// A list of the embeddings of all known OkuNet posts.
+    pub fn get_post_embeddings(&self) -> miette::Result<Vec<OkuPostEmbedding>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()
+    }
+
+    /// Recomputes the embeddings of all known OkuNet posts.
+    pub fn rebuild_post_embeddings(&self) -> miette::Result<()> {
+        let posts = self.get_posts()?;
+        let embeddings: Vec<OkuPostEmbedding> =
+            posts.par_iter().map(OkuPostEmbedding::from).collect();
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        for embedding in self.get_post_embeddings()? {
+            rw.remove(embedding).into_diagnostic()?;
+        }
+        for embedding in embeddings {
+            rw.insert(embedding).into_diagnostic()?;
+        }
+        rw.commit().into_diagnostic()?;
+        self.rebuild_post_vector_index()
+    }
+
+    /// Rebuilds the index of the embeddings of OkuNet posts from the database.
+    pub fn rebuild_post_vector_index(&self) -> miette::Result<()> {
+        let mut index_writer = lock_index_writer(&POST_VECTOR_INDEX_WRITER);
+        index_writer.delete_all_documents().into_diagnostic()?;
+        self.get_post_embeddings()?
+            .par_iter()
+            .for_each(|embedding| {
+                if let Err(e) = index_writer.add_document(embedding.into()) {
+                    error!("{e}");
+                }
+            });
+        index_writer.commit().into_diagnostic()?;
+        Ok(())
+    }
+
+    /// Add the embeddings of OkuNet posts to the index of embeddings, replacing any earlier embeddings of the same posts.
+    pub(crate) fn index_post_embeddings(embeddings: &[OkuPostEmbedding]) -> miette::Result<()> {
+        let mut index_writer = lock_index_writer(&POST_VECTOR_INDEX_WRITER);
+        embeddings.par_iter().for_each(|embedding| {
+            index_writer.delete_term(embedding.index_term());
+            if let Err(e) = index_writer.add_document(embedding.into()) {
+                error!("{e}");
+            }
+        });
+        index_writer.commit().into_diagnostic()?;
+        Ok(())
+    }
+
+    /// Remove the embeddings of OkuNet posts from the index of embeddings.
+    pub(crate) fn unindex_post_embeddings(posts: &[OkuPost]) -> miette::Result<()> {
+        let mut index_writer = lock_index_writer(&POST_VECTOR_INDEX_WRITER);
+        posts.par_iter().for_each(|post| {
+            index_writer.delete_term(OkuPostEmbedding::index_term_for(&post.primary_key()));
+        });
+        index_writer.commit().into_diagnostic()?;
+        Ok(())
+    }
+
+    /// Find known OkuNet posts similar to a URL or some text, excluding muted posts.
+    ///
+    /// Posts are first narrowed to those whose embeddings share the most buckets with the query in the index of embeddings, then ordered by how similar their embeddings are to the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `url_or_text` - A URL, if it has been posted about, or otherwise some text.
+    ///
+    /// * `k` - The maximum number of posts to find.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be found.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts, ordered from most to least similar; posts about the given URL itself are not included.
+    pub fn similar_posts(
+        &self,
+        url_or_text: &str,
+        k: usize,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<Vec<OkuPost>> {
+        let posts_about_url = match Url::parse(url_or_text.trim()) {
+            Ok(url) => self.get_posts_about(&url, &HashSet::new())?,
+            Err(_) => Vec::new(),
+        };
+        let query = match posts_about_url.is_empty() {
+            true => embed_text(url_or_text),
+            false => {
+                let mut query = vec![0f32; EMBEDDING_DIMENSIONS];
+                for post in posts_about_url.iter() {
+                    for (x, y) in query.iter_mut().zip(post.embedding()) {
+                        *x += y;
+                    }
+                }
+                normalise(&mut query);
+                query
+            }
+        };
+        let excluded_posts: HashSet<_> = posts_about_url.iter().map(|x| x.primary_key()).collect();
+
+        // Each shared bucket counts equally towards a post being a candidate
+        let bucket_query = BooleanQuery::new(
+            vector_buckets(&query)
+                .into_iter()
+                .map(|bucket| {
+                    let term_query: Box<dyn Query> = Box::new(TermQuery::new(
+                        Term::from_field_text(POST_VECTOR_SCHEMA.1["bucket"], &bucket),
+                        IndexRecordOption::Basic,
+                    ));
+                    (
+                        Occur::Should,
+                        Box::new(ConstScoreQuery::new(term_query, 1.0)) as Box<dyn Query>,
+                    )
+                })
+                .collect(),
+        );
+        let searcher = POST_VECTOR_INDEX_READER.searcher();
+        let candidates = searcher
+            .search(
+                &bucket_query,
+                &TopDocs::with_limit((k * 20).max(VECTOR_INDEX_CANDIDATES)).order_by_score(),
+            )
+            .into_diagnostic()?;
+
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let mut scores = Vec::with_capacity(candidates.len());
+        for (_, address) in candidates {
+            let doc: TantivyDocument = searcher.doc(address).into_diagnostic()?;
+            let Some(id) = doc
+                .get_first(POST_VECTOR_SCHEMA.1["id"])
+                .and_then(|x| x.as_bytes())
+                .filter(|x| x.len() > 32)
+            else {
+                continue;
+            };
+            let primary_key = (id[..32].to_vec(), id[32..].to_vec());
+            let embedding: Option<OkuPostEmbedding> =
+                r.get().primary(primary_key).into_diagnostic()?;
+            if let Some(embedding) = embedding.filter(|x| {
+                !excluded_authors.contains(&x.author_id)
+                    && !excluded_posts.contains(&x.primary_key())
+            }) {
+                let score = similarity(&query, &embedding.vector);
+                if score > 0.0 {
+                    scores.push((score, embedding.primary_key()));
+                }
+            }
+        }
+        scores.par_sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut similar_posts = Vec::with_capacity(k);
+        for (_, primary_key) in scores {
+            if similar_posts.len() >= k {
+                break;
+            }
+            let post: Option<OkuPost> = r.get().primary(primary_key).into_diagnostic()?;
+            if let Some(post) = post.filter(|x| !self.is_post_muted(x)) {
+                similar_posts.push(post);
+            }
+        }
+        Ok(similar_posts)
+    }
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::posts::embeddings::{embed_text, EMBEDDING_DIMENSIONS};
+
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn test_embed_text_unit_length() {
+        let vector = embed_text("Peer-to-peer file sharing with Rust");
+        assert_eq!(vector.len(), EMBEDDING_DIMENSIONS);
+        assert!((dot(&vector, &vector) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_embed_text_without_meaningful_words() {
+        for text in ["", "the and for", "a an of to", "!?, …"] {
+            let vector = embed_text(text);
+            assert_eq!(vector.len(), EMBEDDING_DIMENSIONS);
+            assert!(vector.iter().all(|x| *x == 0.0));
+        }
+    }
+
+    #[test]
+    fn test_embed_text_ignores_case_and_punctuation() {
+        assert_eq!(
+            embed_text("Rust programming, explained!"),
+            embed_text("rust PROGRAMMING explained")
+        );
+    }
+
+    #[test]
+    fn test_embed_text_similarity() {
+        let text = embed_text("Distributed file systems replicate data between peers");
+        let similar = embed_text("Peers replicate data in distributed file systems");
+        let unrelated = embed_text("A recipe for baking sourdough bread at home");
+        assert!(dot(&text, &similar) > dot(&text, &unrelated));
+        assert!((dot(&text, &text) - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,162 @@
+use super::super::core::*;
+use super::core::{OkuNote, OkuPost};
+use crate::fs::util::{entry_key_to_path, path_to_entry_key};
+use iroh_docs::sync::Entry;
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[native_model(id = 4, version = 1)]
+#[native_db(
+    primary_key(primary_key -> (Vec<u8>, Vec<u8>, u64)),
+    secondary_key(post_key -> (Vec<u8>, Vec<u8>))
+)]
+/// A version of an OkuNet post that has since been replaced.
+pub struct OkuPostRevision {
+    /// The record of the post file as it was before being replaced.
+    pub entry: Entry,
+    /// The content of the post before being replaced.
+    pub note: OkuNote,
+}
+
+impl OkuPostRevision {
+    pub(crate) fn primary_key(&self) -> (Vec<u8>, Vec<u8>, u64) {
+        (
+            self.entry.author().as_bytes().to_vec(),
+            self.entry.key().to_vec(),
+            self.entry.timestamp(),
+        )
+    }
+
+    pub(crate) fn post_key(&self) -> (Vec<u8>, Vec<u8>) {
+        (
+            self.entry.author().as_bytes().to_vec(),
+            self.entry.key().to_vec(),
+        )
+    }
+}
+
+impl From<OkuPost> for OkuPostRevision {
+    fn from(value: OkuPost) -> Self {
+        Self {
+            entry: value.entry,
+            note: value.note,
+        }
+    }
+}
+
+impl From<OkuPostRevision> for OkuPost {
+    fn from(value: OkuPostRevision) -> Self {
+        Self {
+            entry: value.entry,
+            note: value.note,
+        }
+    }
+}
+
+impl OkuDatabase {
+    /// Records a version of an OkuNet post that is being replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - The version of the post being replaced.
+    ///
+    /// # Returns
+    ///
+    /// The previous record of this version, if one existed.
+    pub fn upsert_post_revision(&self, post: &OkuPost) -> miette::Result<Option<OkuPostRevision>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<OkuPostRevision> = rw
+            .upsert(OkuPostRevision::from(post.to_owned()))
+            .into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        Ok(old_value)
+    }
+
+    /// Retrieves the prior versions of an OkuNet post.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - A content authorship ID.
+    ///
+    /// * `path` - A path to a post in the author's home replica.
+    ///
+    /// # Returns
+    ///
+    /// The recorded prior versions of the post, from newest to oldest.
+    pub fn get_post_revisions(
+        &self,
+        author_id: &AuthorId,
+        path: &PathBuf,
+    ) -> miette::Result<Vec<OkuPost>> {
+        let post_key = (
+            author_id.as_bytes().to_vec(),
+            path_to_entry_key(path).to_vec(),
+        );
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let mut revisions: Vec<OkuPost> = r
+            .scan()
+            .secondary::<OkuPostRevision>(OkuPostRevisionKey::post_key)
+            .into_diagnostic()?
+            .range(post_key.clone()..=post_key)
+            .into_diagnostic()?
+            .map(|x| x.map(OkuPost::from))
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()?;
+        revisions.par_sort_unstable_by_key(|x| std::cmp::Reverse(x.entry.timestamp()));
+        Ok(revisions)
+    }
+
+    /// Determines whether an OkuNet post has been edited since it was first seen by this node.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - An OkuNet post.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not prior versions of the post have been recorded.
+    pub fn is_post_edited(&self, post: &OkuPost) -> bool {
+        let Ok(r) = self.database.r_transaction() else {
+            return false;
+        };
+        let edited = r
+            .scan()
+            .secondary::<OkuPostRevision>(OkuPostRevisionKey::post_key)
+            .ok()
+            .and_then(|scan| {
+                scan.range(post.primary_key()..=post.primary_key())
+                    .ok()
+                    .map(|mut x| x.next().is_some())
+            })
+            .unwrap_or(false);
+        edited
+    }
+
+    /// Deletes the recorded prior versions of an OkuNet post.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - An OkuNet post.
+    ///
+    /// # Returns
+    ///
+    /// The deleted prior versions of the post.
+    pub fn delete_post_revisions(&self, post: &OkuPost) -> miette::Result<Vec<OkuPost>> {
+        let revisions =
+            self.get_post_revisions(&post.entry.author(), &entry_key_to_path(post.entry.key())?)?;
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let removed_revisions: Vec<_> = revisions
+            .into_iter()
+            .filter_map(|revision| rw.remove(OkuPostRevision::from(revision)).ok())
+            .map(OkuPost::from)
+            .collect();
+        rw.commit().into_diagnostic()?;
+        Ok(removed_revisions)
+    }
+}
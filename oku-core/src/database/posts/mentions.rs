@@ -0,0 +1,135 @@
+use super::super::core::*;
+use super::core::{OkuNote, OkuPost};
+use iroh_docs::AuthorId;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use regex::{Captures, Regex};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+/// Mentions as stored in a note's body: an `@` followed by a content authorship ID.
+static MENTION_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"@([a-z2-7]{52})\b").unwrap());
+
+impl OkuNote {
+    /// The users mentioned in the note's body.
+    pub fn mentions(&self) -> HashSet<AuthorId> {
+        MENTION_REGEX
+            .captures_iter(&self.body)
+            .filter_map(|x| AuthorId::from_str(&x[1]).ok())
+            .collect()
+    }
+
+    /// Rewrite the mentions in the note's body.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacement` - A function giving the text to replace a mention of a user with.
+    ///
+    /// # Returns
+    ///
+    /// The note's body, with each mention replaced.
+    pub fn replace_mentions(&self, replacement: impl Fn(&AuthorId) -> String) -> String {
+        MENTION_REGEX
+            .replace_all(&self.body, |captures: &Captures| {
+                match AuthorId::from_str(&captures[1]) {
+                    Ok(author_id) => replacement(&author_id),
+                    Err(_) => captures[0].to_string(),
+                }
+            })
+            .to_string()
+    }
+}
+
+impl OkuDatabase {
+    /// Rewrite mentions of users by name as mentions by content authorship ID.
+    ///
+    /// A mention is an `@` followed by a petname or a known user's display name (case-insensitive).
+    /// Petnames take precedence over display names, and display names shared by several users are left as written.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The body of a note.
+    ///
+    /// # Returns
+    ///
+    /// The body of the note, with mentions stored canonically.
+    pub fn resolve_mentions(&self, body: &str) -> miette::Result<String> {
+        let mut names: HashMap<String, HashSet<AuthorId>> = HashMap::new();
+        for user in self.get_users()? {
+            if let Some(identity) = user.identity {
+                let name = identity.name.trim().to_lowercase();
+                if !name.is_empty() {
+                    names.entry(name).or_default().insert(user.author_id);
+                }
+            }
+        }
+        let petnames: HashMap<String, AuthorId> = self
+            .get_contacts()?
+            .into_iter()
+            .filter_map(|x| {
+                x.petname
+                    .map(|y| (y.trim().to_lowercase(), x.author_id))
+                    .filter(|y| !y.0.is_empty())
+            })
+            .collect();
+        let mut names: Vec<_> = names
+            .into_iter()
+            .filter(|x| x.1.len() == 1 && !petnames.contains_key(&x.0))
+            .filter_map(|(name, author_ids)| author_ids.into_iter().next().map(|x| (name, x)))
+            .chain(petnames)
+            .collect();
+        // Prefer the longest name, so a user named 'Ann' does not take a mention of 'Anne'
+        names.sort_unstable_by_key(|x| std::cmp::Reverse(x.0.len()));
+
+        let mut resolved = String::with_capacity(body.len());
+        let mut rest = body;
+        while let Some(index) = rest.find('@') {
+            resolved.push_str(&rest[..index]);
+            let mention = &rest[index + 1..];
+            rest = mention;
+            // Avoid treating e-mail addresses as mentions
+            let is_word_start = resolved
+                .chars()
+                .next_back()
+                .is_none_or(|x| !x.is_alphanumeric());
+            let matched = names.iter().find(|(name, _)| {
+                mention
+                    .get(..name.len())
+                    .is_some_and(|x| x.to_lowercase() == *name)
+                    && mention[name.len()..]
+                        .chars()
+                        .next()
+                        .is_none_or(|x| !x.is_alphanumeric() && x != '_')
+            });
+            match matched {
+                Some((name, author_id)) if is_word_start => {
+                    resolved.push('@');
+                    resolved.push_str(&crate::fs::util::fmt(author_id));
+                    rest = &mention[name.len()..];
+                }
+                _ => resolved.push('@'),
+            }
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+
+    /// Retrieves all known OkuNet posts mentioning a given user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - A content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// A list of all known OkuNet posts mentioning the given user.
+    pub fn get_posts_mentioning(&self, author_id: &AuthorId) -> miette::Result<Vec<OkuPost>> {
+        Ok(self
+            .get_posts()?
+            .into_par_iter()
+            .filter(|x| x.note.mentions().contains(author_id))
+            .collect())
+    }
+}
@@ -1,4 +1,25 @@
 /// Definition of an OkuNet post.
 pub mod core;
+/// Vector embeddings of OkuNet posts, used to find similar posts.
+pub mod embeddings;
+/// Prior versions of OkuNet posts.
+pub mod history;
+/// Mentions of OkuNet users in posts.
+pub mod mentions;
 /// Implementation of OkuNet post-related database operations.
 pub mod operations;
+/// Faceted search of OkuNet posts.
+pub mod search;
+/// Statistics of OkuNet activity over windows of time.
+pub mod statistics;
+/// Atom and JSON Feed documents of OkuNet posts, for reading in feed readers.
+pub mod syndication;
+/// Lookup of OkuNet posts by the URL they regard.
+pub mod urls;
+
+mod core_test;
+mod embeddings_test;
+mod operations_test;
+mod statistics_test;
+mod syndication_test;
+mod urls_test;
@@ -1,21 +1,86 @@
 use super::super::core::*;
 use super::core::OkuPost;
-use super::core::POST_INDEX;
-use super::core::POST_INDEX_READER;
+use super::core::OkuPostKey;
+use super::core::OkuTimelineCursor;
 use super::core::POST_INDEX_WRITER;
-use super::core::POST_SCHEMA;
+use super::embeddings::OkuPostEmbedding;
+use super::history::OkuPostRevision;
+use super::search::OkuPostSearch;
 use crate::fs::util::path_to_entry_key;
 use iroh_docs::AuthorId;
 use log::error;
 use miette::IntoDiagnostic;
 use native_db::*;
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
-use std::{collections::HashSet, path::PathBuf};
-use tantivy::{collector::TopDocs, query::QueryParser, TantivyDocument};
+use std::{cmp::Reverse, collections::HashSet, path::PathBuf};
 
 impl OkuDatabase {
+    /// Retrieves a page of known OkuNet posts, newest first, excluding muted posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - A cursor; if specified, only posts positioned before it in the timeline are retrieved.
+    ///
+    /// * `page_size` - The maximum number of posts to retrieve.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts, ordered from newest to oldest.
+    pub fn get_timeline(
+        &self,
+        before: &Option<OkuTimelineCursor>,
+        page_size: usize,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<Vec<OkuPost>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let scan = r
+            .scan()
+            .secondary::<OkuPost>(OkuPostKey::timestamp)
+            .into_diagnostic()?;
+        let posts: Box<dyn Iterator<Item = OkuPost> + '_> = match before {
+            Some(before) => Box::new(
+                scan.range(..=before.timestamp)
+                    .into_diagnostic()?
+                    .rev()
+                    .filter_map(|x| x.ok())
+                    .filter(move |x| x.cursor() < *before),
+            ),
+            None => Box::new(scan.all().into_diagnostic()?.rev().filter_map(|x| x.ok())),
+        };
+        let mut candidates = posts
+            .filter(|x| !excluded_authors.contains(&x.entry.author()))
+            .peekable();
+        let mut timeline = Vec::with_capacity(page_size);
+        // Muted posts are filtered out a page at a time, so that the page is filled if possible
+        while timeline.len() < page_size {
+            let page: Vec<_> = candidates
+                .by_ref()
+                .take(page_size - timeline.len())
+                .collect();
+            if page.is_empty() {
+                break;
+            }
+            timeline.extend(self.filter_muted_posts(page));
+        }
+        // Posts written at the same time are not read in order of their keys, so all of those written when the last post was are considered
+        if let Some(last_timestamp) = timeline.last().map(|x: &OkuPost| x.entry.timestamp()) {
+            let ties: Vec<_> = std::iter::from_fn(|| {
+                candidates.next_if(|x| x.entry.timestamp() == last_timestamp)
+            })
+            .collect();
+            timeline.extend(self.filter_muted_posts(ties));
+        }
+        timeline.sort_unstable_by_key(|x| Reverse(x.cursor()));
+        timeline.truncate(page_size);
+        Ok(timeline)
+    }
+
     /// Search OkuNet posts with a query string.
     ///
+    /// Posts mentioning a user can be found with a query of the form `mention:<author ID>`.
+    ///
     /// # Arguments
     ///
     /// * `query_string` - The string used to query for posts.
@@ -24,34 +89,37 @@ impl OkuDatabase {
     ///
     /// # Returns
     ///
-    /// A list of OkuNet posts.
+    /// A list of OkuNet posts, excluding muted posts.
     pub fn search_posts(
         query_string: &str,
         result_limit: &Option<usize>,
     ) -> miette::Result<Vec<OkuPost>> {
-        let searcher = POST_INDEX_READER.searcher();
-        let query_parser = QueryParser::for_index(
-            &POST_INDEX,
-            vec![
-                POST_SCHEMA.1["author_id"],
-                POST_SCHEMA.1["path"],
-                POST_SCHEMA.1["title"],
-                POST_SCHEMA.1["body"],
-                POST_SCHEMA.1["tag"],
-            ],
-        );
-        let query = query_parser.parse_query(query_string).into_diagnostic()?;
-        let limit = result_limit.unwrap_or(10);
-        let top_docs = searcher
-            .search(&query, &TopDocs::with_limit(limit).order_by_score())
-            .into_diagnostic()?;
-        Ok(top_docs
-            .par_iter()
-            .filter_map(|x| searcher.doc(x.1).ok())
-            .collect::<Vec<TantivyDocument>>()
-            .into_par_iter()
-            .filter_map(|x| TryInto::try_into(x).ok())
-            .collect())
+        // Mutes are applied while searching, so that muted posts do not take up places in the results
+        Ok(Self::search_posts_faceted(
+            &OkuPostSearch {
+                query: query_string.to_string(),
+                limit: result_limit.unwrap_or(10),
+                ..Default::default()
+            },
+            &HashSet::new(),
+        )?
+        .results
+        .into_iter()
+        .map(|x| x.post)
+        .collect())
+    }
+
+    /// Rebuilds the search index of OkuNet posts from the database.
+    pub fn rebuild_post_index(&self) -> miette::Result<()> {
+        let mut index_writer = lock_index_writer(&POST_INDEX_WRITER);
+        index_writer.delete_all_documents().into_diagnostic()?;
+        self.get_posts()?.into_par_iter().for_each(|post| {
+            if let Err(e) = index_writer.add_document(post.into()) {
+                error!("{e}");
+            }
+        });
+        index_writer.commit().into_diagnostic()?;
+        Ok(())
     }
 
     /// Insert or update an OkuNet post.
@@ -67,12 +135,21 @@ impl OkuDatabase {
         let rw: transaction::RwTransaction<'_> =
             self.database.rw_transaction().into_diagnostic()?;
         let old_value: Option<OkuPost> = rw.upsert(post.clone()).into_diagnostic()?;
+        if let Some(old_post) = &old_value {
+            Self::update_post_activity(&rw, old_post, false)?;
+        }
+        Self::update_post_activity(&rw, post, true)?;
+        if let Some(old_post) = old_value.clone() {
+            if old_post.entry.content_hash() != post.entry.content_hash() {
+                rw.upsert(OkuPostRevision::from(old_post))
+                    .into_diagnostic()?;
+            }
+        }
+        let embedding = OkuPostEmbedding::from(post);
+        rw.upsert(embedding.clone()).into_diagnostic()?;
         rw.commit().into_diagnostic()?;
 
-        let mut index_writer = POST_INDEX_WRITER
-            .clone()
-            .try_lock_owned()
-            .into_diagnostic()?;
+        let mut index_writer = lock_index_writer(&POST_INDEX_WRITER);
         if let Some(old_post) = old_value.clone() {
             index_writer.delete_term(old_post.index_term());
         }
@@ -80,6 +157,7 @@ impl OkuDatabase {
             .add_document(post.to_owned().into())
             .into_diagnostic()?;
         index_writer.commit().into_diagnostic()?;
+        Self::index_post_embeddings(&[embedding])?;
 
         Ok(old_value)
     }
@@ -95,17 +173,39 @@ impl OkuDatabase {
     /// A list containing the previous version of each post, if one existed.
     pub fn upsert_posts(&self, posts: &Vec<OkuPost>) -> miette::Result<Vec<Option<OkuPost>>> {
         let rw = self.database.rw_transaction().into_diagnostic()?;
+        let embeddings: Vec<OkuPostEmbedding> =
+            posts.par_iter().map(OkuPostEmbedding::from).collect();
         let old_posts: Vec<_> = posts
             .clone()
             .into_iter()
-            .filter_map(|post| rw.upsert(post).ok())
+            .filter_map(|post| {
+                let old_post: Option<OkuPost> = rw.upsert(post.clone()).ok()?;
+                if let Some(old_post) = &old_post {
+                    if let Err(e) = Self::update_post_activity(&rw, old_post, false) {
+                        error!("{e}");
+                    }
+                }
+                if let Err(e) = Self::update_post_activity(&rw, &post, true) {
+                    error!("{e}");
+                }
+                if let Some(old_post) = old_post.clone() {
+                    if old_post.entry.content_hash() != post.entry.content_hash() {
+                        if let Err(e) = rw.upsert(OkuPostRevision::from(old_post)) {
+                            error!("{e}");
+                        }
+                    }
+                }
+                Some(old_post)
+            })
             .collect();
+        for embedding in embeddings.iter() {
+            if let Err(e) = rw.upsert(embedding.clone()) {
+                error!("{e}");
+            }
+        }
         rw.commit().into_diagnostic()?;
 
-        let mut index_writer = POST_INDEX_WRITER
-            .clone()
-            .try_lock_owned()
-            .into_diagnostic()?;
+        let mut index_writer = lock_index_writer(&POST_INDEX_WRITER);
         old_posts.par_iter().for_each(|old_post| {
             if let Some(old_post) = old_post {
                 index_writer.delete_term(old_post.index_term());
@@ -117,6 +217,7 @@ impl OkuDatabase {
             }
         });
         index_writer.commit().into_diagnostic()?;
+        Self::index_post_embeddings(&embeddings)?;
 
         Ok(old_posts)
     }
@@ -129,18 +230,23 @@ impl OkuDatabase {
     ///
     /// # Returns
     ///
-    /// The deleted post.
+    /// The deleted post; any recorded prior versions of it are also deleted.
     pub fn delete_post(&self, post: &OkuPost) -> miette::Result<OkuPost> {
         let rw = self.database.rw_transaction().into_diagnostic()?;
         let removed_post = rw.remove(post.to_owned()).into_diagnostic()?;
+        Self::update_post_activity(&rw, &removed_post, false)?;
+        let embedding: Option<OkuPostEmbedding> =
+            rw.get().primary(post.primary_key()).into_diagnostic()?;
+        if let Some(embedding) = embedding {
+            rw.remove(embedding).into_diagnostic()?;
+        }
         rw.commit().into_diagnostic()?;
 
-        let mut index_writer = POST_INDEX_WRITER
-            .clone()
-            .try_lock_owned()
-            .into_diagnostic()?;
+        let mut index_writer = lock_index_writer(&POST_INDEX_WRITER);
         index_writer.delete_term(removed_post.index_term());
         index_writer.commit().into_diagnostic()?;
+        Self::unindex_post_embeddings(std::slice::from_ref(&removed_post))?;
+        self.delete_post_revisions(&removed_post)?;
 
         Ok(removed_post)
     }
@@ -160,16 +266,27 @@ impl OkuDatabase {
             .iter()
             .filter_map(|post| rw.remove(post.to_owned()).ok())
             .collect();
+        for removed_post in removed_posts.iter() {
+            Self::update_post_activity(&rw, removed_post, false)?;
+            let embedding: Option<OkuPostEmbedding> = rw
+                .get()
+                .primary(removed_post.primary_key())
+                .into_diagnostic()?;
+            if let Some(embedding) = embedding {
+                rw.remove(embedding).into_diagnostic()?;
+            }
+        }
         rw.commit().into_diagnostic()?;
 
-        let mut index_writer = POST_INDEX_WRITER
-            .clone()
-            .try_lock_owned()
-            .into_diagnostic()?;
+        let mut index_writer = lock_index_writer(&POST_INDEX_WRITER);
         removed_posts.par_iter().for_each(|removed_post| {
             index_writer.delete_term(removed_post.index_term());
         });
         index_writer.commit().into_diagnostic()?;
+        Self::unindex_post_embeddings(&removed_posts)?;
+        for removed_post in removed_posts.iter() {
+            self.delete_post_revisions(removed_post)?;
+        }
 
         Ok(removed_posts)
     }
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::core::DATABASE;
+    use crate::database::posts::core::{OkuNote, OkuPost, OkuTimelineCursor, OkuVisibility};
+    use crate::fs::util::path_to_entry_key;
+    use iroh_blobs::Hash;
+    use iroh_docs::sync::{Entry, Record, RecordIdentifier};
+    use iroh_docs::{Author, NamespaceSecret};
+    use std::{cmp::Reverse, collections::HashSet, path::PathBuf};
+    use url::Url;
+
+    fn post(author: &Author, path: &str, timestamp: u64) -> OkuPost {
+        OkuPost {
+            entry: Entry::new(
+                RecordIdentifier::new(
+                    NamespaceSecret::from_bytes(&[0; 32]).id(),
+                    author.id(),
+                    path_to_entry_key(&PathBuf::from(path)),
+                ),
+                Record::new(Hash::new(path), 0, timestamp),
+            ),
+            note: OkuNote {
+                url: Url::parse(&format!("https://example.com{path}")).unwrap(),
+                title: String::new(),
+                body: "Timeline test".to_string(),
+                tags: HashSet::new(),
+                content_warning: None,
+                sensitive: false,
+                visibility: OkuVisibility::Public,
+            },
+        }
+    }
+
+    #[test]
+    fn test_timeline_same_timestamp() -> miette::Result<()> {
+        // Posts are written long before those of other tests, so that only these are in the timeline before the start
+        let authors = [Author::from_bytes(&[50; 32]), Author::from_bytes(&[51; 32])];
+        let mut posts: Vec<_> = (0..7)
+            .map(|i| post(&authors[i % 2], &format!("/posts/timeline-{i}.toml"), 1_000))
+            .collect();
+        posts.push(post(&authors[0], "/posts/timeline-older.toml", 999));
+        DATABASE.upsert_posts(&posts)?;
+        let start = OkuTimelineCursor {
+            timestamp: 1_001,
+            key: Vec::new(),
+        };
+
+        // Pages smaller than the number of posts written at once neither skip nor repeat any
+        let mut timeline = Vec::new();
+        let mut before = Some(start.clone());
+        loop {
+            let page = DATABASE.get_timeline(&before, 3, &HashSet::new())?;
+            assert!(page.len() <= 3);
+            match page.last() {
+                Some(last) => before = Some(last.cursor()),
+                None => break,
+            }
+            timeline.extend(page);
+        }
+        let mut expected = posts.clone();
+        expected.sort_unstable_by_key(|x| Reverse(x.cursor()));
+        assert_eq!(
+            expected.iter().map(|x| x.cursor()).collect::<Vec<_>>(),
+            timeline.iter().map(|x| x.cursor()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            999,
+            timeline.last().map(|x| x.timestamp()).unwrap_or_default()
+        );
+
+        // Excluded authors do not take up places in a page
+        let page =
+            DATABASE.get_timeline(&Some(start.clone()), 3, &HashSet::from([authors[1].id()]))?;
+        assert_eq!(3, page.len());
+        assert!(page.iter().all(|x| x.entry.author() == authors[0].id()));
+
+        // A cursor can be written and read back
+        let cursor = timeline[3].cursor();
+        assert_eq!(cursor, cursor.to_string().parse::<OkuTimelineCursor>()?);
+        assert_eq!(start, start.to_string().parse::<OkuTimelineCursor>()?);
+        assert_eq!(
+            timeline[4..].iter().map(|x| x.cursor()).collect::<Vec<_>>(),
+            DATABASE
+                .get_timeline(
+                    &Some(cursor.to_string().parse::<OkuTimelineCursor>()?),
+                    10,
+                    &HashSet::new()
+                )?
+                .iter()
+                .map(|x| x.cursor())
+                .collect::<Vec<_>>()
+        );
+
+        DATABASE.delete_posts(&posts)?;
+        Ok(())
+    }
+}
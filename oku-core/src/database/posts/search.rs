@@ -0,0 +1,526 @@
+use super::super::core::*;
+use super::core::{OkuPost, POST_INDEX, POST_INDEX_READER, POST_SCHEMA};
+use crate::database::mutes::{MuteMatcher, OkuMuteRule};
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+    collections::HashSet,
+    ops::{Bound, Range},
+    str::FromStr,
+};
+use tantivy::{
+    collector::{Count, DocSetCollector, FacetCollector, FacetCounts, TopDocs},
+    query::{
+        AllQuery, BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, RangeQuery, TermQuery,
+    },
+    schema::{Facet, IndexRecordOption},
+    snippet::SnippetGenerator,
+    DocAddress, Order, Searcher, TantivyDocument, Term,
+};
+
+/// The default number of results in a page of search results.
+pub const DEFAULT_SEARCH_PAGE_SIZE: usize = 10;
+
+/// The maximum length of a snippet of a post's body shown in search results.
+const SNIPPET_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A search of OkuNet posts, with filters and pagination.
+pub struct OkuPostSearch {
+    /// The query string; if empty, all posts matching the filters are found.
+    pub query: String,
+    /// If not empty, only posts by these users are found.
+    pub authors: HashSet<AuthorId>,
+    /// If not empty, only posts with all of these tags are found.
+    pub tags: HashSet<String>,
+    /// If specified, only posts about URLs on this domain, or its subdomains, are found.
+    pub domain: Option<String>,
+    /// If specified, only posts written at or after this time (in microseconds since the Unix epoch) are found.
+    pub after: Option<u64>,
+    /// If specified, only posts written before this time (in microseconds since the Unix epoch) are found.
+    pub before: Option<u64>,
+    /// Posts matching any of these rules are not found, in addition to those muted on this node.
+    pub mutes: Vec<OkuMuteRule>,
+    /// Whether posts with a content warning, or marked as sensitive, are not found.
+    pub hide_flagged: bool,
+    /// Whether results should be ordered from newest to oldest, rather than by relevance.
+    pub newest_first: bool,
+    /// The number of results to skip.
+    pub offset: usize,
+    /// The maximum number of results to retrieve.
+    pub limit: usize,
+}
+
+impl Default for OkuPostSearch {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            authors: HashSet::new(),
+            tags: HashSet::new(),
+            domain: None,
+            after: None,
+            before: None,
+            mutes: Vec::new(),
+            hide_flagged: false,
+            newest_first: false,
+            offset: 0,
+            limit: DEFAULT_SEARCH_PAGE_SIZE,
+        }
+    }
+}
+
+impl OkuPostSearch {
+    /// Determine whether a post passes this search's filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - An OkuNet post.
+    ///
+    /// # Returns
+    ///
+    /// Whether the post is by one of the searched authors, has all searched tags, is about a URL on the searched domain, was written in the searched period, is not hidden by the search's mute rules, and is not hidden for having a content warning or being marked as sensitive.
+    pub fn matches(&self, post: &OkuPost) -> bool {
+        let timestamp = post.entry.timestamp();
+        (self.authors.is_empty() || self.authors.contains(&post.entry.author()))
+            && self.tags.is_subset(&post.note.tags)
+            && self.domain.as_ref().is_none_or(|domain| {
+                let domain = domain.trim_start_matches("www.").to_lowercase();
+                post.note
+                    .url
+                    .domain()
+                    .map(|x| x.trim_start_matches("www.").to_lowercase())
+                    .is_some_and(|x| x == domain || x.ends_with(&format!(".{domain}")))
+            })
+            && self.after.is_none_or(|x| timestamp >= x)
+            && self.before.is_none_or(|x| timestamp < x)
+            && !self.mutes.iter().any(|x| x.matches(&post.note))
+            && !(self.hide_flagged && post.note.is_flagged())
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A post found by a search.
+pub struct OkuPostSearchResult {
+    /// The post.
+    pub post: OkuPost,
+    /// How relevant the post is to the query.
+    pub score: f32,
+    /// An excerpt of the post's body.
+    pub snippet: String,
+    /// The ranges of the excerpt matching the query.
+    pub highlights: Vec<Range<usize>>,
+    /// The excerpt of the post's body as HTML, with the ranges matching the query in bold.
+    pub snippet_html: String,
+}
+
+#[derive(Debug, Clone, Default)]
+/// A page of posts found by a search, with counts of the tags and authors of all posts found.
+pub struct OkuPostSearchResults {
+    /// The posts in this page of results.
+    pub results: Vec<OkuPostSearchResult>,
+    /// The total number of posts found.
+    pub total: usize,
+    /// The tags of the posts found, and how many posts have each, from most to least common.
+    pub tag_counts: Vec<(String, usize)>,
+    /// The authors of the posts found, and how many posts each wrote, from most to least prolific.
+    pub author_counts: Vec<(AuthorId, usize)>,
+}
+
+/// A query finding the posts by a user.
+fn author_query(author_id: &AuthorId) -> Box<dyn Query> {
+    Box::new(TermQuery::new(
+        Term::from_facet(
+            POST_SCHEMA.1["authors"],
+            &Facet::from_path([crate::fs::util::fmt(author_id)]),
+        ),
+        IndexRecordOption::Basic,
+    ))
+}
+
+/// A query finding the posts which may be hidden by a mute rule, if the rule can be expressed as one.
+///
+/// The index splits keywords into words and drops their punctuation, so the posts found by a keyword's query must still be checked against the rule.
+fn mute_query(rule: &OkuMuteRule) -> Option<Box<dyn Query>> {
+    match rule {
+        // Keywords are searched for as phrases, so that they match whole words
+        OkuMuteRule::Keyword(keyword) => QueryParser::for_index(
+            &POST_INDEX,
+            vec![
+                POST_SCHEMA.1["title"],
+                POST_SCHEMA.1["body"],
+                POST_SCHEMA.1["content_warning"],
+            ],
+        )
+        .parse_query(&format!("\"{}\"", keyword.replace(['"', '\\'], " ")))
+        .ok(),
+        OkuMuteRule::Regex(_) => None,
+        OkuMuteRule::Tag(tag) => Some(Box::new(TermQuery::new(
+            Term::from_field_text(POST_SCHEMA.1["lowercase_tag"], &tag.to_lowercase()),
+            IndexRecordOption::Basic,
+        ))),
+        OkuMuteRule::Domain(domain) => Some(Box::new(TermQuery::new(
+            Term::from_field_text(POST_SCHEMA.1["domain"], &domain.to_lowercase()),
+            IndexRecordOption::Basic,
+        ))),
+    }
+}
+
+/// A query finding exactly the posts hidden by a mute rule, if the rule can be expressed as one.
+fn exact_mute_query(rule: &OkuMuteRule) -> Option<Box<dyn Query>> {
+    match rule {
+        OkuMuteRule::Keyword(_) | OkuMuteRule::Regex(_) => None,
+        OkuMuteRule::Tag(_) | OkuMuteRule::Domain(_) => mute_query(rule),
+    }
+}
+
+/// Counts the posts found under each child of the root facet, from most to least common.
+fn facet_counts(facet_counts: &FacetCounts) -> Vec<(String, usize)> {
+    let mut counts: Vec<_> = facet_counts
+        .get("/")
+        .filter_map(|(facet, count)| {
+            facet
+                .to_path()
+                .last()
+                .map(|x| (x.to_string(), count as usize))
+        })
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts
+}
+
+impl OkuPostSearch {
+    /// Expresses this search's filters as a query, so that only matching posts are retrieved from the index.
+    fn filter_query(
+        &self,
+        query: &dyn Query,
+        excluded_authors: &HashSet<AuthorId>,
+        mutes: &[&OkuMuteRule],
+    ) -> BooleanQuery {
+        // Filters do not contribute to how relevant a post is
+        let filter =
+            |x: Box<dyn Query>| -> Box<dyn Query> { Box::new(ConstScoreQuery::new(x, 0.0)) };
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, query.box_clone())];
+        if !self.authors.is_empty() {
+            clauses.push((
+                Occur::Must,
+                filter(Box::new(BooleanQuery::new(
+                    self.authors
+                        .iter()
+                        .map(|x| (Occur::Should, author_query(x)))
+                        .collect(),
+                ))),
+            ));
+        }
+        for tag in self.tags.iter() {
+            clauses.push((
+                Occur::Must,
+                filter(Box::new(TermQuery::new(
+                    Term::from_facet(POST_SCHEMA.1["tags"], &Facet::from_path([tag])),
+                    IndexRecordOption::Basic,
+                ))),
+            ));
+        }
+        if let Some(domain) = &self.domain {
+            clauses.push((
+                Occur::Must,
+                filter(Box::new(TermQuery::new(
+                    Term::from_field_text(
+                        POST_SCHEMA.1["domain"],
+                        &domain.trim_start_matches("www.").to_lowercase(),
+                    ),
+                    IndexRecordOption::Basic,
+                ))),
+            ));
+        }
+        if self.after.is_some() || self.before.is_some() {
+            let timestamp_term = |x: u64| {
+                Term::from_field_date(
+                    POST_SCHEMA.1["timestamp"],
+                    tantivy::DateTime::from_timestamp_micros(x as i64),
+                )
+            };
+            clauses.push((
+                Occur::Must,
+                filter(Box::new(RangeQuery::new(
+                    self.after
+                        .map_or(Bound::Unbounded, |x| Bound::Included(timestamp_term(x))),
+                    self.before
+                        .map_or(Bound::Unbounded, |x| Bound::Excluded(timestamp_term(x))),
+                ))),
+            ));
+        }
+        if self.hide_flagged {
+            clauses.push((
+                Occur::MustNot,
+                Box::new(TermQuery::new(
+                    Term::from_field_bool(POST_SCHEMA.1["flagged"], true),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+        for author_id in excluded_authors {
+            clauses.push((Occur::MustNot, author_query(author_id)));
+        }
+        for mute in mutes.iter().filter_map(|x| exact_mute_query(x)) {
+            clauses.push((Occur::MustNot, mute));
+        }
+        BooleanQuery::new(clauses)
+    }
+}
+
+impl OkuDatabase {
+    /// Search OkuNet posts, with filters, pagination, counts of tags and authors, and highlighted snippets.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The query, filters, and page of results to retrieve.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be found.
+    ///
+    /// # Returns
+    ///
+    /// A page of OkuNet posts, excluding muted posts, with counts of the tags and authors of all posts found.
+    /// Posts hidden by keyword or regular expression mutes are left out of the page, but cannot be left out of the counts.
+    pub fn search_posts_faceted(
+        search: &OkuPostSearch,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<OkuPostSearchResults> {
+        let searcher = POST_INDEX_READER.searcher();
+        let (query, filter_query, unindexed_mutes) =
+            Self::post_search_queries(search, excluded_authors)?;
+
+        let mut tag_collector = FacetCollector::for_field("tags");
+        tag_collector.add_facet(Facet::root());
+        let mut author_collector = FacetCollector::for_field("authors");
+        author_collector.add_facet(Facet::root());
+        let (total, tag_facets, author_facets) = searcher
+            .search(&filter_query, &(Count, tag_collector, author_collector))
+            .into_diagnostic()?;
+        let tag_counts = facet_counts(&tag_facets);
+        let author_counts = facet_counts(&author_facets)
+            .into_iter()
+            .filter_map(|(author_id, count)| {
+                AuthorId::from_str(&author_id)
+                    .ok()
+                    .map(|author_id| (author_id, count))
+            })
+            .collect();
+
+        // Only the posts up to the end of the page are retrieved, unless some are muted
+        let wanted = search.offset + search.limit;
+        let mut retrieved = wanted.max(1);
+        let page = loop {
+            let top_docs = Self::top_post_docs(&searcher, &filter_query, search, retrieved)?;
+            let exhausted = top_docs.len() < retrieved;
+            let skip = match unindexed_mutes.is_empty() {
+                true => search.offset,
+                false => 0,
+            };
+            let found: Vec<(Option<f32>, DocAddress, OkuPost)> = top_docs
+                .into_iter()
+                .skip(skip)
+                .collect::<Vec<_>>()
+                .into_par_iter()
+                .filter_map(|(score, address)| {
+                    let doc: TantivyDocument = searcher.doc(address).ok()?;
+                    let post: OkuPost = doc.try_into().ok()?;
+                    Some((score, address, post))
+                })
+                .filter(|(_, _, post)| !unindexed_mutes.iter().any(|x| x.matches(&post.note)))
+                .collect();
+            if unindexed_mutes.is_empty() || exhausted || found.len() >= wanted {
+                break found
+                    .into_iter()
+                    .skip(search.offset - skip)
+                    .take(search.limit)
+                    .collect::<Vec<_>>();
+            }
+            retrieved *= 2;
+        };
+
+        let mut snippet_generator =
+            SnippetGenerator::create(&searcher, &*query, POST_SCHEMA.1["body"])
+                .into_diagnostic()?;
+        snippet_generator.set_max_num_chars(SNIPPET_LENGTH);
+        let results = page
+            .into_iter()
+            .map(|(score, address, post)| {
+                // Posts ordered by time are scored only once they are in the page of results
+                let score = score.unwrap_or_else(|| {
+                    filter_query
+                        .explain(&searcher, address)
+                        .map(|x| x.value())
+                        .unwrap_or_default()
+                });
+                let snippet = snippet_generator.snippet(&post.note.body);
+                // Posts found by their other fields have no part of their body to highlight
+                let (fragment, highlights, snippet_html) = match snippet.fragment().is_empty() {
+                    true => {
+                        let fragment: String =
+                            post.note.body.chars().take(SNIPPET_LENGTH).collect();
+                        let fragment_html = fragment
+                            .replace('&', "&amp;")
+                            .replace('<', "&lt;")
+                            .replace('>', "&gt;");
+                        (fragment, Vec::new(), fragment_html)
+                    }
+                    false => (
+                        snippet.fragment().to_string(),
+                        snippet.highlighted().to_vec(),
+                        snippet.to_html(),
+                    ),
+                };
+                OkuPostSearchResult {
+                    post,
+                    score,
+                    snippet: fragment,
+                    highlights,
+                    snippet_html,
+                }
+            })
+            .collect();
+        Ok(OkuPostSearchResults {
+            results,
+            total,
+            tag_counts,
+            author_counts,
+        })
+    }
+
+    /// Count the OkuNet posts found by a search, without retrieving them.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The query and filters; pagination is ignored.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be counted.
+    ///
+    /// # Returns
+    ///
+    /// The number of posts found, excluding muted posts other than those hidden by keyword or regular expression mutes.
+    pub fn count_posts(
+        search: &OkuPostSearch,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<usize> {
+        let (_, filter_query, _) = Self::post_search_queries(search, excluded_authors)?;
+        POST_INDEX_READER
+            .searcher()
+            .search(&filter_query, &Count)
+            .into_diagnostic()
+    }
+
+    /// Finds the posts hidden by mutes since some point in time, through the search index.
+    ///
+    /// Returns none if some of the mutes cannot be expressed as queries.
+    pub(crate) fn get_muted_posts_since(
+        since: &Option<u64>,
+        excluded_authors: &HashSet<AuthorId>,
+        mutes: &[&OkuMuteRule],
+    ) -> miette::Result<Option<Vec<OkuPost>>> {
+        let Some(mute_queries) = mutes
+            .iter()
+            .map(|x| mute_query(x))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(None);
+        };
+        if mute_queries.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+        let query = BooleanQuery::new(
+            mute_queries
+                .into_iter()
+                .map(|x| (Occur::Should, x))
+                .collect(),
+        );
+        let search = OkuPostSearch {
+            after: *since,
+            ..Default::default()
+        };
+        let filter_query = search.filter_query(&query, excluded_authors, &[]);
+        let searcher = POST_INDEX_READER.searcher();
+        let addresses = searcher
+            .search(&filter_query, &DocSetCollector)
+            .into_diagnostic()?;
+        // The index only suggests which posts are muted; the rules themselves decide
+        let matchers: Vec<_> = mutes.iter().filter_map(|x| x.matcher()).collect();
+        Ok(Some(
+            addresses
+                .into_par_iter()
+                .filter_map(|address| {
+                    let doc: TantivyDocument = searcher.doc(address).ok()?;
+                    doc.try_into().ok()
+                })
+                .filter(|post: &OkuPost| matchers.iter().any(|x| x.matches(&post.note)))
+                .collect(),
+        ))
+    }
+
+    /// Builds the queries finding the posts of a search.
+    ///
+    /// Returns the search's query, the query also applying its filters and mutes, and the mutes which could not be expressed as queries.
+    fn post_search_queries(
+        search: &OkuPostSearch,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<(Box<dyn Query>, BooleanQuery, Vec<MuteMatcher>)> {
+        let query: Box<dyn Query> = match search.query.trim().is_empty() {
+            true => Box::new(AllQuery),
+            false => QueryParser::for_index(
+                &POST_INDEX,
+                vec![
+                    POST_SCHEMA.1["author_id"],
+                    POST_SCHEMA.1["path"],
+                    POST_SCHEMA.1["title"],
+                    POST_SCHEMA.1["body"],
+                    POST_SCHEMA.1["tag"],
+                    POST_SCHEMA.1["mention"],
+                    POST_SCHEMA.1["content_warning"],
+                ],
+            )
+            .parse_query(&search.query)
+            .into_diagnostic()?,
+        };
+        let node_mutes = DATABASE.get_mutes().unwrap_or_default();
+        let mutes: Vec<_> = search
+            .mutes
+            .iter()
+            .chain(node_mutes.iter().map(|x| &x.rule))
+            .collect();
+        // Mutes which cannot be expressed exactly as queries are applied to the posts retrieved
+        let unindexed_mutes: Vec<MuteMatcher> = mutes
+            .iter()
+            .filter(|x| exact_mute_query(x).is_none())
+            .filter_map(|x| x.matcher())
+            .collect();
+        let filter_query = search.filter_query(&*query, excluded_authors, &mutes);
+        Ok((query, filter_query, unindexed_mutes))
+    }
+
+    /// Retrieves the addresses of the first posts found by a search, in the order the search asks for.
+    fn top_post_docs(
+        searcher: &Searcher,
+        query: &dyn Query,
+        search: &OkuPostSearch,
+        limit: usize,
+    ) -> miette::Result<Vec<(Option<f32>, DocAddress)>> {
+        Ok(match search.newest_first {
+            true => searcher
+                .search(
+                    query,
+                    &TopDocs::with_limit(limit)
+                        .order_by_fast_field::<tantivy::DateTime>("timestamp", Order::Desc),
+                )
+                .into_diagnostic()?
+                .into_iter()
+                .map(|(_, address)| (None, address))
+                .collect(),
+            false => searcher
+                .search(query, &TopDocs::with_limit(limit).order_by_score())
+                .into_diagnostic()?
+                .into_iter()
+                .map(|(score, address)| (Some(score), address))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,476 @@
+use super::super::core::*;
+use super::core::{OkuPost, OkuPostKey};
+use super::urls::normalise_url;
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A period of time, ending now, over which OkuNet activity is measured.
+pub enum OkuTimeWindow {
+    /// The last day.
+    Day,
+    /// The last week.
+    Week,
+    /// The last thirty days.
+    Month,
+    /// The last year.
+    Year,
+    /// All time.
+    All,
+}
+
+impl OkuTimeWindow {
+    /// Every time window, from shortest to longest.
+    pub const ALL: [OkuTimeWindow; 5] = [
+        OkuTimeWindow::Day,
+        OkuTimeWindow::Week,
+        OkuTimeWindow::Month,
+        OkuTimeWindow::Year,
+        OkuTimeWindow::All,
+    ];
+
+    /// The length of this time window.
+    ///
+    /// # Returns
+    ///
+    /// The length of this time window, or none if it covers all time.
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            OkuTimeWindow::Day => Some(Duration::from_secs(60 * 60 * 24)),
+            OkuTimeWindow::Week => Some(Duration::from_secs(60 * 60 * 24 * 7)),
+            OkuTimeWindow::Month => Some(Duration::from_secs(60 * 60 * 24 * 30)),
+            OkuTimeWindow::Year => Some(Duration::from_secs(60 * 60 * 24 * 365)),
+            OkuTimeWindow::All => None,
+        }
+    }
+
+    /// The start of this time window.
+    ///
+    /// # Returns
+    ///
+    /// The time this window starts at, in microseconds since the Unix epoch, or none if it covers all time.
+    pub fn start(&self) -> Option<u64> {
+        let start = SystemTime::now().checked_sub(self.duration()?)?;
+        Some(
+            start
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64,
+        )
+    }
+}
+
+impl Display for OkuTimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OkuTimeWindow::Day => write!(f, "day"),
+            OkuTimeWindow::Week => write!(f, "week"),
+            OkuTimeWindow::Month => write!(f, "month"),
+            OkuTimeWindow::Year => write!(f, "year"),
+            OkuTimeWindow::All => write!(f, "all"),
+        }
+    }
+}
+
+impl FromStr for OkuTimeWindow {
+    type Err = miette::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "day" => Ok(OkuTimeWindow::Day),
+            "week" => Ok(OkuTimeWindow::Week),
+            "month" => Ok(OkuTimeWindow::Month),
+            "year" => Ok(OkuTimeWindow::Year),
+            "all" => Ok(OkuTimeWindow::All),
+            _ => Err(miette::miette!("Unexpected time window: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// How active OkuNet users have been since some point in time.
+pub struct OkuActivityStatistics {
+    /// The time the statistics are measured from, in microseconds since the Unix epoch, or none if they cover all time.
+    pub since: Option<u64>,
+    /// The number of posts written.
+    pub posts: usize,
+    /// The tags used, and how many posts have each, from most to least common.
+    pub tags: Vec<(String, usize)>,
+    /// The normalised URLs posted about, and how many posts regard each, from most to least common.
+    pub urls: Vec<(String, usize)>,
+    /// The authors of posts, and how many posts each wrote, from most to least prolific.
+    pub authors: Vec<(AuthorId, usize)>,
+}
+
+impl OkuActivityStatistics {
+    /// Count the tags, URLs, and authors of posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The time the posts were written since, in microseconds since the Unix epoch, or none if they were written at any time.
+    ///
+    /// * `posts` - The posts to count.
+    ///
+    /// # Returns
+    ///
+    /// The activity statistics of the posts.
+    pub fn from_posts<'a>(
+        since: Option<u64>,
+        posts: impl IntoIterator<Item = &'a OkuPost>,
+    ) -> Self {
+        let mut counts = ActivityCounts::default();
+        for post in posts {
+            counts.add_post(post);
+        }
+        counts.into_statistics(since)
+    }
+
+    /// Count more posts alongside those already counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `posts` - The posts to count, which must not already be counted.
+    ///
+    /// # Returns
+    ///
+    /// The activity statistics of both the posts already counted and the given posts.
+    pub fn with_posts<'a>(self, posts: impl IntoIterator<Item = &'a OkuPost>) -> Self {
+        let mut counts = ActivityCounts {
+            posts: self.posts,
+            tags: self.tags.into_iter().collect(),
+            urls: self.urls.into_iter().collect(),
+            authors: self.authors.into_iter().collect(),
+        };
+        for post in posts {
+            counts.add_post(post);
+        }
+        counts.into_statistics(self.since)
+    }
+}
+
+/// The number of microseconds in a day.
+const MICROS_PER_DAY: u64 = 1_000_000 * 60 * 60 * 24;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[native_model(id = 13, version = 1)]
+#[native_db(
+    primary_key(primary_key -> (Vec<u8>, Vec<u8>)),
+    secondary_key(day_key -> u64)
+)]
+/// The activity of an OkuNet user over one day, kept up to date as their posts are stored so that statistics need not be measured from every post.
+pub struct OkuActivityDay {
+    /// The content authorship ID of the user.
+    pub author_id: AuthorId,
+    /// The day, counted in days since the Unix epoch.
+    pub day: u64,
+    /// The number of posts written.
+    pub posts: usize,
+    /// The tags used, and how many posts have each.
+    pub tags: HashMap<String, usize>,
+    /// The normalised URLs posted about, and how many posts regard each.
+    pub urls: HashMap<String, usize>,
+}
+
+impl OkuActivityDay {
+    pub(crate) fn primary_key(&self) -> (Vec<u8>, Vec<u8>) {
+        Self::primary_key_for(&self.author_id, self.day)
+    }
+
+    pub(crate) fn primary_key_for(author_id: &AuthorId, day: u64) -> (Vec<u8>, Vec<u8>) {
+        (author_id.as_bytes().to_vec(), day.to_be_bytes().to_vec())
+    }
+
+    pub(crate) fn day_key(&self) -> u64 {
+        self.day
+    }
+
+    /// The day a post was written on, counted in days since the Unix epoch.
+    pub(crate) fn day_of(post: &OkuPost) -> u64 {
+        post.entry.timestamp() / MICROS_PER_DAY
+    }
+
+    fn new(author_id: AuthorId, day: u64) -> Self {
+        Self {
+            author_id,
+            day,
+            posts: 0,
+            tags: HashMap::new(),
+            urls: HashMap::new(),
+        }
+    }
+
+    fn add_post(&mut self, post: &OkuPost) {
+        self.posts += 1;
+        for tag in post.note.tags.iter() {
+            *self.tags.entry(tag.clone()).or_default() += 1;
+        }
+        *self.urls.entry(normalise_url(&post.note.url)).or_default() += 1;
+    }
+
+    fn remove_post(&mut self, post: &OkuPost) {
+        self.posts = self.posts.saturating_sub(1);
+        for tag in post.note.tags.iter() {
+            discount(&mut self.tags, tag);
+        }
+        discount(&mut self.urls, &normalise_url(&post.note.url));
+    }
+}
+
+#[derive(Debug, Default)]
+/// Tallies of activity, from which statistics are made.
+struct ActivityCounts {
+    posts: usize,
+    tags: HashMap<String, usize>,
+    urls: HashMap<String, usize>,
+    authors: HashMap<AuthorId, usize>,
+}
+
+impl ActivityCounts {
+    fn add_post(&mut self, post: &OkuPost) {
+        self.posts += 1;
+        for tag in post.note.tags.iter() {
+            *self.tags.entry(tag.clone()).or_default() += 1;
+        }
+        *self.urls.entry(normalise_url(&post.note.url)).or_default() += 1;
+        *self.authors.entry(post.entry.author()).or_default() += 1;
+    }
+
+    fn remove_post(&mut self, post: &OkuPost) {
+        self.posts = self.posts.saturating_sub(1);
+        for tag in post.note.tags.iter() {
+            discount(&mut self.tags, tag);
+        }
+        discount(&mut self.urls, &normalise_url(&post.note.url));
+        discount(&mut self.authors, &post.entry.author());
+    }
+
+    fn add_day(&mut self, day: OkuActivityDay) {
+        self.posts += day.posts;
+        for (tag, count) in day.tags {
+            *self.tags.entry(tag).or_default() += count;
+        }
+        for (url, count) in day.urls {
+            *self.urls.entry(url).or_default() += count;
+        }
+        *self.authors.entry(day.author_id).or_default() += day.posts;
+    }
+
+    fn into_statistics(self, since: Option<u64>) -> OkuActivityStatistics {
+        OkuActivityStatistics {
+            since,
+            posts: self.posts,
+            tags: sorted_counts(self.tags),
+            urls: sorted_counts(self.urls),
+            authors: sorted_counts(self.authors),
+        }
+    }
+}
+
+/// Take one from a count, forgetting the key once nothing is counted under it.
+fn discount<T: Eq + std::hash::Hash>(counts: &mut HashMap<T, usize>, key: &T) {
+    if let Some(count) = counts.get_mut(key) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            counts.remove(key);
+        }
+    }
+}
+
+/// Order counts from largest to smallest, breaking ties by key.
+fn sorted_counts<T: Ord>(counts: HashMap<T, usize>) -> Vec<(T, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts
+}
+
+impl OkuDatabase {
+    /// Counts or discounts a post in the activity of its author on the day it was written.
+    ///
+    /// # Arguments
+    ///
+    /// * `rw` - The transaction storing or deleting the post.
+    ///
+    /// * `post` - An OkuNet post.
+    ///
+    /// * `added` - Whether the post is being stored, rather than deleted.
+    pub(crate) fn update_post_activity(
+        rw: &transaction::RwTransaction<'_>,
+        post: &OkuPost,
+        added: bool,
+    ) -> miette::Result<()> {
+        let author_id = post.entry.author();
+        let day = OkuActivityDay::day_of(post);
+        let existing: Option<OkuActivityDay> = rw
+            .get()
+            .primary(OkuActivityDay::primary_key_for(&author_id, day))
+            .into_diagnostic()?;
+        let mut activity = existing
+            .clone()
+            .unwrap_or_else(|| OkuActivityDay::new(author_id, day));
+        match added {
+            true => activity.add_post(post),
+            false => activity.remove_post(post),
+        }
+        match (activity.posts, existing) {
+            (0, Some(existing)) => {
+                rw.remove(existing).into_diagnostic()?;
+            }
+            (0, None) => (),
+            _ => {
+                rw.upsert(activity).into_diagnostic()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the posts recorded in the daily activity of OkuNet users.
+    ///
+    /// # Returns
+    ///
+    /// The number of posts counted in the activity of all users, which matches the number of known posts unless the activity needs rebuilding.
+    pub fn count_post_activity(&self) -> miette::Result<usize> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        Ok(r.scan()
+            .primary::<OkuActivityDay>()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .filter_map(|x| x.ok())
+            .map(|x| x.posts)
+            .sum())
+    }
+
+    /// Rebuilds the daily activity of OkuNet users from the database.
+    pub fn rebuild_post_activity(&self) -> miette::Result<()> {
+        let mut days: HashMap<(Vec<u8>, Vec<u8>), OkuActivityDay> = HashMap::new();
+        for post in self.get_posts()? {
+            let author_id = post.entry.author();
+            let day = OkuActivityDay::day_of(&post);
+            days.entry(OkuActivityDay::primary_key_for(&author_id, day))
+                .or_insert_with(|| OkuActivityDay::new(author_id, day))
+                .add_post(&post);
+        }
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let stale_days: Vec<OkuActivityDay> = rw
+            .scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()?;
+        for stale_day in stale_days {
+            rw.remove(stale_day).into_diagnostic()?;
+        }
+        for day in days.into_values() {
+            rw.upsert(day).into_diagnostic()?;
+        }
+        rw.commit().into_diagnostic()
+    }
+
+    /// Measure the activity of OkuNet users since some point in time, excluding muted posts.
+    ///
+    /// Whole days are read from the activity kept for each user, so only the posts written on the day the period starts are read.
+    /// If a regular expression mute applies, every post in the period is read instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - If specified, only posts written at or after this time (in microseconds since the Unix epoch) are counted.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be counted.
+    ///
+    /// # Returns
+    ///
+    /// The tags and URLs most posted about, and the most active authors.
+    pub fn activity_statistics(
+        &self,
+        since: &Option<u64>,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<OkuActivityStatistics> {
+        let mutes = self.get_mutes().unwrap_or_default();
+        let rules: Vec<_> = mutes.iter().map(|x| &x.rule).collect();
+        let Some(muted_posts) = Self::get_muted_posts_since(since, excluded_authors, &rules)?
+        else {
+            let posts = self.get_posts_since(since, excluded_authors)?;
+            return Ok(OkuActivityStatistics::from_posts(*since, posts.iter()));
+        };
+
+        let mut counts = ActivityCounts::default();
+        let first_day = since.map_or(0, |x| x.div_ceil(MICROS_PER_DAY));
+        let r = self.database.r_transaction().into_diagnostic()?;
+        // The day the period starts in is only partly covered, so its posts are counted one by one
+        if let Some(since) = since {
+            let scan = r
+                .scan()
+                .secondary::<OkuPost>(OkuPostKey::timestamp)
+                .into_diagnostic()?;
+            scan.range(*since..first_day * MICROS_PER_DAY)
+                .into_diagnostic()?
+                .filter_map(|x| x.ok())
+                .filter(|x| !excluded_authors.contains(&x.entry.author()))
+                .for_each(|x| counts.add_post(&x));
+        }
+        let scan = r
+            .scan()
+            .secondary::<OkuActivityDay>(OkuActivityDayKey::day_key)
+            .into_diagnostic()?;
+        scan.range(first_day..)
+            .into_diagnostic()?
+            .filter_map(|x| x.ok())
+            .filter(|x| !excluded_authors.contains(&x.author_id))
+            .for_each(|x| counts.add_day(x));
+        for post in muted_posts.iter() {
+            counts.remove_post(post);
+        }
+        Ok(counts.into_statistics(*since))
+    }
+
+    /// Retrieves the known OkuNet posts written since some point in time.
+    ///
+    /// Posts are read in order of when they were written, so only those in the requested period are read.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - If specified, only posts written at or after this time (in microseconds since the Unix epoch) are retrieved.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts, excluding muted posts.
+    pub fn get_posts_since(
+        &self,
+        since: &Option<u64>,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<Vec<OkuPost>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let scan = r
+            .scan()
+            .secondary::<OkuPost>(OkuPostKey::timestamp)
+            .into_diagnostic()?;
+        let posts: Vec<_> = match since {
+            Some(since) => scan
+                .range(*since..)
+                .into_diagnostic()?
+                .filter_map(|x| x.ok())
+                .filter(|x| !excluded_authors.contains(&x.entry.author()))
+                .collect(),
+            None => scan
+                .all()
+                .into_diagnostic()?
+                .filter_map(|x| x.ok())
+                .filter(|x| !excluded_authors.contains(&x.entry.author()))
+                .collect(),
+        };
+        Ok(self.filter_muted_posts(posts))
+    }
+}
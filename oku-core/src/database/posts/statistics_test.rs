@@ -0,0 +1,255 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::core::DATABASE;
+    use crate::database::mutes::{OkuMute, OkuMuteRule};
+    use crate::database::posts::core::{OkuNote, OkuPost, OkuVisibility, POST_INDEX_READER};
+    use crate::database::posts::statistics::OkuActivityDay;
+    use crate::fs::util::path_to_entry_key;
+    use iroh_blobs::Hash;
+    use iroh_docs::sync::{Entry, Record, RecordIdentifier};
+    use iroh_docs::{Author, AuthorId, NamespaceSecret};
+    use miette::IntoDiagnostic;
+    use std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+    };
+    use url::Url;
+
+    const HOUR: u64 = 1_000_000 * 60 * 60;
+    const DAY: u64 = HOUR * 24;
+    // Posts are written far after those of other tests, so that only these are counted since then
+    const FIRST_DAY: u64 = 100_000;
+
+    fn post(author: &Author, path: &str, timestamp: u64, body: &str, tags: &[&str]) -> OkuPost {
+        OkuPost {
+            entry: Entry::new(
+                RecordIdentifier::new(
+                    NamespaceSecret::from_bytes(&[0; 32]).id(),
+                    author.id(),
+                    path_to_entry_key(&PathBuf::from(path)),
+                ),
+                Record::new(Hash::new(path), 0, timestamp),
+            ),
+            note: OkuNote {
+                url: Url::parse(&format!("https://example.com{path}")).unwrap(),
+                title: String::new(),
+                body: body.to_string(),
+                tags: tags.iter().map(|x| x.to_string()).collect(),
+                content_warning: None,
+                sensitive: false,
+                visibility: OkuVisibility::Public,
+            },
+        }
+    }
+
+    fn activity_day(author_id: &AuthorId, day: u64) -> miette::Result<Option<OkuActivityDay>> {
+        let r = DATABASE.database.r_transaction().into_diagnostic()?;
+        r.get()
+            .primary(OkuActivityDay::primary_key_for(author_id, day))
+            .into_diagnostic()
+    }
+
+    fn tag_counts(tags: &[(&str, usize)]) -> HashMap<String, usize> {
+        tags.iter().map(|(x, y)| (x.to_string(), *y)).collect()
+    }
+
+    #[test]
+    fn test_activity_days() -> miette::Result<()> {
+        let first_author = Author::from_bytes(&[60; 32]);
+        let second_author = Author::from_bytes(&[61; 32]);
+        let first_post = post(
+            &first_author,
+            "/posts/activity-first.toml",
+            FIRST_DAY * DAY + HOUR,
+            "First",
+            &["activity-a", "activity-b"],
+        );
+        let second_post = post(
+            &first_author,
+            "/posts/activity-second.toml",
+            FIRST_DAY * DAY + 2 * HOUR,
+            "Second",
+            &["activity-a"],
+        );
+        let third_post = post(
+            &second_author,
+            "/posts/activity-third.toml",
+            (FIRST_DAY + 1) * DAY + HOUR,
+            "Third",
+            &["activity-b"],
+        );
+        DATABASE.upsert_posts(&vec![
+            first_post.clone(),
+            second_post.clone(),
+            third_post.clone(),
+        ])?;
+
+        // Stored posts are counted on the day they were written
+        let day = activity_day(&first_author.id(), FIRST_DAY)?
+            .ok_or(miette::miette!("Activity was not counted"))?;
+        assert_eq!(2, day.posts);
+        assert_eq!(
+            tag_counts(&[("activity-a", 2), ("activity-b", 1)]),
+            day.tags
+        );
+        assert_eq!(
+            HashMap::from([
+                (first_post.normalised_url(), 1),
+                (second_post.normalised_url(), 1)
+            ]),
+            day.urls
+        );
+        assert_eq!(
+            1,
+            activity_day(&second_author.id(), FIRST_DAY + 1)?
+                .map(|x| x.posts)
+                .unwrap_or_default()
+        );
+        assert!(activity_day(&second_author.id(), FIRST_DAY)?.is_none());
+
+        // Posts written again on another day are moved to that day
+        let moved_post = post(
+            &first_author,
+            "/posts/activity-second.toml",
+            (FIRST_DAY + 1) * DAY + 2 * HOUR,
+            "Second",
+            &["activity-c"],
+        );
+        DATABASE.upsert_post(&moved_post)?;
+        let day = activity_day(&first_author.id(), FIRST_DAY)?
+            .ok_or(miette::miette!("Activity was lost"))?;
+        assert_eq!(1, day.posts);
+        assert_eq!(
+            tag_counts(&[("activity-a", 1), ("activity-b", 1)]),
+            day.tags
+        );
+        let next_day = activity_day(&first_author.id(), FIRST_DAY + 1)?
+            .ok_or(miette::miette!("Activity was not moved"))?;
+        assert_eq!(1, next_day.posts);
+        assert_eq!(tag_counts(&[("activity-c", 1)]), next_day.tags);
+
+        // Posts written again with other tags are counted under those tags
+        let retagged_post = post(
+            &first_author,
+            "/posts/activity-first.toml",
+            FIRST_DAY * DAY + HOUR,
+            "First",
+            &["activity-d"],
+        );
+        DATABASE.upsert_posts(&vec![retagged_post.clone()])?;
+        let day = activity_day(&first_author.id(), FIRST_DAY)?
+            .ok_or(miette::miette!("Activity was lost"))?;
+        assert_eq!(1, day.posts);
+        assert_eq!(tag_counts(&[("activity-d", 1)]), day.tags);
+
+        // Deleted posts are no longer counted, and days without posts are forgotten
+        DATABASE.delete_posts(&[retagged_post, moved_post])?;
+        assert!(activity_day(&first_author.id(), FIRST_DAY)?.is_none());
+        assert!(activity_day(&first_author.id(), FIRST_DAY + 1)?.is_none());
+        DATABASE.delete_post(&third_post)?;
+        assert!(activity_day(&second_author.id(), FIRST_DAY + 1)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_activity_statistics() -> miette::Result<()> {
+        let first_author = Author::from_bytes(&[62; 32]);
+        let second_author = Author::from_bytes(&[63; 32]);
+        let posts = vec![
+            post(
+                &first_author,
+                "/posts/statistics-first.toml",
+                (FIRST_DAY + 10) * DAY + HOUR,
+                "First",
+                &["statistics-a"],
+            ),
+            post(
+                &first_author,
+                "/posts/statistics-second.toml",
+                (FIRST_DAY + 10) * DAY + 3 * HOUR,
+                "Statistics muted body",
+                &["statistics-a", "statistics-muted"],
+            ),
+            post(
+                &second_author,
+                "/posts/statistics-third.toml",
+                (FIRST_DAY + 11) * DAY + HOUR,
+                "Third",
+                &["statistics-b"],
+            ),
+        ];
+        DATABASE.upsert_posts(&posts)?;
+        POST_INDEX_READER
+            .reload()
+            .map_err(|e| miette::miette!("{e}"))?;
+        let start = Some((FIRST_DAY + 10) * DAY);
+
+        let statistics = DATABASE.activity_statistics(&start, &HashSet::new())?;
+        assert_eq!(start, statistics.since);
+        assert_eq!(3, statistics.posts);
+        assert_eq!(
+            vec![(first_author.id(), 2), (second_author.id(), 1)],
+            statistics.authors
+        );
+        assert_eq!(
+            vec![
+                ("statistics-a".to_string(), 2),
+                ("statistics-b".to_string(), 1),
+                ("statistics-muted".to_string(), 1)
+            ],
+            statistics.tags
+        );
+
+        // Posts written on the first day before the period starts are not counted
+        let statistics =
+            DATABASE.activity_statistics(&start.map(|x| x + 2 * HOUR), &HashSet::new())?;
+        assert_eq!(2, statistics.posts);
+        assert_eq!(
+            vec![(first_author.id(), 1), (second_author.id(), 1)],
+            statistics.authors
+        );
+
+        // Posts by blocked users are not counted
+        let statistics =
+            DATABASE.activity_statistics(&start, &HashSet::from([second_author.id()]))?;
+        assert_eq!(2, statistics.posts);
+        assert_eq!(vec![(first_author.id(), 2)], statistics.authors);
+        assert!(!statistics.tags.iter().any(|(tag, _)| tag == "statistics-b"));
+
+        // Muted posts are not counted, whether matched through the index or one by one
+        for rule in [
+            OkuMuteRule::Tag("statistics-muted".to_string()),
+            OkuMuteRule::Regex("^Statistics muted body$".to_string()),
+        ] {
+            DATABASE.upsert_mute(&OkuMute {
+                rule: rule.clone(),
+                expiry: None,
+            })?;
+            let statistics = DATABASE.activity_statistics(&start, &HashSet::new())?;
+            DATABASE.delete_mute(&rule)?;
+            assert_eq!(2, statistics.posts);
+            assert_eq!(
+                vec![(first_author.id(), 1), (second_author.id(), 1)],
+                statistics.authors
+            );
+            assert_eq!(
+                vec![
+                    ("statistics-a".to_string(), 1),
+                    ("statistics-b".to_string(), 1)
+                ],
+                statistics.tags
+            );
+            assert_eq!(
+                vec![
+                    (posts[0].normalised_url(), 1),
+                    (posts[2].normalised_url(), 1)
+                ],
+                statistics.urls
+            );
+        }
+
+        DATABASE.delete_posts(&posts)?;
+        Ok(())
+    }
+}
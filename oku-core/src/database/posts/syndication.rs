@@ -0,0 +1,327 @@
+use super::super::core::*;
+use super::core::OkuPost;
+use super::search::OkuPostSearch;
+use crate::fs::util::{encode_search_value, entry_key_to_path, fmt};
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// A format in which OkuNet posts can be read by feed readers.
+pub enum OkuFeedFormat {
+    #[default]
+    /// An Atom feed (RFC 4287).
+    Atom,
+    /// A JSON Feed (version 1.1).
+    Json,
+}
+
+impl OkuFeedFormat {
+    /// The media type of documents in this format.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            OkuFeedFormat::Atom => "application/atom+xml",
+            OkuFeedFormat::Json => "application/feed+json",
+        }
+    }
+
+    /// The file extension of documents in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OkuFeedFormat::Atom => "atom",
+            OkuFeedFormat::Json => "json",
+        }
+    }
+}
+
+impl Display for OkuFeedFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for OkuFeedFormat {
+    type Err = miette::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "atom" | "xml" => Ok(OkuFeedFormat::Atom),
+            "json" | "jsonfeed" => Ok(OkuFeedFormat::Json),
+            _ => Err(miette::miette!("Unexpected feed format: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The OkuNet posts a feed is made of.
+pub enum OkuFeedSource {
+    /// The posts by a particular user.
+    User(AuthorId),
+    /// The posts with a particular tag.
+    Tag(String),
+    /// The posts found by a search.
+    Search(OkuPostSearch),
+}
+
+impl OkuFeedSource {
+    /// The URI of this feed, as viewed in the Oku browser.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format of the feed.
+    ///
+    /// # Returns
+    ///
+    /// An `oku:` URI referring to the feed, with its parameters percent-encoded.
+    pub fn uri(&self, format: OkuFeedFormat) -> String {
+        let parameters = match self {
+            OkuFeedSource::User(author_id) => vec![format!("author={}", fmt(author_id))],
+            OkuFeedSource::Tag(tag) => vec![format!("tag={}", encode_search_value(tag))],
+            OkuFeedSource::Search(search) => {
+                let mut parameters = vec![format!("query={}", encode_search_value(&search.query))];
+                let mut tags: Vec<_> = search
+                    .tags
+                    .iter()
+                    .map(|x| format!("tag={}", encode_search_value(x)))
+                    .collect();
+                tags.sort_unstable();
+                parameters.extend(tags);
+                let mut authors: Vec<_> = search
+                    .authors
+                    .iter()
+                    .map(|x| format!("author={}", fmt(x)))
+                    .collect();
+                authors.sort_unstable();
+                parameters.extend(authors);
+                if let Some(domain) = &search.domain {
+                    parameters.push(format!("domain={}", encode_search_value(domain)));
+                }
+                if let Some(after) = search.after {
+                    parameters.push(format!("after={after}"));
+                }
+                if let Some(before) = search.before {
+                    parameters.push(format!("before={before}"));
+                }
+                parameters
+            }
+        };
+        format!("oku:feed.{}?{}", format.extension(), parameters.join("&"))
+    }
+
+    /// A title describing this feed.
+    pub fn title(&self) -> String {
+        match self {
+            OkuFeedSource::User(author_id) => match DATABASE.get_user(author_id).ok().flatten() {
+                Some(user) => format!("Posts by {}", DATABASE.display_name(&user)),
+                None => format!("Posts by {}", fmt(author_id)),
+            },
+            OkuFeedSource::Tag(tag) => format!("#{tag}"),
+            OkuFeedSource::Search(search) => match search.query.trim().is_empty() {
+                true => "OkuNet posts".to_string(),
+                false => format!("OkuNet posts matching “{}”", search.query.trim()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A feed of OkuNet posts, which can be written as an Atom or JSON Feed document.
+pub struct OkuSyndicationFeed {
+    /// The title of the feed.
+    pub title: String,
+    /// A URI identifying the feed, which is the same regardless of the format it is written in.
+    pub id: String,
+    /// The posts in the feed, ordered from newest to oldest.
+    pub posts: Vec<OkuPost>,
+    /// Names to give particular authors in place of how they are known to the local user, such as when the feed is published to others.
+    pub author_names: HashMap<AuthorId, String>,
+}
+
+/// The URI of an OkuNet post, as viewed in the Oku browser.
+///
+/// # Arguments
+///
+/// * `post` - An OkuNet post.
+///
+/// # Returns
+///
+/// An `oku:` URI referring to the post.
+pub fn post_uri(post: &OkuPost) -> String {
+    let path = entry_key_to_path(post.entry.key())
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!(
+        "oku:{}{}",
+        fmt(post.entry.author()),
+        path.strip_suffix(".toml").unwrap_or(&path)
+    )
+}
+
+/// Format a time as an RFC 3339 timestamp.
+fn rfc3339(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp_micros(timestamp.try_into().unwrap_or(0))
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Escape text for inclusion in an XML document.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+impl OkuSyndicationFeed {
+    /// When the feed was last updated.
+    ///
+    /// # Returns
+    ///
+    /// The time the newest post in the feed was written, in microseconds since the Unix epoch, or zero if the feed is empty.
+    pub fn updated(&self) -> u64 {
+        self.posts
+            .iter()
+            .map(|x| x.entry.timestamp())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The name an author is given in the feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - A post in the feed.
+    ///
+    /// # Returns
+    ///
+    /// The name given to the post's author in the feed, or otherwise their display name.
+    pub fn author_name(&self, post: &OkuPost) -> String {
+        self.author_names
+            .get(&post.entry.author())
+            .cloned()
+            .unwrap_or_else(|| DATABASE.display_name(&post.user()))
+    }
+
+    /// Write the feed in a particular format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The format to write the feed in.
+    ///
+    /// # Returns
+    ///
+    /// The feed document.
+    pub fn render(&self, format: OkuFeedFormat) -> miette::Result<String> {
+        match format {
+            OkuFeedFormat::Atom => Ok(self.to_atom()),
+            OkuFeedFormat::Json => self.to_json_feed(),
+        }
+    }
+
+    /// Write the feed as an Atom document.
+    ///
+    /// # Returns
+    ///
+    /// An Atom feed document.
+    pub fn to_atom(&self) -> String {
+        let mut document = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        document.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        document.push_str(&format!("  <title>{}</title>\n", escape_xml(&self.title)));
+        document.push_str(&format!("  <id>{}</id>\n", escape_xml(&self.id)));
+        document.push_str(&format!(
+            "  <link rel=\"self\" href=\"{}\"/>\n",
+            escape_xml(&self.id)
+        ));
+        document.push_str(&format!(
+            "  <updated>{}</updated>\n",
+            rfc3339(self.updated())
+        ));
+        document.push_str("  <generator>Oku</generator>\n");
+        for post in self.posts.iter() {
+            let uri = post_uri(post);
+            let author_name = self.author_name(post);
+            let mut tags: Vec<_> = post.note.tags.iter().collect();
+            tags.sort_unstable();
+            document.push_str("  <entry>\n");
+            document.push_str(&format!(
+                "    <title>{}</title>\n",
+                escape_xml(&post.note.title)
+            ));
+            document.push_str(&format!("    <id>{}</id>\n", escape_xml(&uri)));
+            document.push_str(&format!(
+                "    <link rel=\"alternate\" href=\"{}\"/>\n",
+                escape_xml(&uri)
+            ));
+            document.push_str(&format!(
+                "    <link rel=\"related\" href=\"{}\"/>\n",
+                escape_xml(post.note.url.as_str())
+            ));
+            document.push_str(&format!(
+                "    <updated>{}</updated>\n",
+                rfc3339(post.entry.timestamp())
+            ));
+            document.push_str(&format!(
+                "    <author>\n      <name>{}</name>\n      <uri>oku:{}</uri>\n    </author>\n",
+                escape_xml(&author_name),
+                fmt(post.entry.author())
+            ));
+            for tag in tags {
+                document.push_str(&format!("    <category term=\"{}\"/>\n", escape_xml(tag)));
+            }
+            if let Some(warning) = post.note.warning() {
+                document.push_str(&format!(
+                    "    <summary>{}</summary>\n",
+                    escape_xml(&warning)
+                ));
+            }
+            document.push_str(&format!(
+                "    <content type=\"text\">{}</content>\n",
+                escape_xml(&post.note.body)
+            ));
+            document.push_str("  </entry>\n");
+        }
+        document.push_str("</feed>\n");
+        document
+    }
+
+    /// Write the feed as a JSON Feed document.
+    ///
+    /// # Returns
+    ///
+    /// A JSON Feed document.
+    pub fn to_json_feed(&self) -> miette::Result<String> {
+        let items: Vec<_> = self
+            .posts
+            .iter()
+            .map(|post| {
+                let uri = post_uri(post);
+                let mut tags: Vec<_> = post.note.tags.iter().collect();
+                tags.sort_unstable();
+                let mut item = serde_json::json!({
+                    "id": uri,
+                    "url": uri,
+                    "external_url": post.note.url.to_string(),
+                    "title": post.note.title,
+                    "content_text": post.note.body,
+                    "date_published": rfc3339(post.entry.timestamp()),
+                    "authors": [{
+                        "name": self.author_name(post),
+                        "url": format!("oku:{}", fmt(post.entry.author())),
+                    }],
+                    "tags": tags,
+                });
+                if let Some(warning) = post.note.warning() {
+                    item["summary"] = warning.into();
+                }
+                item
+            })
+            .collect();
+        let feed = serde_json::json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": self.title,
+            "items": items,
+        });
+        serde_json::to_string_pretty(&feed).into_diagnostic()
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::posts::core::{OkuNote, OkuPost, OkuVisibility};
+    use crate::database::posts::syndication::{post_uri, OkuFeedFormat, OkuSyndicationFeed};
+    use crate::fs::util::{fmt, path_to_entry_key};
+    use iroh_blobs::Hash;
+    use iroh_docs::sync::{Entry, Record, RecordIdentifier};
+    use iroh_docs::{Author, NamespaceSecret};
+    use std::{collections::HashMap, path::PathBuf};
+    use url::Url;
+
+    fn post(author: &Author, path: &str, timestamp: u64, note: OkuNote) -> OkuPost {
+        OkuPost {
+            entry: Entry::new(
+                RecordIdentifier::new(
+                    NamespaceSecret::from_bytes(&[0; 32]).id(),
+                    author.id(),
+                    path_to_entry_key(&PathBuf::from(path)),
+                ),
+                Record::new(Hash::new(path), 0, timestamp),
+            ),
+            note,
+        }
+    }
+
+    fn feed() -> OkuSyndicationFeed {
+        let author = Author::from_bytes(&[40; 32]);
+        let escaped = post(
+            &author,
+            "/posts/escaped.toml",
+            1_700_000_000_000_000,
+            OkuNote {
+                url: Url::parse("https://example.com/?a=1&b=2").unwrap(),
+                title: "<b>\"Quoted\" & 'apostrophes'</b>".to_string(),
+                body: "</content><script>alert(1)</script>".to_string(),
+                tags: ["zebra".to_string(), "a&b".to_string()]
+                    .into_iter()
+                    .collect(),
+                content_warning: Some("Spoilers <here>".to_string()),
+                sensitive: false,
+                visibility: OkuVisibility::Public,
+            },
+        );
+        let plain = post(
+            &author,
+            "/posts/plain.toml",
+            1_600_000_000_000_000,
+            OkuNote {
+                url: Url::parse("https://example.com/plain").unwrap(),
+                title: "Plain".to_string(),
+                body: "Plain body".to_string(),
+                tags: Default::default(),
+                content_warning: None,
+                sensitive: false,
+                visibility: OkuVisibility::Public,
+            },
+        );
+        OkuSyndicationFeed {
+            title: "Posts & <things>".to_string(),
+            id: "oku:feed.atom?query=a%26b&tag=c".to_string(),
+            posts: vec![escaped, plain],
+            author_names: HashMap::from([(author.id(), "Ann & \"Bob\"".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_post_uri() {
+        let feed = feed();
+        assert_eq!(
+            format!("oku:{}/posts/escaped", fmt(feed.posts[0].entry.author())),
+            post_uri(&feed.posts[0])
+        );
+    }
+
+    #[test]
+    fn test_to_atom() -> miette::Result<()> {
+        let feed = feed();
+        let document = feed.to_atom();
+        assert_eq!(document, feed.render(OkuFeedFormat::Atom)?);
+        let uri = post_uri(&feed.posts[0]);
+
+        assert!(document.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n"));
+        assert!(document.contains("  <title>Posts &amp; &lt;things&gt;</title>\n"));
+        assert!(document.contains("  <id>oku:feed.atom?query=a%26b&amp;tag=c</id>\n"));
+        // The feed was last updated when its newest post was written
+        assert!(document.contains("  <updated>2023-11-14T22:13:20Z</updated>\n"));
+        assert!(document.contains(&format!("    <id>{uri}</id>\n")));
+        assert!(document
+            .contains("    <link rel=\"related\" href=\"https://example.com/?a=1&amp;b=2\"/>\n"));
+        assert!(document.contains("    <updated>2020-09-13T12:26:40Z</updated>\n"));
+        assert!(document.contains("      <name>Ann &amp; &quot;Bob&quot;</name>\n"));
+
+        // Markup in posts is written as text, and cannot end or add elements
+        assert!(document.contains(
+            "    <title>&lt;b&gt;&quot;Quoted&quot; &amp; &apos;apostrophes&apos;&lt;/b&gt;</title>\n"
+        ));
+        assert!(document.contains(
+            "    <content type=\"text\">&lt;/content&gt;&lt;script&gt;alert(1)&lt;/script&gt;</content>\n"
+        ));
+        assert!(!document.contains("<script>"));
+        assert!(document.contains("    <summary>Spoilers &lt;here&gt;</summary>\n"));
+
+        // Tags are written in order, and posts without a warning have no summary
+        let first_tag = document.find("<category term=\"a&amp;b\"/>");
+        let second_tag = document.find("<category term=\"zebra\"/>");
+        assert!(first_tag.is_some() && second_tag.is_some() && first_tag < second_tag);
+        assert_eq!(2, document.matches("<entry>").count());
+        assert_eq!(1, document.matches("<summary>").count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_json_feed() -> miette::Result<()> {
+        let feed = feed();
+        let document: serde_json::Value =
+            serde_json::from_str(&feed.render(OkuFeedFormat::Json)?).unwrap();
+        let uri = post_uri(&feed.posts[0]);
+
+        assert_eq!("https://jsonfeed.org/version/1.1", document["version"]);
+        assert_eq!("Posts & <things>", document["title"]);
+        let items = document["items"].as_array().unwrap();
+        assert_eq!(2, items.len());
+
+        // Text is kept as it was written, leaving escaping to JSON
+        let item = &items[0];
+        assert_eq!(uri, item["id"]);
+        assert_eq!(uri, item["url"]);
+        assert_eq!("https://example.com/?a=1&b=2", item["external_url"]);
+        assert_eq!("<b>\"Quoted\" & 'apostrophes'</b>", item["title"]);
+        assert_eq!("</content><script>alert(1)</script>", item["content_text"]);
+        assert_eq!("2023-11-14T22:13:20Z", item["date_published"]);
+        assert_eq!("Ann & \"Bob\"", item["authors"][0]["name"]);
+        assert_eq!(
+            format!("oku:{}", fmt(feed.posts[0].entry.author())),
+            item["authors"][0]["url"]
+        );
+        assert_eq!(serde_json::json!(["a&b", "zebra"]), item["tags"]);
+        assert_eq!("Spoilers <here>", item["summary"]);
+
+        // Posts without a warning have no summary
+        assert!(items[1].get("summary").is_none());
+        assert_eq!(serde_json::json!([]), items[1]["tags"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use super::super::core::*;
+use super::core::{OkuPost, OkuPostKey};
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use native_db::*;
+use std::collections::HashSet;
+use url::Url;
+
+/// Query parameters used to track visitors, which do not change the content a URL refers to.
+const TRACKING_PARAMETERS: [&str; 8] = [
+    "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "igshid", "ref_src",
+];
+
+/// Normalise a URL, so that URLs referring to the same content compare equal.
+///
+/// Secure and insecure web URLs are treated alike, as are hosts with and without a leading `www.`; fragments, tracking parameters, and trailing slashes are removed, and the remaining query parameters are sorted.
+///
+/// # Arguments
+///
+/// * `url` - A URL.
+///
+/// # Returns
+///
+/// The normalised form of the URL.
+pub fn normalise_url(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    if url.scheme() == "http" {
+        let _ = url.set_scheme("https");
+    }
+    if let Some(host) = url
+        .host_str()
+        .and_then(|x| x.strip_prefix("www."))
+        .map(|x| x.to_owned())
+    {
+        let _ = url.set_host(Some(&host));
+    }
+    let mut query_pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMETERS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    query_pairs.sort();
+    match query_pairs.is_empty() {
+        true => url.set_query(None),
+        false => {
+            url.query_pairs_mut().clear().extend_pairs(query_pairs);
+        }
+    }
+    if url.path().len() > 1 && url.path().ends_with('/') {
+        let path = url.path().trim_end_matches('/').to_owned();
+        url.set_path(&path);
+    }
+    url.to_string()
+}
+
+impl OkuDatabase {
+    /// Retrieves the known OkuNet posts regarding a URL, or any URL with the same normalised form.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A URL.
+    ///
+    /// * `excluded_authors` - The content authorship IDs of users whose posts should not be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts regarding the URL, excluding muted posts.
+    pub fn get_posts_about(
+        &self,
+        url: &Url,
+        excluded_authors: &HashSet<AuthorId>,
+    ) -> miette::Result<Vec<OkuPost>> {
+        let normalised_url = normalise_url(url);
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let posts: Vec<_> = r
+            .scan()
+            .secondary::<OkuPost>(OkuPostKey::normalised_url)
+            .into_diagnostic()?
+            .range(normalised_url.clone()..=normalised_url)
+            .into_diagnostic()?
+            .filter_map(|x| x.ok())
+            .filter(|x| !excluded_authors.contains(&x.entry.author()))
+            .collect();
+        Ok(self.filter_muted_posts(posts))
+    }
+}
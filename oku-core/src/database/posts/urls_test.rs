@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::posts::urls::normalise_url;
+    use url::Url;
+
+    fn normalised(url: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(normalise_url(&Url::parse(url)?))
+    }
+
+    #[test]
+    fn test_normalise_url() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(
+            normalised("http://www.example.com/path/?utm_source=feed&b=2&a=1#section")?,
+            "https://example.com/path?a=1&b=2"
+        );
+        assert_eq!(
+            normalised("https://example.com/article?fbclid=123&gclid=456")?,
+            "https://example.com/article"
+        );
+        assert_eq!(normalised("https://example.com/")?, "https://example.com/");
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalise_url_equivalent() -> Result<(), Box<dyn std::error::Error>> {
+        let normal = normalised("https://example.com/post?id=1&page=2")?;
+        for url in [
+            "http://example.com/post?id=1&page=2",
+            "https://www.example.com/post?id=1&page=2",
+            "https://example.com/post/?page=2&id=1",
+            "https://example.com/post?id=1&page=2&utm_medium=social#comments",
+        ] {
+            assert_eq!(normalised(url)?, normal);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalise_url_distinct() -> Result<(), Box<dyn std::error::Error>> {
+        // Query parameters other than tracking parameters change the content referred to
+        assert_ne!(
+            normalised("https://example.com/post?id=1")?,
+            normalised("https://example.com/post?id=2")?
+        );
+        // Only a leading 'www.' is ignored
+        assert_ne!(
+            normalised("https://blog.example.com/")?,
+            normalised("https://example.com/")?
+        );
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
 use super::core::*;
+use super::migrations::OkuUserV1;
 use super::posts::core::OkuPost;
+#[cfg(feature = "persistent")]
+use crate::fs::FS_PATH;
 use iroh_docs::sync::Entry;
 use iroh_docs::AuthorId;
 use log::error;
@@ -9,10 +12,63 @@ use native_model::{native_model, Model};
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
-use std::{collections::HashSet, time::SystemTime};
+#[cfg(feature = "persistent")]
+use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::{Arc, LazyLock},
+    time::SystemTime,
+};
+use tantivy::{
+    collector::TopDocs,
+    query::QueryParser,
+    schema::{Field, Schema, Value, STORED, TEXT},
+    Index, IndexReader, IndexWriter, TantivyDocument, Term,
+};
+#[cfg(feature = "persistent")]
+use tantivy::{directory::MmapDirectory, Directory};
+use tokio::sync::Mutex;
+
+#[cfg(feature = "persistent")]
+pub(crate) static USER_INDEX_PATH: LazyLock<PathBuf> =
+    LazyLock::new(|| PathBuf::from(FS_PATH).join("USER_INDEX"));
+pub(crate) static USER_SCHEMA: LazyLock<(Schema, HashMap<&str, Field>)> = LazyLock::new(|| {
+    let mut schema_builder = Schema::builder();
+    let fields = HashMap::from([
+        ("id", schema_builder.add_bytes_field("id", STORED)),
+        (
+            "author_id",
+            schema_builder.add_text_field("author_id", TEXT | STORED),
+        ),
+        ("name", schema_builder.add_text_field("name", TEXT | STORED)),
+        ("bio", schema_builder.add_text_field("bio", TEXT | STORED)),
+    ]);
+    let schema = schema_builder.build();
+    (schema, fields)
+});
+pub(crate) static USER_INDEX: LazyLock<Index> = LazyLock::new(|| {
+    cfg_select! {
+        feature = "persistent" => {
+            if let Err(e) = std::fs::create_dir_all(&*USER_INDEX_PATH) {
+                error!("{e}");
+            }
+            let mmap_directory: Box<dyn Directory> =
+                Box::new(MmapDirectory::open(&*USER_INDEX_PATH).unwrap());
+            Index::open_or_create(mmap_directory, USER_SCHEMA.0.clone()).unwrap()
+        }
+        _ => {
+            Index::create_in_ram(USER_SCHEMA.0.clone())
+        }
+    }
+});
+pub(crate) static USER_INDEX_READER: LazyLock<IndexReader> =
+    LazyLock::new(|| USER_INDEX.reader().unwrap());
+pub(crate) static USER_INDEX_WRITER: LazyLock<Arc<Mutex<IndexWriter>>> =
+    LazyLock::new(|| Arc::new(Mutex::new(USER_INDEX.writer(50_000_000).unwrap())));
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[native_model(id = 1, version = 1)]
+#[native_model(id = 1, version = 2, from = OkuUserV1)]
 #[native_db(
     primary_key(author_id -> Vec<u8>)
 )]
@@ -44,6 +100,50 @@ impl OkuUser {
     fn author_id(&self) -> Vec<u8> {
         self.author_id.as_bytes().to_vec()
     }
+
+    pub(crate) fn index_term(&self) -> Term {
+        Term::from_field_bytes(USER_SCHEMA.1["id"], self.author_id.as_bytes())
+    }
+}
+
+impl From<OkuUser> for TantivyDocument {
+    fn from(value: OkuUser) -> Self {
+        let mut doc = TantivyDocument::default();
+        doc.add_bytes(USER_SCHEMA.1["id"], value.author_id.as_bytes());
+        doc.add_text(
+            USER_SCHEMA.1["author_id"],
+            crate::fs::util::fmt(value.author_id),
+        );
+        if let Some(identity) = value.identity {
+            doc.add_text(USER_SCHEMA.1["name"], identity.name);
+            if let Some(bio) = identity.bio {
+                doc.add_text(USER_SCHEMA.1["bio"], bio);
+            }
+        }
+        doc
+    }
+}
+
+impl TryFrom<TantivyDocument> for OkuUser {
+    type Error = anyhow::Error;
+
+    fn try_from(value: TantivyDocument) -> Result<Self, Self::Error> {
+        let author_id = AuthorId::from_str(
+            value
+                .get_first(USER_SCHEMA.1["author_id"])
+                .ok_or(anyhow::anyhow!("No author ID for document in index … "))?
+                .as_str()
+                .ok_or(anyhow::anyhow!("No author ID for document in index … "))?,
+        )?;
+        DATABASE
+            .get_user(&author_id)
+            .ok()
+            .flatten()
+            .ok_or(anyhow::anyhow!(
+                "No user with author {} found … ",
+                author_id
+            ))
+    }
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
@@ -51,15 +151,75 @@ impl OkuUser {
 pub struct OkuIdentity {
     /// The display name of the Oku user.
     pub name: String,
+    #[serde(default)]
+    /// A short description the Oku user gives of themselves.
+    pub bio: Option<String>,
     /// The content authors followed by the Oku user.
     /// OkuNet content is retrieved from followed users and the users those users follow.
     pub following: HashSet<AuthorId>,
     /// The content authors blocked by the Oku user.
     /// Blocked authors are ignored when fetching new OkuNet posts.
     pub blocked: HashSet<AuthorId>,
+    #[serde(default)]
+    /// The content authors whose blocked lists are also applied by the Oku user.
+    pub blocklist_subscriptions: HashSet<AuthorId>,
+    #[serde(default)]
+    /// The content authors exempt from the blocked lists the Oku user subscribes to.
+    pub allowed: HashSet<AuthorId>,
 }
 
 impl OkuDatabase {
+    /// Search OkuNet users by their display names, biographies, and content authorship IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `query_string` - The string used to query for users.
+    ///
+    /// * `result_limit` - The maximum number of results to get (defaults to 10).
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet users.
+    pub fn search_users(
+        query_string: &str,
+        result_limit: &Option<usize>,
+    ) -> miette::Result<Vec<OkuUser>> {
+        let searcher = USER_INDEX_READER.searcher();
+        let query_parser = QueryParser::for_index(
+            &USER_INDEX,
+            vec![
+                USER_SCHEMA.1["author_id"],
+                USER_SCHEMA.1["name"],
+                USER_SCHEMA.1["bio"],
+            ],
+        );
+        let query = query_parser.parse_query(query_string).into_diagnostic()?;
+        let limit = result_limit.unwrap_or(10);
+        let top_docs = searcher
+            .search(&query, &TopDocs::with_limit(limit).order_by_score())
+            .into_diagnostic()?;
+        Ok(top_docs
+            .par_iter()
+            .filter_map(|x| searcher.doc(x.1).ok())
+            .collect::<Vec<TantivyDocument>>()
+            .into_par_iter()
+            .filter_map(|x| TryInto::try_into(x).ok())
+            .collect())
+    }
+
+    /// Rebuilds the search index of OkuNet users from the database.
+    pub fn rebuild_user_index(&self) -> miette::Result<()> {
+        let mut index_writer = lock_index_writer(&USER_INDEX_WRITER);
+        index_writer.delete_all_documents().into_diagnostic()?;
+        self.get_users()?.into_par_iter().for_each(|user| {
+            if let Err(e) = index_writer.add_document(user.into()) {
+                error!("{e}");
+            }
+        });
+        index_writer.commit().into_diagnostic()?;
+        Ok(())
+    }
+
     /// Insert or update an OkuNet user.
     ///
     /// # Arguments
@@ -73,6 +233,18 @@ impl OkuDatabase {
         let rw = self.database.rw_transaction().into_diagnostic()?;
         let old_value: Option<OkuUser> = rw.upsert(user.to_owned()).into_diagnostic()?;
         rw.commit().into_diagnostic()?;
+        if let Some(old_user) = &old_value {
+            self.unindex_follows(old_user);
+        }
+        self.index_follows(user);
+
+        let mut index_writer = lock_index_writer(&USER_INDEX_WRITER);
+        index_writer.delete_term(user.index_term());
+        index_writer
+            .add_document(user.to_owned().into())
+            .into_diagnostic()?;
+        index_writer.commit().into_diagnostic()?;
+
         Ok(old_value)
     }
 
@@ -89,6 +261,12 @@ impl OkuDatabase {
         let rw = self.database.rw_transaction().into_diagnostic()?;
         let removed_user = rw.remove(user.to_owned()).into_diagnostic()?;
         rw.commit().into_diagnostic()?;
+        self.unindex_follows(&removed_user);
+
+        let mut index_writer = lock_index_writer(&USER_INDEX_WRITER);
+        index_writer.delete_term(removed_user.index_term());
+        index_writer.commit().into_diagnostic()?;
+
         Ok(removed_user)
     }
 
@@ -103,11 +281,21 @@ impl OkuDatabase {
     /// A list containing the deleted users.
     pub fn delete_users(&self, users: &[OkuUser]) -> miette::Result<Vec<OkuUser>> {
         let rw = self.database.rw_transaction().into_diagnostic()?;
-        let removed_users = users
+        let removed_users: Vec<_> = users
             .iter()
             .filter_map(|user| rw.remove(user.to_owned()).ok())
             .collect();
         rw.commit().into_diagnostic()?;
+        for removed_user in removed_users.iter() {
+            self.unindex_follows(removed_user);
+        }
+
+        let mut index_writer = lock_index_writer(&USER_INDEX_WRITER);
+        removed_users.par_iter().for_each(|removed_user| {
+            index_writer.delete_term(removed_user.index_term());
+        });
+        index_writer.commit().into_diagnostic()?;
+
         Ok(removed_users)
     }
 
@@ -215,4 +403,74 @@ impl OkuDatabase {
             .primary(author_id.as_bytes().to_vec())
             .into_diagnostic()
     }
+
+    /// Records the users followed by an OkuNet user in the index of followers.
+    fn index_follows(&self, user: &OkuUser) {
+        for followed_user in user.identity.iter().flat_map(|x| x.following.iter()) {
+            self.follower_index
+                .entry(*followed_user)
+                .or_default()
+                .insert(user.author_id);
+        }
+    }
+
+    /// Removes the users followed by an OkuNet user from the index of followers.
+    fn unindex_follows(&self, user: &OkuUser) {
+        for followed_user in user.identity.iter().flat_map(|x| x.following.iter()) {
+            if let Some(mut followers) = self.follower_index.get_mut(followed_user) {
+                followers.remove(&user.author_id);
+            }
+            self.follower_index
+                .remove_if(followed_user, |_, followers| followers.is_empty());
+        }
+    }
+
+    /// Rebuilds the index of followers from the identities of all known users.
+    pub(crate) fn rebuild_follower_index(&self) -> miette::Result<()> {
+        self.follower_index.clear();
+        for user in self.get_users()? {
+            self.index_follows(&user);
+        }
+        Ok(())
+    }
+
+    /// Gets the known followers of an OkuNet user.
+    ///
+    /// Only users whose identities have been retrieved are known to follow others.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - A content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The content authorship IDs of the known users following the given user.
+    pub fn followers_of(&self, author_id: &AuthorId) -> HashSet<AuthorId> {
+        self.follower_index
+            .get(author_id)
+            .map(|x| x.clone())
+            .unwrap_or_default()
+    }
+
+    /// Gets the known users who both follow, and are followed by, an OkuNet user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - A content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The content authorship IDs of the known users mutually following the given user.
+    pub fn mutuals(&self, author_id: &AuthorId) -> miette::Result<HashSet<AuthorId>> {
+        let following = self
+            .get_user(author_id)?
+            .and_then(|x| x.identity)
+            .map(|x| x.following)
+            .unwrap_or_default();
+        Ok(self
+            .followers_of(author_id)
+            .into_iter()
+            .filter(|x| following.contains(x))
+            .collect())
+    }
 }
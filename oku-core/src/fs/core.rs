@@ -1,11 +1,12 @@
 use super::*;
 use crate::{config::OkuFsConfig, error::OkuFsError, fs::util::path_to_entry_key};
 use bytes::Bytes;
+use dashmap::DashMap;
 use iroh::protocol::ProtocolHandler;
 #[cfg(feature = "persistent")]
 use iroh_blobs::store::fs::FsStore;
 use iroh_blobs::{api::Store, store::mem::MemStore, BlobsProtocol};
-use iroh_docs::{Author, NamespaceId};
+use iroh_docs::{Author, AuthorId, NamespaceId};
 use iroh_gossip::Gossip;
 use log::{error, info, trace};
 use miette::IntoDiagnostic;
@@ -115,6 +116,8 @@ impl OkuFs {
         let (okunet_fetch_sender, _okunet_fetch_receiver) = watch::channel(false);
         let (okunet_post_sender, _okunet_post_receiver) = watch::channel(());
         let (okunet_user_sender, _okunet_user_receiver) = watch::channel(());
+        let (okunet_draft_sender, _okunet_draft_receiver) = watch::channel(());
+        let (okunet_message_sender, _okunet_message_receiver) = watch::channel(());
 
         let docs_client = docs.clone();
         let blobs_client = blobs.clone();
@@ -173,6 +176,21 @@ impl OkuFs {
             .async_eviction_listener(eviction_listener)
             .build();
 
+        cfg_if::cfg_if!(
+            if #[cfg(any(feature = "persistent"))] {
+                let config = match persistent {
+                    true => OkuFsConfig::load_or_create_config().unwrap_or_default(),
+                    false => OkuFsConfig::default()
+                };
+            } else {
+                let config = OkuFsConfig::default();
+            }
+        );
+        let republish_delay = config.get_republish_delay();
+        let initial_publish_delay = config.get_initial_publish_delay();
+        let blocklist_cache: Cache<AuthorId, HashSet<AuthorId>> =
+            Cache::builder().time_to_live(republish_delay).build();
+
         let oku_core = Self {
             endpoint,
             blobs,
@@ -182,26 +200,18 @@ impl OkuFs {
             okunet_fetch_sender,
             okunet_post_sender,
             okunet_user_sender,
+            okunet_draft_sender,
+            okunet_message_sender,
             #[cfg(feature = "fuse")]
             fuse_handler: DebugIgnore::from(Arc::new(DefaultFuseHandler::new())),
             #[cfg(feature = "fuse")]
             handle: handle.cloned(),
             dht: mainline::Dht::server()?.as_async(),
             file_cache,
+            blocklist_cache,
+            live_subscriptions: Arc::new(DashMap::new()),
         };
         let oku_core_clone = oku_core.clone();
-        cfg_if::cfg_if!(
-            if #[cfg(any(feature = "persistent"))] {
-                let config = match persistent {
-                    true => OkuFsConfig::load_or_create_config().unwrap_or_default(),
-                    false => OkuFsConfig::default()
-                };
-            } else {
-                let config = OkuFsConfig::default();
-            }
-        );
-        let republish_delay = config.get_republish_delay();
-        let initial_publish_delay = config.get_initial_publish_delay();
 
         tokio::spawn(async move {
             tokio::time::sleep(initial_publish_delay).await;
@@ -211,9 +221,26 @@ impl OkuFs {
                     Err(e) => error!("{}", e),
                 }
                 match oku_core_clone.refresh_users().await {
-                    Ok(_) => info!("Refreshed OkuNet database … "),
+                    Ok(report) => info!(
+                        "Refreshed OkuNet database ({} users recorded, {} could not be retrieved) … ",
+                        report.users.len(),
+                        report.failures.len()
+                    ),
                     Err(e) => error!("{}", e),
                 }
+                let refreshed_inboxes = oku_core_clone.refresh_messages().await;
+                info!("Fetched direct messages from {refreshed_inboxes} users … ");
+                oku_core_clone.refresh_live_subscriptions().await;
+                tokio::time::sleep(republish_delay).await;
+            }
+        });
+        // Imported feeds are refreshed separately, so slow feeds do not hold up OkuNet
+        let oku_core_clone = oku_core.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(initial_publish_delay).await;
+            loop {
+                let refreshed_feeds = oku_core_clone.refresh_external_feeds().await;
+                info!("Refreshed {refreshed_feeds} imported feeds … ");
                 tokio::time::sleep(republish_delay).await;
             }
         });
@@ -223,6 +250,11 @@ impl OkuFs {
     /// Shuts down the Oku file system.
     pub async fn shutdown(self) {
         info!("Node shutting down … ");
+        for subscription in self.live_subscriptions.iter() {
+            if let Some(subscription) = subscription.value() {
+                subscription.abort();
+            }
+        }
         self.endpoint.close().await;
         if let Err(e) = self.router.shutdown().await {
             error!("{e}");
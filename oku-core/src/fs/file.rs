@@ -26,22 +26,22 @@ use util::path_to_entry_key;
 use util::path_to_entry_prefix;
 
 impl OkuFs {
-    /// Lists files in a replica.
+    /// Lists the latest entries of files in a replica.
     ///
     /// # Arguments
     ///
-    /// * `namespace_id` - The ID of the replica to list files in.
+    /// * `namespace_id` - The ID of the replica to list entries in.
     ///
     /// * `path` - An optional path within the replica.
     ///
     /// # Returns
     ///
-    /// A list of files in the replica.
-    pub async fn list_files(
+    /// The latest entry of each file in the replica.
+    pub async fn list_entries(
         &self,
         namespace_id: &NamespaceId,
         path: &Option<PathBuf>,
-    ) -> miette::Result<Vec<PathBuf>> {
+    ) -> miette::Result<Vec<Entry>> {
         let docs_client = &self.docs;
         let document = docs_client
             .open(*namespace_id)
@@ -64,11 +64,35 @@ impl OkuFs {
             OkuFsError::CannotListFiles
         })?;
         pin_mut!(entries);
-        let files = entries
-            .filter_map(|entry| async { entry.ok().and_then(|x| entry_key_to_path(x.key()).ok()) })
+        let entries = entries
+            .filter_map(|entry| async { entry.ok() })
             .collect::<Vec<_>>()
             .await;
-        Ok(files)
+        Ok(entries)
+    }
+
+    /// Lists files in a replica.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace_id` - The ID of the replica to list files in.
+    ///
+    /// * `path` - An optional path within the replica.
+    ///
+    /// # Returns
+    ///
+    /// A list of files in the replica.
+    pub async fn list_files(
+        &self,
+        namespace_id: &NamespaceId,
+        path: &Option<PathBuf>,
+    ) -> miette::Result<Vec<PathBuf>> {
+        Ok(self
+            .list_entries(namespace_id, path)
+            .await?
+            .into_iter()
+            .filter_map(|entry| entry_key_to_path(entry.key()).ok())
+            .collect())
     }
 
     /// Creates a file.
@@ -1,10 +1,12 @@
+use dashmap::DashMap;
 #[cfg(feature = "fuse")]
 use debug_ignore::DebugIgnore;
 #[cfg(feature = "fuse")]
 use easy_fuser::fuse_presets::DefaultFuseHandler;
 use iroh_blobs::BlobsProtocol;
-use iroh_docs::{protocol::Docs, NamespaceId};
+use iroh_docs::{protocol::Docs, AuthorId, NamespaceId};
 use moka::future::Cache;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 #[cfg(feature = "persistent")]
@@ -51,6 +53,10 @@ pub struct OkuFs {
     pub okunet_post_sender: Sender<()>,
     /// A watcher for when OkuNet user records are created, deleted, or modified.
     pub okunet_user_sender: Sender<()>,
+    /// A watcher for when OkuNet drafts are saved, published, or discarded.
+    pub okunet_draft_sender: Sender<()>,
+    /// A watcher for when direct messages are sent or received.
+    pub okunet_message_sender: Sender<()>,
     /// A watcher for whether or not content is being fetched from the OkuNet.
     pub okunet_fetch_sender: Sender<bool>,
     #[cfg(feature = "fuse")]
@@ -60,4 +66,7 @@ pub struct OkuFs {
     pub(crate) handle: Option<Handle>,
     pub(crate) dht: mainline::async_dht::AsyncDht,
     pub(crate) file_cache: Cache<(NamespaceId, PathBuf), Arc<Mutex<NamedTempFile>>>,
+    pub(crate) blocklist_cache: Cache<AuthorId, HashSet<AuthorId>>,
+    /// Live subscriptions on the home replicas of followed users; a subscription being opened has no task yet.
+    pub(crate) live_subscriptions: Arc<DashMap<AuthorId, Option<tokio::task::AbortHandle>>>,
 }
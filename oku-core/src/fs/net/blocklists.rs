@@ -0,0 +1,181 @@
+use crate::{
+    database::{core::DATABASE, posts::core::OkuPost},
+    fs::OkuFs,
+};
+use iroh_blobs::Hash;
+use iroh_docs::AuthorId;
+use log::error;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::HashSet;
+
+impl OkuFs {
+    /// Fetch the latest version of an OkuNet user's blocked list, regardless of when last retrieved.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user whose blocked list should be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// The content authors blocked by the user.
+    pub async fn fetch_blocklist(&self, author_id: &AuthorId) -> miette::Result<HashSet<AuthorId>> {
+        let ticket = self
+            .resolve_author_id(author_id)
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let blocked = self.fetch_profile(&ticket).await?.blocked;
+        self.blocklist_cache
+            .insert(*author_id, blocked.clone())
+            .await;
+        Ok(blocked)
+    }
+
+    /// Obtain an OkuNet user's blocked list.
+    ///
+    /// If last retrieved longer than [`crate::config::OkuFsConfig::get_republish_delay`] ago, the blocked list will be re-fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user whose blocked list should be retrieved.
+    ///
+    /// # Returns
+    ///
+    /// The content authors blocked by the user.
+    pub async fn blocklist(&self, author_id: &AuthorId) -> HashSet<AuthorId> {
+        if let Some(blocked) = self.blocklist_cache.get(author_id).await {
+            return blocked;
+        }
+        if let Some(identity) = DATABASE
+            .get_user(author_id)
+            .ok()
+            .flatten()
+            .and_then(|x| x.identity)
+        {
+            self.blocklist_cache
+                .insert(*author_id, identity.blocked.clone())
+                .await;
+            return identity.blocked;
+        }
+        self.fetch_blocklist(author_id).await.unwrap_or_else(|e| {
+            error!("{}", e);
+            HashSet::new()
+        })
+    }
+
+    /// Determines every author blocked by the local user, either directly or through a subscribed blocked list.
+    ///
+    /// Authors on the local user's allowed list are never blocked through a subscription.
+    ///
+    /// # Returns
+    ///
+    /// The content authors whose OkuNet content should be ignored.
+    pub async fn blocked_users(&self) -> HashSet<AuthorId> {
+        let identity = self.identity().await.unwrap_or_default();
+        let me = self.default_author().await;
+        let mut blocked = identity.blocked.clone();
+        for subscription in identity.blocklist_subscriptions.iter() {
+            blocked.extend(
+                self.blocklist(subscription)
+                    .await
+                    .into_iter()
+                    .filter(|x| !identity.allowed.contains(x)),
+            );
+        }
+        blocked.remove(&me);
+        blocked
+    }
+
+    /// Removes posts by blocked authors from a list of OkuNet posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `posts` - A list of OkuNet posts.
+    ///
+    /// # Returns
+    ///
+    /// The posts whose authors are not blocked, either directly or through a subscribed blocked list.
+    pub async fn without_blocked_posts(&self, posts: Vec<OkuPost>) -> Vec<OkuPost> {
+        let blocked = self.blocked_users().await;
+        if blocked.is_empty() {
+            return posts;
+        }
+        posts
+            .into_par_iter()
+            .filter(|x| !blocked.contains(&x.entry.author()))
+            .collect()
+    }
+
+    /// Subscribe to a user's blocked list, blocking every author they block.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user whose blocked list should be applied.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the new identity file in the local user's home replica, if it didn't already exist.
+    pub async fn subscribe_to_blocklist(
+        &self,
+        author_id: &AuthorId,
+    ) -> miette::Result<Option<Hash>> {
+        let mut identity = self.identity().await.unwrap_or_default();
+        identity.blocklist_subscriptions.insert(*author_id);
+        let hash = self.set_identity(&identity).await;
+        self.okunet_post_sender.send_replace(());
+        hash
+    }
+
+    /// Unsubscribe from a user's blocked list.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user whose blocked list should no longer be applied.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the new identity file in the local user's home replica, if it didn't already exist.
+    pub async fn unsubscribe_from_blocklist(
+        &self,
+        author_id: &AuthorId,
+    ) -> miette::Result<Option<Hash>> {
+        let mut identity = self.identity().await.unwrap_or_default();
+        identity.blocklist_subscriptions.remove(author_id);
+        let hash = self.set_identity(&identity).await;
+        self.okunet_post_sender.send_replace(());
+        hash
+    }
+
+    /// Exempt a user from the blocked lists the local user subscribes to.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The user to allow's content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the new identity file in the local user's home replica, if it didn't already exist.
+    pub async fn allow(&self, author_id: &AuthorId) -> miette::Result<Option<Hash>> {
+        let mut identity = self.identity().await.unwrap_or_default();
+        identity.allowed.insert(*author_id);
+        let hash = self.set_identity(&identity).await;
+        self.okunet_post_sender.send_replace(());
+        hash
+    }
+
+    /// Stop exempting a user from the blocked lists the local user subscribes to.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The previously-allowed user's content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the new identity file in the local user's home replica, if it didn't already exist.
+    pub async fn disallow(&self, author_id: &AuthorId) -> miette::Result<Option<Hash>> {
+        let mut identity = self.identity().await.unwrap_or_default();
+        identity.allowed.remove(author_id);
+        let hash = self.set_identity(&identity).await;
+        self.okunet_post_sender.send_replace(());
+        hash
+    }
+}
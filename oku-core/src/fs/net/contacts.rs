@@ -0,0 +1,117 @@
+use crate::{
+    database::{contacts::OkuContact, core::DATABASE},
+    fs::OkuFs,
+};
+use iroh_docs::AuthorId;
+
+impl OkuFs {
+    /// Retrieves what the local user knows about other OkuNet users.
+    ///
+    /// # Returns
+    ///
+    /// A list of the contacts saved on this node.
+    pub async fn contacts(&self) -> Vec<OkuContact> {
+        DATABASE.get_contacts().unwrap_or_default()
+    }
+
+    /// Retrieves what the local user knows about an OkuNet user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user.
+    ///
+    /// # Returns
+    ///
+    /// The contact, if the user has been given a petname or notes.
+    pub async fn contact(&self, author_id: &AuthorId) -> Option<OkuContact> {
+        DATABASE.get_contact(author_id).ok().flatten()
+    }
+
+    /// Give an OkuNet user a private nickname, shown in place of their self-chosen display name.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user.
+    ///
+    /// * `petname` - The new petname, or none if the user's petname should be removed.
+    ///
+    /// # Returns
+    ///
+    /// The contact, with the petname changed.
+    pub async fn set_petname(
+        &self,
+        author_id: &AuthorId,
+        petname: &Option<String>,
+    ) -> miette::Result<OkuContact> {
+        let petname = petname
+            .as_ref()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty());
+        if let Some(petname) = &petname {
+            if let Some(other_contact) = DATABASE.get_contacts()?.into_iter().find(|x| {
+                x.author_id != *author_id
+                    && x.petname
+                        .as_ref()
+                        .is_some_and(|y| y.to_lowercase() == petname.to_lowercase())
+            }) {
+                return Err(miette::miette!(
+                    "The petname {} is already given to {} … ",
+                    petname,
+                    crate::fs::util::fmt(other_contact.author_id)
+                ));
+            }
+        }
+        let mut contact = self.contact(author_id).await.unwrap_or(OkuContact {
+            author_id: *author_id,
+            petname: None,
+            notes: None,
+        });
+        contact.petname = petname;
+        self.save_contact(&contact)?;
+        Ok(contact)
+    }
+
+    /// Replace the local user's notes about an OkuNet user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user.
+    ///
+    /// * `notes` - The new notes, or none if the notes should be removed.
+    ///
+    /// # Returns
+    ///
+    /// The contact, with the notes changed.
+    pub async fn set_contact_notes(
+        &self,
+        author_id: &AuthorId,
+        notes: &Option<String>,
+    ) -> miette::Result<OkuContact> {
+        let mut contact = self.contact(author_id).await.unwrap_or(OkuContact {
+            author_id: *author_id,
+            petname: None,
+            notes: None,
+        });
+        contact.notes = notes
+            .as_ref()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty());
+        self.save_contact(&contact)?;
+        Ok(contact)
+    }
+
+    /// Save a contact, removing it if nothing is known about the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `contact` - The contact to save.
+    fn save_contact(&self, contact: &OkuContact) -> miette::Result<()> {
+        match contact.petname.is_none() && contact.notes.is_none() {
+            true => DATABASE.delete_contact(&contact.author_id).map(|_| ())?,
+            false => DATABASE.upsert_contact(contact).map(|_| ())?,
+        }
+        // Names shown alongside posts may have changed
+        self.okunet_post_sender.send_replace(());
+        Ok(())
+    }
+}
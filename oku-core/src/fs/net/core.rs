@@ -1,9 +1,25 @@
 use crate::fs::util::{path_to_entry_key, path_to_entry_prefix};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
 use iroh_docs::store::FilterKind;
 use iroh_docs::Author;
+use iroh_docs::AuthorId;
 use iroh_docs::DocTicket;
 use iroh_docs::NamespaceId;
+use miette::IntoDiagnostic;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The default number of levels of the social graph to crawl when refreshing OkuNet users; the followed users, and the users they follow.
+pub const DEFAULT_CRAWL_DEPTH: usize = 2;
+/// The default maximum number of OkuNet users to fetch at once.
+pub const DEFAULT_CRAWL_PARALLELISM: usize = 8;
+/// The default number of posts in a page of a timeline.
+pub const DEFAULT_TIMELINE_PAGE_SIZE: usize = 50;
+/// The context in which the names of the directories holding direct messages are derived.
+const INBOX_CONTEXT: &str = "oku direct message inbox";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 /// An Oku user's credentials, which are sensitive, exported from a node, able to be imported into another.
@@ -35,3 +51,149 @@ pub fn home_replica_filters() -> Vec<FilterKind> {
     let posts_filter = FilterKind::Prefix(path_to_entry_prefix(&"/posts/".into()));
     vec![profile_filter, posts_filter]
 }
+
+/// The directory of a home replica holding the direct messages its owner has sent to a particular user.
+///
+/// The directory is named with a key derived from the secret shared by the two users, so that others cannot learn whom the messages are sent to.
+/// The messages' entries remain public, so it can still be seen when, and how many, messages were sent.
+///
+/// # Arguments
+///
+/// * `author` - The private key of the local user's authorship credentials.
+///
+/// * `correspondent` - The content authorship ID of the other user.
+///
+/// # Returns
+///
+/// The path of the directory holding messages sent between the local user and the other user, in either user's home replica.
+pub fn inbox_path(author: &Author, correspondent: &AuthorId) -> miette::Result<PathBuf> {
+    Ok(PathBuf::from(format!(
+        "/inbox/{}/",
+        bs58::encode(shared_key(author, correspondent, INBOX_CONTEXT)?).into_string()
+    )))
+}
+
+/// Filters to download the direct messages sent to the local user by the owner of a home replica, alongside the content allowed by [`home_replica_filters`].
+///
+/// # Arguments
+///
+/// * `author` - The private key of the local user's authorship credentials.
+///
+/// * `correspondent` - The content authorship ID of the owner of the home replica.
+///
+/// # Returns
+///
+/// The download filters specifying the only content allowed to be downloaded from the other user's home replica by the local user.
+pub fn inbox_filters(author: &Author, correspondent: &AuthorId) -> miette::Result<Vec<FilterKind>> {
+    let mut filters = home_replica_filters();
+    filters.push(FilterKind::Prefix(path_to_entry_prefix(&inbox_path(
+        author,
+        correspondent,
+    )?)));
+    Ok(filters)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Data encrypted with a key shared by its readers.
+pub(crate) struct OkuCiphertext {
+    /// The nonce the data was encrypted with, in hexadecimal.
+    pub(crate) nonce: String,
+    /// The encrypted data, in hexadecimal.
+    pub(crate) ciphertext: String,
+}
+
+impl OkuCiphertext {
+    /// Encrypt data.
+    ///
+    /// # Arguments
+    ///
+    /// * `cipher` - The cipher to encrypt the data with.
+    ///
+    /// * `plaintext` - The data to encrypt.
+    ///
+    /// # Returns
+    ///
+    /// The encrypted data, alongside the randomly-generated nonce it was encrypted with.
+    pub(crate) fn seal(cipher: &XChaCha20Poly1305, plaintext: &[u8]) -> miette::Result<Self> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| miette::miette!("{}", e))?;
+        Ok(Self {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypt data.
+    ///
+    /// # Arguments
+    ///
+    /// * `cipher` - The cipher the data was encrypted with.
+    ///
+    /// # Returns
+    ///
+    /// The decrypted data.
+    pub(crate) fn open(&self, cipher: &XChaCha20Poly1305) -> miette::Result<Vec<u8>> {
+        let nonce = hex::decode(&self.nonce).into_diagnostic()?;
+        if nonce.len() != 24 {
+            return Err(miette::miette!("Encrypted data has a malformed nonce … "));
+        }
+        let ciphertext = hex::decode(&self.ciphertext).into_diagnostic()?;
+        cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|e| miette::miette!("{}", e))
+    }
+}
+
+/// Derive a key shared between two users.
+///
+/// The users' Ed25519 authorship keys are converted to X25519 keys, and the key is derived from their shared secret; each user can derive it using their own private key and the other's public key.
+///
+/// # Arguments
+///
+/// * `author` - The private key of the local user's authorship credentials.
+///
+/// * `correspondent` - The content authorship ID of the other user, who may be the local user.
+///
+/// * `context` - What the key is used for; keys derived for different purposes differ.
+///
+/// # Returns
+///
+/// A key known only to the two users.
+fn shared_key(
+    author: &Author,
+    correspondent: &AuthorId,
+    context: &str,
+) -> miette::Result<[u8; 32]> {
+    let signing_key = ed25519_dalek::SigningKey::from_bytes(&author.to_bytes());
+    let secret = x25519_dalek::StaticSecret::from(signing_key.to_scalar_bytes());
+    let verifying_key =
+        ed25519_dalek::VerifyingKey::from_bytes(correspondent.as_bytes()).into_diagnostic()?;
+    let public = x25519_dalek::PublicKey::from(verifying_key.to_montgomery().to_bytes());
+    let shared_secret = secret.diffie_hellman(&public);
+    Ok(blake3::derive_key(context, shared_secret.as_bytes()))
+}
+
+/// Derive a cipher shared between two users.
+///
+/// # Arguments
+///
+/// * `author` - The private key of the local user's authorship credentials.
+///
+/// * `correspondent` - The content authorship ID of the other user, who may be the local user.
+///
+/// * `context` - What the cipher is used for; ciphers derived for different purposes do not share a key.
+///
+/// # Returns
+///
+/// A cipher able to encrypt and decrypt data shared between the two users.
+pub(crate) fn shared_cipher(
+    author: &Author,
+    correspondent: &AuthorId,
+    context: &str,
+) -> miette::Result<XChaCha20Poly1305> {
+    Ok(XChaCha20Poly1305::new(
+        &shared_key(author, correspondent, context)?.into(),
+    ))
+}
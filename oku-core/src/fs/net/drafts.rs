@@ -0,0 +1,139 @@
+use crate::{
+    database::{
+        core::DATABASE,
+        drafts::OkuDraft,
+        posts::core::{OkuNote, OkuVisibility},
+    },
+    fs::OkuFs,
+};
+use iroh_blobs::Hash;
+use iroh_docs::NamespaceId;
+use rayon::slice::ParallelSliceMut;
+use std::{cmp::Reverse, collections::HashSet, path::PathBuf, time::SystemTime};
+use url::Url;
+
+impl OkuFs {
+    /// Retrieves the drafts saved on this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of drafts, from most to least recently saved.
+    pub async fn drafts(&self) -> Vec<OkuDraft> {
+        let mut drafts = DATABASE.get_drafts().unwrap_or_default();
+        drafts.par_sort_unstable_by_key(|x| Reverse(x.last_modified));
+        drafts
+    }
+
+    /// Retrieves a draft saved on this node.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the draft is regarding.
+    ///
+    /// # Returns
+    ///
+    /// The draft regarding the given URL, if one exists.
+    pub async fn draft(&self, url: &Url) -> Option<OkuDraft> {
+        DATABASE.get_draft(url).ok().flatten()
+    }
+
+    /// Create or modify a draft; drafts are kept on this node and are not visible to other OkuNet users until published.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the draft is regarding.
+    ///
+    /// * `title` - The title of the draft.
+    ///
+    /// * `body` - The body of the draft.
+    ///
+    /// * `tags` - A list of tags associated with the draft.
+    ///
+    /// * `content_warning` - An optional warning shown in place of the draft's body.
+    ///
+    /// * `sensitive` - Whether the draft regards sensitive content.
+    ///
+    /// * `visibility` - Who should be able to read the draft once published.
+    ///
+    /// # Returns
+    ///
+    /// The previous version of the draft, if one existed.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn save_draft(
+        &self,
+        url: &Url,
+        title: &String,
+        body: &String,
+        tags: &HashSet<String>,
+        content_warning: &Option<String>,
+        sensitive: bool,
+        visibility: &OkuVisibility,
+    ) -> miette::Result<Option<OkuDraft>> {
+        let old_draft = DATABASE.upsert_draft(&OkuDraft {
+            note: OkuNote {
+                url: url.clone(),
+                title: title.to_string(),
+                body: body.to_string(),
+                tags: tags.clone(),
+                content_warning: content_warning.clone(),
+                sensitive,
+                visibility: visibility.clone(),
+            },
+            last_modified: SystemTime::now(),
+        });
+        self.okunet_draft_sender.send_replace(());
+        old_draft
+    }
+
+    /// Publish a draft as an OkuNet post in the user's home replica, removing it from the drafts.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the draft is regarding.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the user's home replica, the path to the post file, and a hash of the post's content if the post is new.
+    pub async fn publish_draft(
+        &self,
+        url: &Url,
+    ) -> miette::Result<(NamespaceId, PathBuf, Option<Hash>)> {
+        let draft = self
+            .draft(url)
+            .await
+            .ok_or(miette::miette!("No draft regarding {} found … ", url))?;
+        let published = self
+            .create_or_modify_post(
+                &draft.note.url,
+                &draft.note.title,
+                &draft.note.body,
+                &draft.note.tags,
+                &draft.note.content_warning,
+                draft.note.sensitive,
+                &draft.note.visibility,
+            )
+            .await?;
+        DATABASE.delete_draft(&draft)?;
+        self.okunet_draft_sender.send_replace(());
+        Ok(published)
+    }
+
+    /// Discard a draft without publishing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the draft is regarding.
+    ///
+    /// # Returns
+    ///
+    /// The discarded draft.
+    pub async fn discard_draft(&self, url: &Url) -> miette::Result<OkuDraft> {
+        let draft = self
+            .draft(url)
+            .await
+            .ok_or(miette::miette!("No draft regarding {} found … ", url))?;
+        let discarded = DATABASE.delete_draft(&draft);
+        self.okunet_draft_sender.send_replace(());
+        discarded
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use crate::database::posts::core::OkuVisibility;
+    use std::collections::HashSet;
+    use url::Url;
+
+    #[tokio::test]
+    async fn test_draft_save_and_publish() -> Result<(), Box<dyn std::error::Error>> {
+        let url = Url::parse("https://example.com/drafts/publish")?;
+        let tags = HashSet::from(["test".to_string()]);
+        let node = crate::fs::OkuFs::start(
+            #[cfg(feature = "fuse")]
+            None,
+            #[cfg(feature = "persistent")]
+            false,
+        )
+        .await?;
+
+        // Test saving a new draft
+        let old_draft = node
+            .save_draft(
+                &url,
+                &"First title".to_string(),
+                &"First body".to_string(),
+                &tags,
+                &None,
+                false,
+                &OkuVisibility::Public,
+            )
+            .await?;
+        assert!(old_draft.is_none());
+
+        // Test replacing a draft
+        let content_warning = Some("Spoilers".to_string());
+        let old_draft = node
+            .save_draft(
+                &url,
+                &"Second title".to_string(),
+                &"Second body".to_string(),
+                &tags,
+                &content_warning,
+                true,
+                &OkuVisibility::Public,
+            )
+            .await?;
+        assert_eq!(
+            Some("First title".to_string()),
+            old_draft.map(|x| x.note.title)
+        );
+        let draft = node.draft(&url).await.ok_or("Draft was not saved")?;
+        assert_eq!("Second title", draft.note.title);
+        assert_eq!(content_warning, draft.note.content_warning);
+        assert!(draft.note.sensitive);
+        assert_eq!(
+            1,
+            node.drafts()
+                .await
+                .iter()
+                .filter(|x| x.note.url == url)
+                .count()
+        );
+
+        // Test publishing the draft
+        let (_, post_path, _) = node.publish_draft(&url).await?;
+        assert!(node.draft(&url).await.is_none());
+        let post = node.post(&post_path).await?;
+        assert_eq!(url, post.note.url);
+        assert_eq!("Second title", post.note.title);
+        assert_eq!("Second body", post.note.body);
+        assert_eq!(tags, post.note.tags);
+        assert_eq!(content_warning, post.note.content_warning);
+        assert!(post.note.sensitive);
+
+        // A published draft cannot be published again
+        assert!(node.publish_draft(&url).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_draft_discard() -> Result<(), Box<dyn std::error::Error>> {
+        let url = Url::parse("https://example.com/drafts/discard")?;
+        let node = crate::fs::OkuFs::start(
+            #[cfg(feature = "fuse")]
+            None,
+            #[cfg(feature = "persistent")]
+            false,
+        )
+        .await?;
+        node.save_draft(
+            &url,
+            &"Title".to_string(),
+            &"Body".to_string(),
+            &HashSet::new(),
+            &None,
+            false,
+            &OkuVisibility::Public,
+        )
+        .await?;
+
+        // Test discarding the draft
+        let discarded = node.discard_draft(&url).await?;
+        assert_eq!(url, discarded.note.url);
+        assert!(node.draft(&url).await.is_none());
+        assert!(!node.drafts().await.iter().any(|x| x.note.url == url));
+
+        // A discarded draft is not published
+        assert!(node.publish_draft(&url).await.is_err());
+        assert!(node.discard_draft(&url).await.is_err());
+
+        Ok(())
+    }
+}
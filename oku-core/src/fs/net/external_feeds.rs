@@ -0,0 +1,185 @@
+use crate::{
+    database::{
+        core::DATABASE,
+        external_feeds::{OkuExternalFeed, OkuTimelineItem},
+        posts::core::OkuTimelineCursor,
+    },
+    fs::OkuFs,
+};
+use log::error;
+use miette::IntoDiagnostic;
+use rayon::slice::ParallelSliceMut;
+#[cfg(feature = "http")]
+use std::sync::LazyLock;
+use std::{cmp::Reverse, time::Duration};
+
+/// How long retrieving an imported feed may take before it is abandoned.
+pub const EXTERNAL_FEED_TIMEOUT: Duration = Duration::from_secs(30);
+/// The size of the largest feed document, in bytes, that will be read.
+pub const EXTERNAL_FEED_SIZE_LIMIT: u64 = 10 * 1024 * 1024;
+#[cfg(feature = "http")]
+static EXTERNAL_FEED_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(EXTERNAL_FEED_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+impl OkuFs {
+    /// Read an RSS or Atom document.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The HTTP(S) URL or file path of the document.
+    ///
+    /// # Returns
+    ///
+    /// The content of the document.
+    async fn read_external_feed(&self, source: &str) -> miette::Result<Vec<u8>> {
+        let too_large = || {
+            miette::miette!(
+                "{} is larger than {} bytes … ",
+                source,
+                EXTERNAL_FEED_SIZE_LIMIT
+            )
+        };
+        match source.starts_with("http://") || source.starts_with("https://") {
+            true => cfg_select! {
+                feature = "http" => {
+                    let mut response = EXTERNAL_FEED_CLIENT
+                        .get(source)
+                        .send()
+                        .await
+                        .into_diagnostic()?
+                        .error_for_status()
+                        .into_diagnostic()?;
+                    if response
+                        .content_length()
+                        .is_some_and(|x| x > EXTERNAL_FEED_SIZE_LIMIT)
+                    {
+                        return Err(too_large());
+                    }
+                    let mut bytes = Vec::new();
+                    while let Some(chunk) = response.chunk().await.into_diagnostic()? {
+                        if (bytes.len() + chunk.len()) as u64 > EXTERNAL_FEED_SIZE_LIMIT {
+                            return Err(too_large());
+                        }
+                        bytes.extend_from_slice(&chunk);
+                    }
+                    Ok(bytes)
+                },
+                _ => {
+                    Err(miette::miette!(
+                        "Retrieving feeds over HTTP requires the `http` feature … "
+                    ))
+                }
+            },
+            false => {
+                let path = source.strip_prefix("file://").unwrap_or(source);
+                if tokio::fs::metadata(path).await.into_diagnostic()?.len()
+                    > EXTERNAL_FEED_SIZE_LIMIT
+                {
+                    return Err(too_large());
+                }
+                tokio::fs::read(path).await.into_diagnostic()
+            }
+        }
+    }
+
+    /// Import an RSS or Atom feed from outside OkuNet; its items are shown in the home timeline and search results alongside OkuNet posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The HTTP(S) URL or file path of the feed.
+    ///
+    /// # Returns
+    ///
+    /// The imported feed, and the number of its items.
+    pub async fn import_external_feed(
+        &self,
+        source: &str,
+    ) -> miette::Result<(OkuExternalFeed, usize)> {
+        let source = source.trim();
+        let bytes = self.read_external_feed(source).await?;
+        let (feed, posts) = OkuExternalFeed::parse(source, &bytes)?;
+        DATABASE.upsert_external_feed(&feed, &posts)?;
+        self.okunet_post_sender.send_replace(());
+        Ok((feed, posts.len()))
+    }
+
+    /// Stop showing the items of an imported feed.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The HTTP(S) URL or file path of the feed.
+    ///
+    /// # Returns
+    ///
+    /// The removed feed, if it was imported.
+    pub async fn remove_external_feed(
+        &self,
+        source: &str,
+    ) -> miette::Result<Option<OkuExternalFeed>> {
+        let removed_feed = DATABASE.delete_external_feed(source.trim())?;
+        self.okunet_post_sender.send_replace(());
+        Ok(removed_feed)
+    }
+
+    /// Retrieves the feeds imported from outside OkuNet.
+    ///
+    /// # Returns
+    ///
+    /// A list of imported feeds.
+    pub async fn external_feeds(&self) -> Vec<OkuExternalFeed> {
+        DATABASE.get_external_feeds().unwrap_or_default()
+    }
+
+    /// Read every imported feed again, recording any new or changed items.
+    ///
+    /// # Returns
+    ///
+    /// The number of feeds which were read successfully.
+    pub async fn refresh_external_feeds(&self) -> usize {
+        let mut refreshed = 0;
+        for feed in self.external_feeds().await {
+            match self.import_external_feed(&feed.source).await {
+                Ok(_) => refreshed += 1,
+                Err(e) => error!("{}: {}", feed.source, e),
+            }
+        }
+        refreshed
+    }
+
+    /// Retrieves a page of the home timeline, made of OkuNet posts and the items of imported feeds.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - A cursor; if specified, only entries positioned before it in the timeline are retrieved.
+    ///
+    /// * `page_size` - The maximum number of entries to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A list of timeline entries, ordered from newest to oldest. The cursor of the last entry is the cursor for the next page.
+    pub async fn home_timeline(
+        &self,
+        before: &Option<OkuTimelineCursor>,
+        page_size: usize,
+    ) -> miette::Result<Vec<OkuTimelineItem>> {
+        let mut items: Vec<_> = self
+            .timeline(before, page_size)
+            .await?
+            .into_iter()
+            .map(OkuTimelineItem::Post)
+            .collect();
+        items.extend(
+            DATABASE
+                .get_external_timeline(before, page_size)?
+                .into_iter()
+                .map(OkuTimelineItem::External),
+        );
+        items.par_sort_unstable_by_key(|x| Reverse(x.cursor()));
+        items.truncate(page_size);
+        Ok(items)
+    }
+}
@@ -0,0 +1,280 @@
+use super::core::{inbox_filters, inbox_path};
+use crate::{
+    database::{core::DATABASE, posts::core::OkuPost},
+    fs::{util::entry_key_to_path, OkuFs},
+};
+use futures::StreamExt;
+use iroh_blobs::Hash;
+use iroh_docs::{
+    engine::LiveEvent,
+    store::DownloadPolicy,
+    sync::{ContentStatus, Entry},
+    AuthorId, NamespaceId,
+};
+use log::{debug, error, info};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+impl OkuFs {
+    /// Keep a live subscription open on an OkuNet user's home replica, recording their posts and direct messages as they arrive.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user to subscribe to.
+    pub async fn subscribe_to_user(&self, author_id: &AuthorId) -> miette::Result<()> {
+        // The subscription is reserved before it is opened, so it is not opened twice at once
+        match self.live_subscriptions.entry(*author_id) {
+            dashmap::mapref::entry::Entry::Occupied(_) => return Ok(()),
+            dashmap::mapref::entry::Entry::Vacant(reservation) => {
+                reservation.insert(None);
+            }
+        }
+        let subscription = match self.open_live_subscription(author_id).await {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                self.live_subscriptions
+                    .remove_if(author_id, |_, x| x.is_none());
+                return Err(e);
+            }
+        };
+        match self.live_subscriptions.get_mut(author_id) {
+            Some(mut reservation) if reservation.is_none() => {
+                *reservation = Some(subscription);
+            }
+            // The user was unsubscribed from while the subscription was being opened
+            _ => {
+                subscription.abort();
+                return Ok(());
+            }
+        }
+        // The subscription may have ended before it was recorded
+        self.live_subscriptions.remove_if(author_id, |_, x| {
+            x.as_ref().is_some_and(|x| x.is_finished())
+        });
+        debug!(
+            "Subscribed to live updates from {} … ",
+            crate::fs::util::fmt_short(author_id)
+        );
+        Ok(())
+    }
+
+    /// Open a live subscription on an OkuNet user's home replica.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user to subscribe to.
+    ///
+    /// # Returns
+    ///
+    /// A handle to the task receiving live updates from the user.
+    async fn open_live_subscription(
+        &self,
+        author_id: &AuthorId,
+    ) -> miette::Result<tokio::task::AbortHandle> {
+        let ticket = self
+            .resolve_author_id(author_id)
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let replica = self
+            .docs
+            .import_namespace(ticket.capability.clone())
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        replica
+            .set_download_policy(DownloadPolicy::NothingExcept(inbox_filters(
+                &author, author_id,
+            )?))
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let mut events = replica
+            .subscribe()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        replica
+            .start_sync(ticket.nodes.clone())
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+
+        let oku_fs = self.clone();
+        let author_id = *author_id;
+        let subscription = tokio::spawn(async move {
+            // Entries whose content has yet to be downloaded
+            let mut pending_entries: HashMap<Hash, Entry> = HashMap::new();
+            while let Some(event) = events.next().await {
+                let entry = match event {
+                    Ok(LiveEvent::InsertRemote {
+                        entry,
+                        content_status,
+                        ..
+                    }) => match content_status {
+                        ContentStatus::Complete => Some(entry),
+                        _ => {
+                            pending_entries.insert(entry.content_hash(), entry);
+                            None
+                        }
+                    },
+                    Ok(LiveEvent::ContentReady { hash }) => pending_entries.remove(&hash),
+                    Ok(_) => None,
+                    Err(e) => {
+                        error!("{}", e);
+                        None
+                    }
+                };
+                if let Some(entry) = entry {
+                    if let Err(e) = oku_fs.record_live_entry(&author_id, entry).await {
+                        error!("{}", e);
+                    }
+                }
+            }
+            // The subscription ended on its own, so it may be reopened later, unless it has been already
+            let task_id = tokio::task::id();
+            oku_fs.live_subscriptions.remove_if(&author_id, |_, x| {
+                x.as_ref().is_some_and(|x| x.id() == task_id)
+            });
+        });
+        Ok(subscription.abort_handle())
+    }
+
+    /// Close the live subscription on an OkuNet user's home replica, if one is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user to unsubscribe from.
+    pub async fn unsubscribe_from_user(&self, author_id: &AuthorId) -> miette::Result<()> {
+        if let Some((_, subscription)) = self.live_subscriptions.remove(author_id) {
+            if let Some(subscription) = subscription {
+                subscription.abort();
+            }
+            if let Some(replica) = self
+                .docs
+                .open(NamespaceId::from(author_id.as_bytes()))
+                .await
+                .map_err(|e| miette::miette!("{}", e))?
+            {
+                replica
+                    .leave()
+                    .await
+                    .map_err(|e| miette::miette!("{}", e))?;
+            }
+            debug!(
+                "Unsubscribed from live updates from {} … ",
+                crate::fs::util::fmt_short(author_id)
+            );
+        }
+        Ok(())
+    }
+
+    /// Opens live subscriptions on the home replicas of followed users, and closes those of users no longer followed or now blocked.
+    pub async fn refresh_live_subscriptions(&self) {
+        let following = self.identity().await.unwrap_or_default().following;
+        let blocked = self.blocked_users().await;
+        let followed: HashSet<_> = following.difference(&blocked).copied().collect();
+        let stale_subscriptions: Vec<_> = self
+            .live_subscriptions
+            .iter()
+            .map(|x| *x.key())
+            .filter(|x| !followed.contains(x))
+            .collect();
+        for author_id in stale_subscriptions {
+            if let Err(e) = self.unsubscribe_from_user(&author_id).await {
+                error!("{}", e);
+            }
+        }
+        for author_id in followed {
+            if let Err(e) = self.subscribe_to_user(&author_id).await {
+                error!("{}", e);
+            }
+        }
+        info!(
+            "Subscribed to live updates from {} users … ",
+            self.live_subscriptions.len()
+        );
+    }
+
+    /// Records an entry received from a subscribed OkuNet user's home replica.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the user the entry came from.
+    ///
+    /// * `entry` - An entry from the user's home replica, whose content has been downloaded.
+    async fn record_live_entry(&self, author_id: &AuthorId, entry: Entry) -> miette::Result<()> {
+        let mut user = DATABASE.get_user(author_id)?;
+        if entry.content_len() == 0 {
+            // Deletions are recorded as empty entries, covering every key with the entry's key as a prefix
+            let deleted_posts: Vec<_> = DATABASE
+                .get_posts_by_author(author_id)?
+                .into_par_iter()
+                .filter(|x| x.entry.key().starts_with(entry.key()))
+                .collect();
+            DATABASE.delete_posts(&deleted_posts)?;
+            if let Some(user) = user.as_mut() {
+                user.posts.retain(|x| !x.key().starts_with(entry.key()));
+            }
+            let deleted_messages: Vec<_> = DATABASE
+                .get_messages()?
+                .into_iter()
+                .filter(|x| x.sender() == *author_id && x.entry.key().starts_with(entry.key()))
+                .collect();
+            if !deleted_messages.is_empty() {
+                DATABASE.delete_messages(&deleted_messages)?;
+                self.okunet_message_sender.send_replace(());
+            }
+        } else {
+            let path = entry_key_to_path(entry.key())?;
+            let author = self
+                .get_author()
+                .await
+                .map_err(|e| miette::miette!("{}", e))?;
+            if path.starts_with(inbox_path(&author, author_id)?) {
+                if self.blocked_users().await.contains(author_id) {
+                    return Ok(());
+                }
+                let message = self.read_message(entry).await?;
+                DATABASE.upsert_messages(&vec![message])?;
+                self.okunet_message_sender.send_replace(());
+                return Ok(());
+            }
+            if !path.starts_with(Path::new("/posts/"))
+                || !matches!(path.extension(), Some(x) if x == "toml")
+            {
+                return Ok(());
+            }
+            let bytes = self
+                .content_bytes(&entry, &None, &None)
+                .await
+                .map_err(|e| miette::miette!("{}", e))?;
+            match self.decode_note(author_id, &bytes).await? {
+                Some(note) => {
+                    DATABASE.upsert_posts(&vec![OkuPost {
+                        entry: entry.clone(),
+                        note,
+                    }])?;
+                }
+                // The post is now restricted to other users
+                None => {
+                    if let Some(post) = DATABASE.get_post(author_id, &path)? {
+                        DATABASE.delete_posts(&vec![post])?;
+                    }
+                }
+            }
+            if let Some(user) = user.as_mut() {
+                user.posts.retain(|x| x.key() != entry.key());
+                user.posts.push(entry);
+            }
+        }
+        // Keep the recorded entries current, so the next fetch of this user need not re-read these posts
+        if let Some(user) = user {
+            DATABASE.upsert_user(&user)?;
+        }
+        self.okunet_post_sender.send_replace(());
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use super::core::{inbox_filters, inbox_path, shared_cipher, OkuCiphertext};
+use crate::{
+    config::OkuFsConfig,
+    database::{core::DATABASE, messages::OkuMessage},
+    fs::OkuFs,
+};
+use futures::StreamExt;
+use iroh_docs::{sync::Entry, AuthorId, DocTicket};
+use log::{debug, error};
+use miette::IntoDiagnostic;
+use std::{
+    collections::{HashMap, HashSet},
+    time::SystemTime,
+};
+
+/// The context in which ciphers for direct messages are derived.
+const MESSAGE_CONTEXT: &str = "oku direct message";
+
+impl OkuFs {
+    /// Send a direct message to an OkuNet user.
+    ///
+    /// The message is encrypted so that only the sender and recipient can read it, and written to the sender's home replica.
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The content authorship ID of the user to send the message to.
+    ///
+    /// * `body` - The body of the message.
+    ///
+    /// # Returns
+    ///
+    /// The sent message.
+    pub async fn send_message(
+        &self,
+        recipient: &AuthorId,
+        body: &String,
+    ) -> miette::Result<OkuMessage> {
+        let home_replica_id = self
+            .home_replica()
+            .await
+            .ok_or(miette::miette!("No home replica set … "))?;
+        if self.is_me(recipient).await {
+            return Err(miette::miette!(
+                "Cannot send a direct message to oneself … "
+            ));
+        }
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let encrypted_message = OkuCiphertext::seal(
+            &shared_cipher(&author, recipient, MESSAGE_CONTEXT)?,
+            body.as_bytes(),
+        )?;
+        let sent_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .into_diagnostic()?
+            .as_micros();
+        let path = inbox_path(&author, recipient)?.join(format!("{sent_at}.toml"));
+        self.create_or_replace_file(
+            &home_replica_id,
+            &path,
+            toml::to_string_pretty(&encrypted_message).into_diagnostic()?,
+        )
+        .await?;
+        let message = OkuMessage {
+            entry: self.get_entry(&home_replica_id, &path).await?,
+            recipient: *recipient,
+            body: body.to_owned(),
+        };
+        DATABASE.upsert_messages(&vec![message.clone()])?;
+        self.okunet_message_sender.send_replace(());
+        Ok(message)
+    }
+
+    /// Decrypt a direct message received by the local user.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry of the encrypted message file in the sender's home replica, whose content has been downloaded.
+    ///
+    /// # Returns
+    ///
+    /// The decrypted message.
+    pub(crate) async fn read_message(&self, entry: Entry) -> miette::Result<OkuMessage> {
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let bytes = self
+            .content_bytes(&entry, &None, &None)
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let encrypted_message =
+            toml::from_str::<OkuCiphertext>(String::from_utf8_lossy(&bytes).as_ref())
+                .into_diagnostic()?;
+        let body =
+            encrypted_message.open(&shared_cipher(&author, &entry.author(), MESSAGE_CONTEXT)?)?;
+        Ok(OkuMessage {
+            entry,
+            recipient: author.id(),
+            body: String::from_utf8_lossy(&body).to_string(),
+        })
+    }
+
+    /// Join a swarm to fetch the direct messages sent to the local user from a home replica.
+    ///
+    /// Messages from blocked users are dropped, as are messages the sender has since deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `ticket` - A ticket for the home replica of an OkuNet user.
+    ///
+    /// # Returns
+    ///
+    /// The direct messages sent to the local user by the owner of the home replica.
+    pub async fn fetch_messages(&self, ticket: &DocTicket) -> miette::Result<Vec<OkuMessage>> {
+        let namespace_id = ticket.capability.id();
+        let sender = AuthorId::from(namespace_id.as_bytes());
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        if sender == author.id() {
+            return Ok(Vec::new());
+        }
+        let known_messages: Vec<_> = DATABASE
+            .get_messages()?
+            .into_iter()
+            .filter(|x| x.sender() == sender)
+            .collect();
+        if self.blocked_users().await.contains(&sender) {
+            DATABASE.delete_messages(&known_messages)?;
+            return Ok(Vec::new());
+        }
+        let inbox = inbox_path(&author, &sender)?;
+        self.fetch_replica_by_ticket(
+            ticket,
+            &Some(inbox.clone()),
+            &Some(inbox_filters(&author, &sender)?),
+        )
+        .await
+        .map_err(|e| miette::miette!("{}", e))?;
+
+        let mut known_messages: HashMap<_, _> = known_messages
+            .into_iter()
+            .map(|x| (x.entry.key().to_vec(), x))
+            .collect();
+        let mut messages = Vec::new();
+        let mut new_messages = Vec::new();
+        for entry in self
+            .list_entries(&namespace_id, &Some(inbox))
+            .await?
+            .into_iter()
+            .filter(|x| x.content_len() > 0)
+        {
+            match known_messages
+                .remove(entry.key())
+                .filter(|x| x.entry.content_hash() == entry.content_hash())
+            {
+                Some(known_message) => messages.push(known_message),
+                None => match self.read_message(entry).await {
+                    Ok(message) => {
+                        new_messages.push(message.clone());
+                        messages.push(message);
+                    }
+                    Err(e) => error!("{}", e),
+                },
+            }
+        }
+        // Any known messages not found in the inbox were deleted by their sender
+        let deleted_messages: Vec<_> = known_messages.into_values().collect();
+        debug!(
+            "{} new and {} deleted direct messages from {} … ",
+            new_messages.len(),
+            deleted_messages.len(),
+            crate::fs::util::fmt_short(sender)
+        );
+        if !deleted_messages.is_empty() {
+            DATABASE.delete_messages(&deleted_messages)?;
+        }
+        if !new_messages.is_empty() {
+            DATABASE.upsert_messages(&new_messages)?;
+        }
+        if !deleted_messages.is_empty() || !new_messages.is_empty() {
+            self.okunet_message_sender.send_replace(());
+        }
+        Ok(messages)
+    }
+
+    /// Fetch the direct messages sent to the local user from the home replica of every OkuNet user known to this node.
+    ///
+    /// Known users are those recorded while crawling, contacts, and those the local user has already exchanged messages with; messages from any other user are not found until the user becomes known.
+    ///
+    /// # Returns
+    ///
+    /// The number of home replicas whose messages were fetched.
+    pub async fn refresh_messages(&self) -> usize {
+        let parallelism = cfg_select! {
+            feature = "persistent" => {
+                OkuFsConfig::load_or_create_config().unwrap_or_default().get_crawl_parallelism()
+            },
+            _ => {
+                OkuFsConfig::default().get_crawl_parallelism()
+            }
+        };
+        let me = self.default_author().await;
+        let mut correspondents: HashSet<_> = DATABASE.all_local_users().into_iter().collect();
+        correspondents.extend(self.contacts().await.into_iter().map(|x| x.author_id));
+        correspondents.extend(
+            DATABASE
+                .get_conversations(&me)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|x| x.correspondent(&me)),
+        );
+        correspondents.remove(&me);
+        futures::stream::iter(correspondents)
+            .map(|author_id| async move {
+                let ticket = self
+                    .resolve_author_id(&author_id)
+                    .await
+                    .map_err(|e| miette::miette!("{}", e))?;
+                self.fetch_messages(&ticket).await
+            })
+            .buffer_unordered(parallelism)
+            .filter_map(|x| async move {
+                if let Err(e) = &x {
+                    error!("{}", e);
+                }
+                x.ok()
+            })
+            .count()
+            .await
+    }
+
+    /// Retrieves the direct messages exchanged with an OkuNet user.
+    ///
+    /// # Arguments
+    ///
+    /// * `correspondent` - The content authorship ID of the other user.
+    ///
+    /// # Returns
+    ///
+    /// The messages exchanged with the other user, oldest first, or none if the user is blocked.
+    pub async fn conversation(&self, correspondent: &AuthorId) -> miette::Result<Vec<OkuMessage>> {
+        if self.blocked_users().await.contains(correspondent) {
+            return Ok(Vec::new());
+        }
+        DATABASE.get_conversation(&self.default_author().await, correspondent)
+    }
+
+    /// Retrieves the latest direct message of each of the local user's conversations.
+    ///
+    /// # Returns
+    ///
+    /// The latest message exchanged with each other user, newest first, excluding blocked users.
+    pub async fn conversations(&self) -> miette::Result<Vec<OkuMessage>> {
+        let me = self.default_author().await;
+        let blocked: HashSet<_> = self.blocked_users().await;
+        Ok(DATABASE
+            .get_conversations(&me)?
+            .into_iter()
+            .filter(|x| !blocked.contains(&x.correspondent(&me)))
+            .collect())
+    }
+}
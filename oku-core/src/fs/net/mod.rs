@@ -1,6 +1,25 @@
+/// OkuNet blocklist-related node functionality.
+pub mod blocklists;
+/// OkuNet contact-related node functionality.
+pub mod contacts;
 /// Common OkuNet code.
 pub mod core;
+/// OkuNet draft-related node functionality.
+pub mod drafts;
+/// Node functionality relating to RSS and Atom feeds imported from outside OkuNet.
+pub mod external_feeds;
+/// Live OkuNet updates from followed users.
+pub mod live;
+/// OkuNet direct message-related node functionality.
+pub mod messages;
+/// OkuNet mute-related node functionality.
+pub mod mutes;
 /// OkuNet post-related node functionality.
 pub mod posts;
 /// OkuNet user-related node functionality.
 pub mod users;
+/// OkuNet post visibility-related node functionality.
+pub mod visibility;
+
+mod drafts_test;
+mod visibility_test;
@@ -0,0 +1,65 @@
+use crate::{
+    database::{
+        core::DATABASE,
+        mutes::{OkuMute, OkuMuteRule},
+    },
+    fs::OkuFs,
+};
+use log::error;
+use std::time::{Duration, SystemTime};
+
+impl OkuFs {
+    /// Retrieves the mute rules currently applying on this node, removing any which have expired.
+    ///
+    /// # Returns
+    ///
+    /// A list of unexpired mute rules.
+    pub async fn mutes(&self) -> Vec<OkuMute> {
+        if let Err(e) = DATABASE.delete_expired_mutes() {
+            error!("{}", e);
+        }
+        DATABASE.get_mutes().unwrap_or_default()
+    }
+
+    /// Hide OkuNet posts matching a rule from timelines, tag pages, and search results.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - A rule describing which posts to mute.
+    ///
+    /// * `duration` - How long the mute should apply for; if unspecified, the mute applies until removed.
+    ///
+    /// # Returns
+    ///
+    /// The previous mute with the same rule, if one existed.
+    pub async fn mute(
+        &self,
+        rule: &OkuMuteRule,
+        duration: &Option<Duration>,
+    ) -> miette::Result<Option<OkuMute>> {
+        if let Err(e) = DATABASE.delete_expired_mutes() {
+            error!("{}", e);
+        }
+        let old_mute = DATABASE.upsert_mute(&OkuMute {
+            rule: rule.clone(),
+            expiry: duration.map(|x| SystemTime::now() + x),
+        })?;
+        self.okunet_post_sender.send_replace(());
+        Ok(old_mute)
+    }
+
+    /// Stop hiding OkuNet posts matching a rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - A previously-muted rule.
+    ///
+    /// # Returns
+    ///
+    /// The removed mute, if one existed.
+    pub async fn unmute(&self, rule: &OkuMuteRule) -> miette::Result<Option<OkuMute>> {
+        let removed_mute = DATABASE.delete_mute(rule)?;
+        self.okunet_post_sender.send_replace(());
+        Ok(removed_mute)
+    }
+}
@@ -1,8 +1,15 @@
 use super::core::home_replica_filters;
+use crate::fs::util::entry_key_to_path;
 use crate::{
     database::{
-        core::DATABASE,
-        posts::core::{OkuNote, OkuPost},
+        core::{OkuDatabase, DATABASE},
+        posts::{
+            core::{OkuNote, OkuPost, OkuTimelineCursor, OkuVisibility},
+            search::{OkuPostSearch, OkuPostSearchResults},
+            statistics::OkuActivityStatistics,
+            syndication::{OkuFeedSource, OkuSyndicationFeed},
+            urls::normalise_url,
+        },
         users::OkuUser,
     },
     fs::OkuFs,
@@ -11,17 +18,89 @@ use dashmap::DashMap;
 use iroh_blobs::Hash;
 use iroh_docs::sync::Entry;
 use iroh_docs::{AuthorId, NamespaceId};
-use miette::IntoDiagnostic;
 use rayon::iter::{
     FromParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
 };
+use rayon::slice::ParallelSliceMut;
 use std::{
+    cmp::Reverse,
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::atomic::AtomicUsize,
 };
 use url::Url;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// What to do when publishing a note regarding a URL the local user has already posted about.
+pub enum OkuNoteConflict {
+    #[default]
+    /// Keep the existing post.
+    Skip,
+    /// Replace the existing post's content with the new note's, keeping who can read the post and its content warning.
+    Replace,
+    /// Keep the existing post, adding the new note's tags, and using the new note's title and body where the existing post has none.
+    Merge,
+}
+
+impl OkuNoteConflict {
+    /// Resolve a conflict between an existing post and a new note regarding the same URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `existing` - The note of the existing post.
+    ///
+    /// * `new` - The note being published.
+    ///
+    /// # Returns
+    ///
+    /// The note to publish, or `None` if the existing post should be kept as it is.
+    pub fn resolve(&self, existing: &OkuNote, new: &OkuNote) -> Option<OkuNote> {
+        let resolved = match self {
+            OkuNoteConflict::Skip => return None,
+            OkuNoteConflict::Replace => OkuNote {
+                content_warning: existing
+                    .content_warning
+                    .clone()
+                    .or(new.content_warning.clone()),
+                sensitive: existing.sensitive || new.sensitive,
+                visibility: existing.visibility.clone(),
+                ..new.clone()
+            },
+            OkuNoteConflict::Merge => OkuNote {
+                title: match existing.title.trim().is_empty() {
+                    true => new.title.clone(),
+                    false => existing.title.clone(),
+                },
+                body: match existing.body.trim().is_empty() {
+                    true => new.body.clone(),
+                    false => existing.body.clone(),
+                },
+                tags: existing.tags.union(&new.tags).cloned().collect(),
+                content_warning: existing
+                    .content_warning
+                    .clone()
+                    .or(new.content_warning.clone()),
+                sensitive: existing.sensitive || new.sensitive,
+                ..existing.clone()
+            },
+        };
+        (&resolved != existing).then_some(resolved)
+    }
+}
+
+#[derive(Debug, Default)]
+/// The outcome of publishing several notes at once.
+pub struct OkuPublishReport {
+    /// The URLs of the posts created.
+    pub created: Vec<Url>,
+    /// The URLs of existing posts which were replaced or merged with.
+    pub updated: Vec<Url>,
+    /// The URLs of existing posts which were kept as they were.
+    pub skipped: Vec<Url>,
+    /// The URLs of the notes which could not be published, and why.
+    pub failures: Vec<(Url, miette::Report)>,
+}
+
 impl OkuFs {
     /// Retrieves the OkuNet posts by the local user, if any.
     ///
@@ -39,11 +118,11 @@ impl OkuFs {
             .par_iter()
             .filter(|(post_path, _)| matches!(post_path.extension(), Some(y) if y == "toml"))
             .collect();
+        let author_id = self.default_author().await;
         let mut posts: Vec<OkuPost> = Vec::new();
         for (post_path, bytes) in post_file_paths {
             if let Ok(entry) = self.get_entry(&home_replica_id, post_path).await {
-                if let Ok(note) = toml::from_str::<OkuNote>(String::from_utf8_lossy(bytes).as_ref())
-                {
+                if let Ok(Some(note)) = self.decode_note(&author_id, bytes).await {
                     posts.push(OkuPost { entry, note })
                 }
             }
@@ -62,6 +141,174 @@ impl OkuFs {
         posts
     }
 
+    /// Merge the local user's posts into a list of posts drawn from the database, as the local user's posts are not kept in the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `posts` - A list of OkuNet posts drawn from the database.
+    ///
+    /// * `predicate` - Which of the local user's posts to include.
+    ///
+    /// # Returns
+    ///
+    /// The posts with the local user's unmuted posts matching the predicate added, without duplicates, ordered from newest to oldest.
+    async fn with_own_posts(
+        &self,
+        mut posts: Vec<OkuPost>,
+        predicate: impl Fn(&OkuPost) -> bool + Send + Sync,
+    ) -> Vec<OkuPost> {
+        let own_posts: Vec<_> = self
+            .posts()
+            .await
+            .unwrap_or_default()
+            .into_par_iter()
+            .filter(|x| predicate(x))
+            .collect();
+        posts.extend(DATABASE.filter_muted_posts(own_posts));
+        posts.par_sort_unstable_by_key(|x| Reverse(x.cursor()));
+        posts.dedup();
+        posts
+    }
+
+    /// Retrieves a page of the timeline of posts known to this Oku node, excluding blocked and muted posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - A cursor; if specified, only posts positioned before it in the timeline are retrieved.
+    ///
+    /// * `page_size` - The maximum number of posts to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts, ordered from newest to oldest. The cursor of the last post is the cursor for the next page.
+    pub async fn timeline(
+        &self,
+        before: &Option<OkuTimelineCursor>,
+        page_size: usize,
+    ) -> miette::Result<Vec<OkuPost>> {
+        let blocked = self.blocked_users().await;
+        let posts = DATABASE.get_timeline(before, page_size, &blocked)?;
+        let mut posts = self
+            .with_own_posts(posts, |x| before.as_ref().is_none_or(|y| x.cursor() < *y))
+            .await;
+        posts.truncate(page_size);
+        Ok(posts)
+    }
+
+    /// Find known OkuNet posts similar to a URL or some text, excluding blocked and muted posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `url_or_text` - A URL, if it has been posted about, or otherwise some text.
+    ///
+    /// * `k` - The maximum number of posts to find.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts, ordered from most to least similar.
+    pub async fn similar_posts(&self, url_or_text: &str, k: usize) -> miette::Result<Vec<OkuPost>> {
+        DATABASE.similar_posts(url_or_text, k, &self.blocked_users().await)
+    }
+
+    /// Retrieves the posts known to this Oku node regarding a URL, or any URL with the same normalised form, excluding blocked and muted posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A URL.
+    ///
+    /// # Returns
+    ///
+    /// A list of OkuNet posts regarding the URL, ordered from newest to oldest.
+    pub async fn posts_about(&self, url: &Url) -> miette::Result<Vec<OkuPost>> {
+        let posts = DATABASE.get_posts_about(url, &self.blocked_users().await)?;
+        let normalised_url = normalise_url(url);
+        Ok(self
+            .with_own_posts(posts, |x| normalise_url(&x.note.url) == normalised_url)
+            .await)
+    }
+
+    /// Search known OkuNet posts, with filters, pagination, counts of tags and authors, and highlighted snippets.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The query, filters, and page of results to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A page of OkuNet posts, excluding blocked and muted posts, with counts of the tags and authors of all posts found.
+    pub async fn search_posts(
+        &self,
+        search: &OkuPostSearch,
+    ) -> miette::Result<OkuPostSearchResults> {
+        OkuDatabase::search_posts_faceted(search, &self.blocked_users().await)
+    }
+
+    /// Count the known OkuNet posts found by a search, without retrieving them.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The query and filters; pagination is ignored.
+    ///
+    /// # Returns
+    ///
+    /// The number of posts found, excluding blocked and muted posts.
+    pub async fn count_posts(&self, search: &OkuPostSearch) -> miette::Result<usize> {
+        OkuDatabase::count_posts(search, &self.blocked_users().await)
+    }
+
+    /// Collects OkuNet posts into a feed, to be read in feed readers; blocked and muted posts are excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The user, tag, or search whose posts make up the feed.
+    ///
+    /// * `limit` - The maximum number of posts in the feed.
+    ///
+    /// # Returns
+    ///
+    /// A feed of the newest posts from the source, ordered from newest to oldest.
+    pub async fn syndicate(
+        &self,
+        source: &OkuFeedSource,
+        limit: usize,
+    ) -> miette::Result<OkuSyndicationFeed> {
+        let search = match source {
+            OkuFeedSource::User(author_id) => OkuPostSearch {
+                authors: HashSet::from([*author_id]),
+                ..Default::default()
+            },
+            OkuFeedSource::Tag(tag) => OkuPostSearch {
+                tags: HashSet::from([tag.clone()]),
+                ..Default::default()
+            },
+            OkuFeedSource::Search(search) => search.clone(),
+        };
+        let search = OkuPostSearch {
+            newest_first: true,
+            offset: 0,
+            limit,
+            ..search
+        };
+        let mut posts: Vec<_> = self
+            .search_posts(&search)
+            .await?
+            .results
+            .into_iter()
+            .map(|x| x.post)
+            .collect();
+        // The local user's posts can only be filtered, not searched
+        if search.query.trim().is_empty() {
+            posts = self.with_own_posts(posts, |x| search.matches(x)).await;
+        }
+        posts.truncate(limit);
+        Ok(OkuSyndicationFeed {
+            title: source.title(),
+            id: source.uri(Default::default()),
+            posts,
+            author_names: HashMap::new(),
+        })
+    }
+
     /// Filters posts containing at least one of the given tags.
     ///
     /// # Arguments
@@ -121,6 +368,31 @@ impl OkuFs {
             .collect()
     }
 
+    /// Measure the activity of OkuNet users since some point in time, excluding blocked and muted posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - If specified, only posts written at or after this time (in microseconds since the Unix epoch) are counted.
+    ///
+    /// # Returns
+    ///
+    /// The tags and URLs most posted about, and the most active authors.
+    pub async fn activity_statistics(
+        &self,
+        since: &Option<u64>,
+    ) -> miette::Result<OkuActivityStatistics> {
+        // The local user's posts are read from their home replica rather than the database
+        let mut excluded_authors = self.blocked_users().await;
+        excluded_authors.insert(self.default_author().await);
+        let statistics = DATABASE.activity_statistics(since, &excluded_authors)?;
+        let own_posts = self
+            .with_own_posts(Vec::new(), |x| {
+                since.is_none_or(|y| x.entry.timestamp() >= y)
+            })
+            .await;
+        Ok(statistics.with_posts(own_posts.iter()))
+    }
+
     /// Retrieves an OkuNet post authored by the local user using its path.
     ///
     /// # Arguments
@@ -137,8 +409,10 @@ impl OkuFs {
             .ok_or(miette::miette!("Home replica not set … "))?;
         match self.read_file(&namespace_id, path, &None, &None).await {
             Ok(bytes) => {
-                let note = toml::from_str::<OkuNote>(String::from_utf8_lossy(&bytes).as_ref())
-                    .into_diagnostic()?;
+                let note = self
+                    .decode_note(&self.default_author().await, &bytes)
+                    .await?
+                    .ok_or(miette::miette!("Post at {:?} cannot be read … ", path))?;
                 Ok(OkuPost {
                     entry: self.get_entry(&namespace_id, path).await?,
                     note,
@@ -156,14 +430,19 @@ impl OkuFs {
     ///
     /// # Returns
     ///
-    /// An OkuNet post, if the entry represents one.
+    /// An OkuNet post, if the entry represents one readable by the local user.
     pub async fn post_from_entry(&self, entry: &Entry) -> miette::Result<OkuPost> {
         let bytes = self
             .content_bytes(entry, &None, &None)
             .await
             .map_err(|e| miette::miette!("{}", e))?;
-        let note = toml::from_str::<OkuNote>(String::from_utf8_lossy(&bytes).as_ref())
-            .into_diagnostic()?;
+        let note = self
+            .decode_note(&entry.author(), &bytes)
+            .await?
+            .ok_or(miette::miette!(
+                "Post at {:?} cannot be read … ",
+                entry_key_to_path(entry.key())?
+            ))?;
         Ok(OkuPost {
             entry: entry.clone(),
             note,
@@ -187,6 +466,28 @@ impl OkuFs {
         Ok(posts)
     }
 
+    /// Retrieves the local user's post regarding a URL, whether public or restricted.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the post regards.
+    ///
+    /// # Returns
+    ///
+    /// The local user's post regarding the URL.
+    pub async fn post_about(&self, url: &Url) -> miette::Result<OkuPost> {
+        match self
+            .post(&OkuNote::post_path_from_url(&url.to_string()).into())
+            .await
+        {
+            Ok(post) => Ok(post),
+            Err(_) => {
+                self.post(&self.restricted_post_path(url).await?.into())
+                    .await
+            }
+        }
+    }
+
     /// Create or modify an OkuNet post in the user's home replica.
     ///
     /// # Arguments
@@ -197,19 +498,29 @@ impl OkuFs {
     ///
     /// * `title` - The title of the post.
     ///
-    /// * `body` - The body of the post.
+    /// * `body` - The body of the post; mentions of users by display name are stored by content authorship ID.
     ///
     /// * `tags` - A list of tags associated with the post.
     ///
+    /// * `content_warning` - An optional warning shown in place of the post's body.
+    ///
+    /// * `sensitive` - Whether the post regards sensitive content.
+    ///
+    /// * `visibility` - Who is able to read the post; restricted posts are encrypted to their readers at the time of writing.
+    ///
     /// # Returns
     ///
     /// The ID of the user's home replica, the path to the post file, and a hash of the post's content if the post is new.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_or_modify_post(
         &self,
         url: &Url,
         title: &String,
         body: &String,
         tags: &HashSet<String>,
+        content_warning: &Option<String>,
+        sensitive: bool,
+        visibility: &OkuVisibility,
     ) -> miette::Result<(NamespaceId, PathBuf, Option<Hash>)> {
         let home_replica_id = self
             .home_replica()
@@ -218,21 +529,90 @@ impl OkuFs {
         let new_note = OkuNote {
             url: url.clone(),
             title: title.to_string(),
-            body: body.to_string(),
+            body: DATABASE.resolve_mentions(body)?,
             tags: tags.clone(),
+            content_warning: content_warning.clone(),
+            sensitive,
+            visibility: visibility.clone(),
+        };
+        let public_path: PathBuf = new_note.post_path().into();
+        let restricted_path: PathBuf = self.restricted_post_path(url).await?.into();
+        let (post_path, other_path) = match new_note.is_restricted() {
+            true => (restricted_path, public_path),
+            false => (public_path, restricted_path),
         };
-        let post_path = new_note.post_path().into();
+        let moved_post = self.post(&other_path).await.ok();
+        if let Some(old_post) = self.post(&post_path).await.ok().or(moved_post.clone()) {
+            if old_post.note != new_note {
+                DATABASE.upsert_post_revision(&old_post)?;
+            }
+        }
         let hash = self
             .create_or_replace_file(
                 &home_replica_id,
                 &post_path,
-                toml::to_string_pretty(&new_note).into_diagnostic()?,
+                self.encode_note(&new_note).await?,
             )
             .await?;
+        // Posts changing between public and restricted are moved, so that one URL has only one post
+        if moved_post.is_some() {
+            self.delete_file(&home_replica_id, &other_path).await?;
+        }
         self.okunet_post_sender.send_replace(());
         Ok((home_replica_id, post_path, hash))
     }
 
+    /// Publish several notes as OkuNet posts in the user's home replica.
+    ///
+    /// # Arguments
+    ///
+    /// * `notes` - The notes to publish.
+    ///
+    /// * `conflict` - What to do when a note regards a URL the user has already posted about.
+    ///
+    /// # Returns
+    ///
+    /// Which notes were published as new posts, which updated existing posts, which were skipped, and which could not be published.
+    pub async fn publish_notes(
+        &self,
+        notes: Vec<OkuNote>,
+        conflict: OkuNoteConflict,
+    ) -> miette::Result<OkuPublishReport> {
+        if self.home_replica().await.is_none() {
+            return Err(miette::miette!("No home replica set … "));
+        }
+        let mut report = OkuPublishReport::default();
+        for note in notes {
+            let (note, updating) = match self.post_about(&note.url).await {
+                Ok(existing_post) => match conflict.resolve(&existing_post.note, &note) {
+                    Some(resolved_note) => (resolved_note, true),
+                    None => {
+                        report.skipped.push(note.url);
+                        continue;
+                    }
+                },
+                Err(_) => (note, false),
+            };
+            match self
+                .create_or_modify_post(
+                    &note.url,
+                    &note.title,
+                    &note.body,
+                    &note.tags,
+                    &note.content_warning,
+                    note.sensitive,
+                    &note.visibility,
+                )
+                .await
+            {
+                Ok(_) if updating => report.updated.push(note.url),
+                Ok(_) => report.created.push(note.url),
+                Err(e) => report.failures.push((note.url, e)),
+            }
+        }
+        Ok(report)
+    }
+
     /// Delete an OkuNet post in the user's home replica.
     ///
     /// # Arguments
@@ -247,11 +627,52 @@ impl OkuFs {
             .home_replica()
             .await
             .ok_or(miette::miette!("No home replica set … "))?;
+        if let Ok(post) = self.post(path).await {
+            DATABASE.delete_post_revisions(&post)?;
+        }
         let deleted = self.delete_file(&home_replica_id, path).await;
         self.okunet_post_sender.send_replace(());
         deleted
     }
 
+    /// Retrieves the prior versions of an OkuNet post.
+    ///
+    /// Versions are drawn from those recorded when the post was replaced, as well as any other entries for the post's file still held in its author's home replica.
+    ///
+    /// # Arguments
+    ///
+    /// * `post` - An OkuNet post.
+    ///
+    /// # Returns
+    ///
+    /// The prior versions of the post, from newest to oldest.
+    pub async fn post_history(&self, post: &OkuPost) -> miette::Result<Vec<OkuPost>> {
+        let path = entry_key_to_path(post.entry.key())?;
+        let mut revisions: HashMap<u64, OkuPost> = DATABASE
+            .get_post_revisions(&post.entry.author(), &path)?
+            .into_par_iter()
+            .map(|x| (x.entry.timestamp(), x))
+            .collect();
+        let namespace_id = NamespaceId::from(post.entry.author().as_bytes());
+        for entry in self
+            .get_entries(&namespace_id, &path)
+            .await
+            .unwrap_or_default()
+        {
+            if entry.timestamp() < post.entry.timestamp()
+                && entry.content_hash() != post.entry.content_hash()
+                && !revisions.contains_key(&entry.timestamp())
+            {
+                if let Ok(revision) = self.post_from_entry(&entry).await {
+                    revisions.insert(entry.timestamp(), revision);
+                }
+            }
+        }
+        let mut revisions: Vec<_> = revisions.into_values().collect();
+        revisions.par_sort_unstable_by_key(|x| Reverse(x.entry.timestamp()));
+        Ok(revisions)
+    }
+
     /// Join a swarm to fetch the latest version of an OkuNet post.
     ///
     /// # Arguments
@@ -278,8 +699,10 @@ impl OkuFs {
             .await
         {
             Ok(bytes) => {
-                let note = toml::from_str::<OkuNote>(String::from_utf8_lossy(&bytes).as_ref())
-                    .into_diagnostic()?;
+                let note = self
+                    .decode_note(author_id, &bytes)
+                    .await?
+                    .ok_or(miette::miette!("Post at {:?} cannot be read … ", path))?;
                 Ok(OkuPost {
                     entry: self.get_entry(&namespace_id, path).await?,
                     note,
@@ -3,25 +3,70 @@ use crate::{
     config::OkuFsConfig,
     database::{
         core::DATABASE,
-        posts::core::{OkuNote, OkuPost},
+        posts::core::OkuPost,
         users::{OkuIdentity, OkuUser},
     },
-    fs::OkuFs,
+    fs::{util::entry_key_to_path, OkuFs},
 };
-use cfg_if::cfg_if;
 use futures::StreamExt;
 use iroh_blobs::Hash;
-use iroh_docs::sync::CapabilityKind;
+use iroh_docs::sync::{CapabilityKind, Entry};
 use iroh_docs::AuthorId;
 use iroh_docs::DocTicket;
 use iroh_docs::NamespaceId;
 use iroh_docs::{api::protocol::ShareMode, Author, NamespaceSecret};
-use log::debug;
+use log::{debug, error};
 use miette::IntoDiagnostic;
 use rayon::iter::{
     FromParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
 };
-use std::{collections::HashSet, path::Path, time::SystemTime};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::SystemTime,
+};
+
+#[derive(Debug, Default)]
+/// The outcome of crawling the OkuNet social graph.
+pub struct OkuCrawlReport {
+    /// The users successfully recorded.
+    pub users: HashSet<AuthorId>,
+    /// The users which could not be retrieved, and why.
+    pub failures: Vec<(AuthorId, miette::Report)>,
+}
+
+/// Selects which users from a followed list should be crawled.
+///
+/// # Arguments
+///
+/// * `following` - The users followed by a user.
+///
+/// * `user_blocked` - The users blocked by that same user.
+///
+/// * `blocked` - The users blocked by the local user.
+///
+/// * `fan_out` - The maximum number of users to select, if any.
+///
+/// # Returns
+///
+/// The users to crawl.
+fn crawl_candidates(
+    following: HashSet<AuthorId>,
+    user_blocked: &HashSet<AuthorId>,
+    blocked: &HashSet<AuthorId>,
+    fan_out: Option<usize>,
+) -> Vec<AuthorId> {
+    // In case a user is somehow followed and blocked (additional checks should already prevent this)
+    let mut candidates: Vec<_> = following
+        .into_iter()
+        .filter(|x| !user_blocked.contains(x) && !blocked.contains(x))
+        .collect();
+    candidates.sort_unstable_by_key(|x| *x.as_bytes());
+    if let Some(fan_out) = fan_out {
+        candidates.truncate(fan_out);
+    }
+    candidates
+}
 
 impl OkuFs {
     /// Retrieve the content authorship ID used by the node.
@@ -221,6 +266,9 @@ impl OkuFs {
         let me = self.default_author().await;
         validated_identity.following.retain(|y| me != *y);
         validated_identity.blocked.retain(|y| me != *y);
+        validated_identity
+            .blocklist_subscriptions
+            .retain(|y| me != *y);
         // It is not valid to follow blocked people.
         validated_identity.following = validated_identity
             .following
@@ -239,6 +287,9 @@ impl OkuFs {
             )
             .await;
         self.okunet_user_sender.send_replace(());
+        // Follows and blocks change which home replicas to keep live subscriptions on
+        let oku_fs = self.clone();
+        tokio::spawn(async move { oku_fs.refresh_live_subscriptions().await });
         hash
     }
 
@@ -257,6 +308,24 @@ impl OkuFs {
         self.set_identity(&identity).await
     }
 
+    /// Replaces the current biography of the local user.
+    ///
+    /// # Arguments
+    ///
+    /// * `bio` - The new biography, or none if the local user should have no biography.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the new identity file in the local user's home replica, if it didn't already exist.
+    pub async fn set_bio(&self, bio: &Option<String>) -> miette::Result<Option<Hash>> {
+        let mut identity = self.identity().await.unwrap_or_default();
+        identity.bio = bio
+            .as_ref()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty());
+        self.set_identity(&identity).await
+    }
+
     /// Follow or unfollow a user.
     ///
     /// # Arguments
@@ -412,6 +481,73 @@ impl OkuFs {
         &self.default_author().await == author_id
     }
 
+    /// Check if a user follows the local user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The user's content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// Whether or not the user is known to follow the local user.
+    pub async fn follows_me(&self, author_id: &AuthorId) -> bool {
+        DATABASE
+            .followers_of(&self.default_author().await)
+            .contains(author_id)
+    }
+
+    /// Retrieves the known OkuNet users following the local user.
+    ///
+    /// # Returns
+    ///
+    /// The content authorship IDs of the users known to follow the local user.
+    pub async fn followers(&self) -> HashSet<AuthorId> {
+        DATABASE.followers_of(&self.default_author().await)
+    }
+
+    /// Retrieves the known followers of a user, including the local user if they follow them.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The user's content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The content authorship IDs of the known users following the given user.
+    pub async fn followers_of(&self, author_id: &AuthorId) -> HashSet<AuthorId> {
+        let mut followers = DATABASE.followers_of(author_id);
+        if self.is_followed(author_id).await {
+            followers.insert(self.default_author().await);
+        }
+        followers
+    }
+
+    /// Retrieves the known users who both follow, and are followed by, a user.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The user's content authorship ID.
+    ///
+    /// # Returns
+    ///
+    /// The content authorship IDs of the known users mutually following the given user.
+    pub async fn mutuals(&self, author_id: &AuthorId) -> miette::Result<HashSet<AuthorId>> {
+        let following = match self.is_me(author_id).await {
+            true => self.identity().await.map(|x| x.following),
+            false => DATABASE
+                .get_user(author_id)?
+                .and_then(|x| x.identity)
+                .map(|x| x.following),
+        }
+        .unwrap_or_default();
+        Ok(self
+            .followers_of(author_id)
+            .await
+            .into_iter()
+            .filter(|x| following.contains(x))
+            .collect())
+    }
+
     /// Retrieves an [`OkuUser`] representing the local user.
     ///
     /// # Returns
@@ -431,73 +567,133 @@ impl OkuFs {
     }
 
     /// Refreshes any user data last retrieved longer than [`crate::config::OkuFsConfig::get_republish_delay`] ago according to the system time; the users one is following, and the users they're following, are recorded locally.
-    /// Blocked users are not recorded.
-    pub async fn refresh_users(&self) -> miette::Result<()> {
-        // Wanted users: followed users
-        // Unwanted users: blocked users, unfollowed users
-        let (followed_users, blocked_users) = match self.identity().await {
-            Some(identity) => (identity.following, identity.blocked),
-            None => (HashSet::new(), HashSet::new()),
-        };
-        // In case a user is somehow followed and blocked (additional checks should already prevent this)
-        let users_to_add: HashSet<_> = followed_users
-            .difference(&blocked_users)
-            .map(|x| x.to_owned())
-            .collect();
-        let local_users: HashSet<_> = DATABASE.all_local_users().into_par_iter().collect();
-        let users_to_delete: HashSet<_> = local_users
-            .difference(&users_to_add)
-            .map(|x| x.to_owned())
-            .collect();
+    /// Blocked users, including those blocked through subscribed blocked lists, are not recorded.
+    ///
+    /// How far the social graph is crawled is configured in [`crate::config::OkuFsConfig`].
+    ///
+    /// # Returns
+    ///
+    /// The users recorded, and the users which could not be retrieved.
+    pub async fn refresh_users(&self) -> miette::Result<OkuCrawlReport> {
+        self.crawl_users(false).await
+    }
 
-        for user_id in users_to_add {
-            let user = self.get_or_fetch_user(&user_id).await?;
-            let (user_followed_users, user_blocked_users) = match user.identity {
-                Some(identity) => (identity.following, identity.blocked),
-                None => (HashSet::new(), HashSet::new()),
-            };
-            for user_user in user_followed_users.difference(&user_blocked_users) {
-                self.get_or_fetch_user(user_user).await?;
+    /// Retrieves user data regardless of when last retrieved; the users one is following, and the users they're following, are recorded locally.
+    /// Blocked users, including those blocked through subscribed blocked lists, are not recorded.
+    ///
+    /// How far the social graph is crawled is configured in [`crate::config::OkuFsConfig`].
+    ///
+    /// # Returns
+    ///
+    /// The users recorded, and the users which could not be retrieved.
+    pub async fn fetch_users(&self) -> miette::Result<OkuCrawlReport> {
+        let identity = self.identity().await.unwrap_or_default();
+        for subscription in identity.blocklist_subscriptions.iter() {
+            if let Err(e) = self.fetch_blocklist(subscription).await {
+                error!("{}", e);
             }
         }
-        DATABASE.delete_by_author_ids(&Vec::from_par_iter(users_to_delete))?;
-        self.okunet_user_sender.send_replace(());
-        Ok(())
+        self.crawl_users(true).await
     }
 
-    /// Retrieves user data regardless of when last retrieved; the users one is following, and the users they're following, are recorded locally.
-    /// Blocked users are not recorded.
-    pub async fn fetch_users(&self) -> miette::Result<()> {
-        // Wanted users: followed users
-        // Unwanted users: blocked users, unfollowed users
-        let (followed_users, blocked_users) = match self.identity().await {
-            Some(identity) => (identity.following, identity.blocked),
-            None => (HashSet::new(), HashSet::new()),
+    /// Crawls the social graph outwards from the local user, recording the users found and removing any other users from the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - Whether to retrieve user data regardless of when last retrieved.
+    ///
+    /// # Returns
+    ///
+    /// The users recorded, and the users which could not be retrieved.
+    async fn crawl_users(&self, force: bool) -> miette::Result<OkuCrawlReport> {
+        let config = cfg_select! {
+            feature = "persistent" => {
+                OkuFsConfig::load_or_create_config().unwrap_or_default()
+            },
+            _ => {
+                OkuFsConfig::default()
+            }
         };
-        // In case a user is somehow followed and blocked (additional checks should already prevent this)
-        let users_to_add: HashSet<_> = followed_users
-            .difference(&blocked_users)
-            .map(|x| x.to_owned())
-            .collect();
+        let depth = config.get_crawl_depth();
+        let user_cap = config.get_crawl_user_cap();
+        let parallelism = config.get_crawl_parallelism();
+
+        // Wanted users: followed users, and the users they follow, up to the configured depth
+        // Unwanted users: blocked users (including through subscribed blocked lists), unfollowed users
+        let blocked_users = self.blocked_users().await;
+        let mut visited = HashSet::from([self.default_author().await]);
+        let mut report = OkuCrawlReport::default();
+        let mut frontier = crawl_candidates(
+            self.identity()
+                .await
+                .map(|x| x.following)
+                .unwrap_or_default(),
+            &HashSet::new(),
+            &blocked_users,
+            config.get_crawl_fan_out_at(0),
+        );
+
+        for level in 0..depth {
+            let mut level_users = Vec::new();
+            for user_id in frontier {
+                if user_cap.is_some_and(|x| visited.len() - 1 >= x) {
+                    break;
+                }
+                if visited.insert(user_id) {
+                    level_users.push(user_id);
+                }
+            }
+            let results: Vec<_> = futures::stream::iter(level_users)
+                .map(|user_id| async move {
+                    let user = match force {
+                        true => self.fetch_user(&user_id).await,
+                        false => self.get_or_fetch_user(&user_id).await,
+                    };
+                    (user_id, user)
+                })
+                .buffer_unordered(parallelism)
+                .collect()
+                .await;
+
+            let fan_out = config.get_crawl_fan_out_at(level + 1);
+            frontier = Vec::new();
+            for (user_id, user) in results {
+                let user = match user {
+                    Ok(user) => {
+                        report.users.insert(user_id);
+                        Some(user)
+                    }
+                    Err(e) => {
+                        error!("{}", e);
+                        report.failures.push((user_id, e));
+                        // Crawl on from the user as last recorded, so the users they follow are not removed over a failure to retrieve them
+                        DATABASE.get_user(&user_id).ok().flatten()
+                    }
+                };
+                if let Some(user) = user.filter(|_| level + 1 < depth) {
+                    let (user_followed_users, user_blocked_users) = match user.identity {
+                        Some(identity) => (identity.following, identity.blocked),
+                        None => (HashSet::new(), HashSet::new()),
+                    };
+                    frontier.extend(crawl_candidates(
+                        user_followed_users,
+                        &user_blocked_users,
+                        &blocked_users,
+                        fan_out,
+                    ));
+                }
+            }
+        }
+
+        // Users which could not be retrieved are kept until they can be
         let local_users: HashSet<_> = DATABASE.all_local_users().into_par_iter().collect();
         let users_to_delete: HashSet<_> = local_users
-            .difference(&users_to_add)
+            .difference(&visited)
             .map(|x| x.to_owned())
             .collect();
-
-        for user_id in users_to_add {
-            let user = self.fetch_user(&user_id).await?;
-            let (user_followed_users, user_blocked_users) = match user.identity {
-                Some(identity) => (identity.following, identity.blocked),
-                None => (HashSet::new(), HashSet::new()),
-            };
-            for user_user in user_followed_users.difference(&user_blocked_users) {
-                self.fetch_user(user_user).await?;
-            }
-        }
         DATABASE.delete_by_author_ids(&Vec::from_par_iter(users_to_delete))?;
         self.okunet_user_sender.send_replace(());
-        Ok(())
+        Ok(report)
     }
 
     /// Use the mainline DHT to obtain a ticket for the home replica of the user with the given content authorship ID.
@@ -550,43 +746,136 @@ impl OkuFs {
 
     /// Join a swarm to fetch the latest version of a home replica and obtain the OkuNet posts within it.
     ///
+    /// Only posts which are new or have changed since the user was last fetched are read, parsed, and saved to the database; unchanged posts are taken from the database.
+    /// Previously-fetched posts no longer present in the home replica are removed from the database.
+    ///
     /// # Arguments
     ///
-    /// * `author_id` - A content authorship ID.
+    /// * `ticket` - A ticket for the home replica of an OkuNet user.
     ///
     /// # Returns
     ///
     /// The OkuNet posts within the home replica of the user with the given content authorship ID.
     pub async fn fetch_posts(&self, ticket: &DocTicket) -> miette::Result<Vec<OkuPost>> {
-        let posts = match self
-            .fetch_directory_with_ticket(
-                ticket,
-                Path::new("/posts/"),
-                &Some(home_replica_filters()),
-            )
-            .await
-        {
-            Ok(directory_paths) => {
-                let post_file_paths: Vec<_> = directory_paths
-                    .par_iter()
-                    .filter(
-                        |(post_path, _)| matches!(post_path.extension(), Some(y) if y == "toml"),
-                    )
-                    .collect();
-                let mut posts: Vec<OkuPost> = Vec::new();
-                for (post_path, bytes) in post_file_paths {
-                    let entry = self.get_entry(&ticket.capability.id(), post_path).await?;
-                    let note = toml::from_str::<OkuNote>(String::from_utf8_lossy(bytes).as_ref())
-                        .into_diagnostic()?;
-                    posts.push(OkuPost { entry, note })
-                }
-                Ok(posts)
-            }
-            Err(e) => Err(miette::miette!("{}", e)),
-        };
+        let posts = self.fetch_changed_posts(ticket).await;
         self.okunet_user_sender.send_replace(());
         self.okunet_post_sender.send_replace(());
-        posts
+        posts.map(|(posts, _)| posts)
+    }
+
+    /// Synchronise a home replica, reading only the posts whose entries differ from those last recorded for the user.
+    ///
+    /// Returns the posts read, and the entries of all posts in the home replica which should be recorded for the user; this includes posts restricted to other users, so that they are not read again until they change.
+    async fn fetch_changed_posts(
+        &self,
+        ticket: &DocTicket,
+    ) -> miette::Result<(Vec<OkuPost>, Vec<Entry>)> {
+        let namespace_id = ticket.capability.id();
+        let author_id = AuthorId::from(namespace_id.as_bytes());
+        let posts_path = PathBuf::from("/posts/");
+        self.fetch_replica_by_ticket(
+            ticket,
+            &Some(posts_path.clone()),
+            &Some(home_replica_filters()),
+        )
+        .await
+        .map_err(|e| miette::miette!("{}", e))?;
+
+        // The entries recorded when the user was last fetched, and the posts made from them
+        let known_entries: HashMap<_, _> = DATABASE
+            .get_user(&author_id)?
+            .map(|x| x.posts)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|x| (x.key().to_vec(), (x.content_hash(), x.timestamp())))
+            .collect();
+        let known_posts: HashMap<_, _> = DATABASE
+            .get_posts_by_author(&author_id)?
+            .into_iter()
+            .map(|x| (x.entry.key().to_vec(), x))
+            .collect();
+
+        let entries: Vec<_> = self
+            .list_entries(&namespace_id, &Some(posts_path))
+            .await?
+            .into_par_iter()
+            .filter(|x| {
+                entry_key_to_path(x.key())
+                    .is_ok_and(|y| matches!(y.extension(), Some(z) if z == "toml"))
+            })
+            .collect();
+        let mut posts: Vec<OkuPost> = Vec::new();
+        let mut changed_posts: Vec<OkuPost> = Vec::new();
+        let mut restricted_entries: Vec<Entry> = Vec::new();
+        for entry in entries {
+            let known_post = known_posts
+                .get(entry.key())
+                .filter(|x| x.entry.content_hash() == entry.content_hash());
+            let is_unchanged = known_entries
+                .get(entry.key())
+                .is_some_and(|x| *x == (entry.content_hash(), entry.timestamp()));
+            match known_post {
+                // Neither the content nor the entry have changed
+                Some(known_post) if is_unchanged => posts.push(known_post.clone()),
+                // The content is the same, but it was rewritten
+                Some(known_post) => {
+                    let post = OkuPost {
+                        entry,
+                        note: known_post.note.clone(),
+                    };
+                    changed_posts.push(post.clone());
+                    posts.push(post);
+                }
+                // A post restricted to other users, unchanged since it was last skipped
+                None if is_unchanged => restricted_entries.push(entry),
+                None => {
+                    let note = match self.content_bytes(&entry, &None, &None).await {
+                        Ok(bytes) => self.decode_note(&author_id, &bytes).await,
+                        Err(e) => Err(miette::miette!("{}", e)),
+                    };
+                    match note {
+                        Ok(Some(note)) => {
+                            let post = OkuPost { entry, note };
+                            changed_posts.push(post.clone());
+                            posts.push(post);
+                        }
+                        // Posts restricted to other users are skipped
+                        Ok(None) => restricted_entries.push(entry),
+                        // Posts which cannot be read are retried on the next fetch, keeping any earlier version until then
+                        Err(e) => {
+                            error!("{}", e);
+                            if let Some(known_post) = known_posts.get(entry.key()) {
+                                posts.push(known_post.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        debug!(
+            "{} of {} posts by {} are new or changed … ",
+            changed_posts.len(),
+            posts.len(),
+            crate::fs::util::fmt_short(author_id)
+        );
+        if !changed_posts.is_empty() {
+            DATABASE.upsert_posts(&changed_posts)?;
+        }
+
+        // Posts no longer in the home replica have been deleted by their author
+        let fetched_posts: HashSet<_> = posts.par_iter().cloned().collect();
+        let deleted_posts: Vec<_> = known_posts
+            .into_par_iter()
+            .map(|(_, x)| x)
+            .filter(|x| !fetched_posts.contains(x))
+            .collect();
+        DATABASE.delete_posts(&deleted_posts)?;
+        let entries = posts
+            .par_iter()
+            .map(|x| x.entry.clone())
+            .chain(restricted_entries)
+            .collect();
+        Ok((posts, entries))
     }
 
     /// Obtain an OkuNet user's content, identified by their content authorship ID.
@@ -601,7 +890,14 @@ impl OkuFs {
     ///
     /// An OkuNet user's content.
     pub async fn get_or_fetch_user(&self, author_id: &AuthorId) -> miette::Result<OkuUser> {
-        cfg_if!(if #[cfg(any(feature = "persistent"))]{let config = OkuFsConfig::load_or_create_config().unwrap_or_default();}else{let config = OkuFsConfig::default();});
+        let config = cfg_select! {
+            feature = "persistent" => {
+                OkuFsConfig::load_or_create_config().unwrap_or_default()
+            },
+            _ => {
+                OkuFsConfig::default()
+            }
+        };
         let republish_delay = config.get_republish_delay();
         match DATABASE.get_user(author_id).ok().flatten() {
             Some(user) => {
@@ -635,14 +931,25 @@ impl OkuFs {
             .map_err(|e| miette::miette!("{}", e))?;
 
         let profile = self.fetch_profile(&ticket).await.ok();
-        let posts = self.fetch_posts(&ticket).await.unwrap_or_default();
-        DATABASE.upsert_posts(&posts)?;
+        let entries = match self.fetch_changed_posts(&ticket).await {
+            Ok((_, entries)) => entries,
+            // The entries last recorded are kept, so the user's posts are not all read again on the next fetch
+            Err(e) => {
+                error!("{}", e);
+                DATABASE
+                    .get_user(author_id)?
+                    .map(|x| x.posts)
+                    .unwrap_or_default()
+            }
+        };
         DATABASE.upsert_user(&OkuUser {
             author_id: *author_id,
             last_fetched: SystemTime::now(),
-            posts: posts.into_par_iter().map(|y| y.entry).collect(),
+            posts: entries,
             identity: profile,
         })?;
+        self.okunet_user_sender.send_replace(());
+        self.okunet_post_sender.send_replace(());
         self.okunet_fetch_sender.send_replace(false);
         DATABASE
             .get_user(author_id)?
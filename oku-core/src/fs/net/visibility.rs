@@ -0,0 +1,248 @@
+use super::core::{shared_cipher, OkuCiphertext};
+use crate::{
+    database::{
+        circles::OkuCircle,
+        core::DATABASE,
+        posts::core::{OkuNote, OkuVisibility},
+    },
+    fs::OkuFs,
+};
+use chacha20poly1305::{
+    aead::{KeyInit, OsRng},
+    XChaCha20Poly1305,
+};
+use iroh_docs::AuthorId;
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use url::Url;
+
+/// The context in which ciphers for the keys of restricted posts are derived.
+const POST_CONTEXT: &str = "oku restricted post";
+
+/// The context in which the key naming the files of restricted posts is derived.
+const POST_PATH_CONTEXT: &str = "oku restricted post path";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+/// An OkuNet post as written to its author's home replica when only some users are able to read it.
+///
+/// The content authorship IDs of the post's readers are not encrypted, and can be seen by anyone retrieving the author's home replica.
+struct OkuEncryptedNote {
+    /// The key the note is encrypted with, itself encrypted for each reader, by their content authorship ID.
+    keys: HashMap<String, OkuCiphertext>,
+    /// The encrypted note.
+    note: OkuCiphertext,
+}
+
+impl OkuFs {
+    /// Retrieves the circles of OkuNet users saved on this node.
+    ///
+    /// # Returns
+    ///
+    /// A list of the circles saved on this node.
+    pub async fn circles(&self) -> Vec<OkuCircle> {
+        DATABASE.get_circles().unwrap_or_default()
+    }
+
+    /// Add OkuNet users to a circle, creating it if it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the circle.
+    ///
+    /// * `author_ids` - The content authorship IDs of the users to add.
+    ///
+    /// # Returns
+    ///
+    /// The circle, with the users added.
+    pub async fn add_to_circle(
+        &self,
+        name: &String,
+        author_ids: &HashSet<AuthorId>,
+    ) -> miette::Result<OkuCircle> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(miette::miette!("Circles must be named … "));
+        }
+        let mut circle = DATABASE.get_circle(&name)?.unwrap_or(OkuCircle {
+            name,
+            members: HashSet::new(),
+        });
+        circle.members.extend(author_ids);
+        DATABASE.upsert_circle(&circle)?;
+        Ok(circle)
+    }
+
+    /// Remove OkuNet users from a circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the circle.
+    ///
+    /// * `author_ids` - The content authorship IDs of the users to remove.
+    ///
+    /// # Returns
+    ///
+    /// The circle, with the users removed, if it exists.
+    pub async fn remove_from_circle(
+        &self,
+        name: &String,
+        author_ids: &HashSet<AuthorId>,
+    ) -> miette::Result<Option<OkuCircle>> {
+        match DATABASE.get_circle(name)? {
+            Some(mut circle) => {
+                circle.members.retain(|x| !author_ids.contains(x));
+                DATABASE.upsert_circle(&circle)?;
+                Ok(Some(circle))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Delete a circle.
+    ///
+    /// Posts already written for the circle remain readable by its members.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the circle.
+    ///
+    /// # Returns
+    ///
+    /// The deleted circle, if it existed.
+    pub async fn delete_circle(&self, name: &String) -> miette::Result<Option<OkuCircle>> {
+        DATABASE.delete_circle(name)
+    }
+
+    /// Determine which users are able to read a restricted post.
+    ///
+    /// Blocked users are never able to read restricted posts.
+    ///
+    /// # Arguments
+    ///
+    /// * `visibility` - The visibility of the post.
+    ///
+    /// # Returns
+    ///
+    /// The content authorship IDs of the users able to read the post, including the local user.
+    async fn readers(&self, visibility: &OkuVisibility) -> miette::Result<HashSet<AuthorId>> {
+        let mut readers = match visibility {
+            OkuVisibility::Public => {
+                return Err(miette::miette!("Public posts can be read by anyone … "))
+            }
+            OkuVisibility::Followers => self.followers().await,
+            OkuVisibility::Circle(name) => {
+                DATABASE
+                    .get_circle(name)?
+                    .ok_or(miette::miette!("No circle named {} … ", name))?
+                    .members
+            }
+        };
+        let blocked = self.blocked_users().await;
+        readers.retain(|x| !blocked.contains(x));
+        readers.insert(self.default_author().await);
+        Ok(readers)
+    }
+
+    /// Generate the path to the post file of a restricted note by the local user regarding a URL.
+    ///
+    /// The path is derived from the local user's secret key, so that the URL cannot be learned from it.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL the note regards.
+    ///
+    /// # Returns
+    ///
+    /// A path which can only be derived by the local user.
+    pub(crate) async fn restricted_post_path(&self, url: &Url) -> miette::Result<String> {
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let key = blake3::derive_key(POST_PATH_CONTEXT, &author.to_bytes());
+        let hash = blake3::keyed_hash(&key, url.as_str().as_bytes());
+        Ok(format!(
+            "/posts/{}.toml",
+            bs58::encode(hash.as_bytes()).into_string()
+        ))
+    }
+
+    /// Serialise a note by the local user, as it should be written to their home replica.
+    ///
+    /// Restricted notes are encrypted with a random key, which is then encrypted for each of the note's readers.
+    ///
+    /// # Arguments
+    ///
+    /// * `note` - A note by the local user.
+    ///
+    /// # Returns
+    ///
+    /// The contents of the note's post file.
+    pub(crate) async fn encode_note(&self, note: &OkuNote) -> miette::Result<String> {
+        if !note.is_restricted() {
+            return toml::to_string_pretty(note).into_diagnostic();
+        }
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+        let mut keys = HashMap::new();
+        for reader in self.readers(&note.visibility).await? {
+            keys.insert(
+                crate::fs::util::fmt(reader),
+                OkuCiphertext::seal(&shared_cipher(&author, &reader, POST_CONTEXT)?, &key)?,
+            );
+        }
+        let encrypted_note = OkuEncryptedNote {
+            keys,
+            note: OkuCiphertext::seal(
+                &XChaCha20Poly1305::new(&key),
+                toml::to_string_pretty(note).into_diagnostic()?.as_bytes(),
+            )?,
+        };
+        toml::to_string_pretty(&encrypted_note).into_diagnostic()
+    }
+
+    /// Parse the contents of a post file, decrypting it if it is restricted.
+    ///
+    /// # Arguments
+    ///
+    /// * `author_id` - The content authorship ID of the post's author.
+    ///
+    /// * `bytes` - The contents of the post file.
+    ///
+    /// # Returns
+    ///
+    /// The note within the post, or none if the post is restricted to users other than the local user.
+    pub async fn decode_note(
+        &self,
+        author_id: &AuthorId,
+        bytes: &[u8],
+    ) -> miette::Result<Option<OkuNote>> {
+        let contents = String::from_utf8_lossy(bytes);
+        let encrypted_note = match toml::from_str::<OkuEncryptedNote>(contents.as_ref()) {
+            Ok(encrypted_note) => encrypted_note,
+            Err(_) => {
+                return toml::from_str::<OkuNote>(contents.as_ref())
+                    .into_diagnostic()
+                    .map(Some)
+            }
+        };
+        let author = self
+            .get_author()
+            .await
+            .map_err(|e| miette::miette!("{}", e))?;
+        let Some(wrapped_key) = encrypted_note.keys.get(&crate::fs::util::fmt(author.id())) else {
+            return Ok(None);
+        };
+        let key = wrapped_key.open(&shared_cipher(&author, author_id, POST_CONTEXT)?)?;
+        let cipher =
+            XChaCha20Poly1305::new_from_slice(&key).map_err(|e| miette::miette!("{}", e))?;
+        let note = encrypted_note.note.open(&cipher)?;
+        toml::from_str::<OkuNote>(String::from_utf8_lossy(&note).as_ref())
+            .into_diagnostic()
+            .map(Some)
+    }
+}
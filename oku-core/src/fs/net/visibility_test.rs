@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::fs::net::core::{shared_cipher, OkuCiphertext};
+    use iroh_docs::Author;
+
+    const CONTEXT: &str = "oku test";
+
+    #[test]
+    fn test_shared_cipher_round_trip() -> miette::Result<()> {
+        let writer = Author::from_bytes(&[1; 32]);
+        let reader = Author::from_bytes(&[2; 32]);
+        let plaintext = b"Hello, World!";
+        let sealed =
+            OkuCiphertext::seal(&shared_cipher(&writer, &reader.id(), CONTEXT)?, plaintext)?;
+
+        // Both users derive the same cipher from their own private key
+        assert_eq!(
+            sealed.open(&shared_cipher(&reader, &writer.id(), CONTEXT)?)?,
+            plaintext
+        );
+        assert_eq!(
+            sealed.open(&shared_cipher(&writer, &reader.id(), CONTEXT)?)?,
+            plaintext
+        );
+
+        // Data can be encrypted by a user for themselves
+        let sealed_for_self =
+            OkuCiphertext::seal(&shared_cipher(&writer, &writer.id(), CONTEXT)?, plaintext)?;
+        assert_eq!(
+            sealed_for_self.open(&shared_cipher(&writer, &writer.id(), CONTEXT)?)?,
+            plaintext
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared_cipher_non_reader() -> miette::Result<()> {
+        let writer = Author::from_bytes(&[1; 32]);
+        let reader = Author::from_bytes(&[2; 32]);
+        let non_reader = Author::from_bytes(&[3; 32]);
+        let sealed = OkuCiphertext::seal(
+            &shared_cipher(&writer, &reader.id(), CONTEXT)?,
+            b"Hello, World!",
+        )?;
+
+        // A user the data was not encrypted for cannot decrypt it
+        assert!(sealed
+            .open(&shared_cipher(&non_reader, &writer.id(), CONTEXT)?)
+            .is_err());
+        assert!(sealed
+            .open(&shared_cipher(&non_reader, &reader.id(), CONTEXT)?)
+            .is_err());
+
+        // Ciphers derived for a different purpose do not share a key
+        assert!(sealed
+            .open(&shared_cipher(&reader, &writer.id(), "oku other test")?)
+            .is_err());
+
+        // Tampered data is rejected
+        let mut tampered = sealed.clone();
+        let replacement = match tampered.ciphertext.starts_with('0') {
+            true => "1",
+            false => "0",
+        };
+        tampered.ciphertext.replace_range(0..1, replacement);
+        assert!(tampered
+            .open(&shared_cipher(&reader, &writer.id(), CONTEXT)?)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -3,10 +3,18 @@ use iroh_docs::DocTicket;
 use log::error;
 use miette::IntoDiagnostic;
 use path_clean::PathClean;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::ffi::CString;
 use std::path::PathBuf;
 
+/// Characters escaped in the values of search parameters, leaving only unreserved characters as they are.
+const SEARCH_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
 /// Cleans a path and ensures it begins with the root.
 ///
 /// # Arguments
@@ -69,6 +77,62 @@ pub fn path_to_entry_prefix(path: &PathBuf) -> Bytes {
     path_bytes.into()
 }
 
+/// Parses a point in time, given as microseconds since the Unix epoch, an RFC 3339 timestamp, or a date.
+///
+/// # Arguments
+///
+/// * `value` - The time to parse; dates are taken to begin at midnight in the local time zone.
+///
+/// # Returns
+///
+/// The time, in microseconds since the Unix epoch.
+pub fn parse_date(value: &str) -> miette::Result<u64> {
+    let value = value.trim();
+    if let Ok(microseconds) = value.parse::<u64>() {
+        return Ok(microseconds);
+    }
+    let timestamp = match chrono::DateTime::parse_from_rfc3339(value) {
+        Ok(timestamp) => timestamp.timestamp_micros(),
+        Err(_) => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .into_diagnostic()?
+            .and_time(chrono::NaiveTime::MIN)
+            .and_local_timezone(chrono::Local)
+            .earliest()
+            .ok_or(miette::miette!(
+                "The start of {value} does not exist in the local time zone … "
+            ))?
+            .timestamp_micros(),
+    };
+    u64::try_from(timestamp)
+        .map_err(|_| miette::miette!("Dates before the Unix epoch are not supported: {value}"))
+}
+
+/// Percent-encode a value for inclusion in the parameters of an `oku:` URI.
+///
+/// # Arguments
+///
+/// * `value` - The value to encode.
+///
+/// # Returns
+///
+/// The value, with all but unreserved characters percent-encoded.
+pub fn encode_search_value(value: &str) -> String {
+    utf8_percent_encode(value, SEARCH_VALUE).to_string()
+}
+
+/// Decode a percent-encoded value from the parameters of an `oku:` URI.
+///
+/// # Arguments
+///
+/// * `value` - The value to decode.
+///
+/// # Returns
+///
+/// The decoded value, with invalid UTF-8 replaced.
+pub fn decode_search_value(value: &str) -> String {
+    percent_decode_str(value).decode_utf8_lossy().to_string()
+}
+
 /// Format bytes as a base32-encoded lowercase string.
 ///
 /// # Arguments
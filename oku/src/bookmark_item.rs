@@ -9,12 +9,13 @@ use glib::subclass::types::ObjectSubclassExt;
 use glib::subclass::types::ObjectSubclassIsExt;
 use glib::value::ToValue;
 use glib::ParamSpec;
+use glib::ParamSpecBoolean;
 use glib::ParamSpecBoxed;
 use glib::ParamSpecBuilderExt;
 use glib::ParamSpecObject;
 use glib::ParamSpecString;
 use glib::Value;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::sync::LazyLock;
 use webkit2gtk::functions::uri_for_display;
@@ -29,6 +30,7 @@ pub mod imp {
         pub(crate) body: RefCell<String>,
         pub(crate) tags: RefCell<Vec<String>>,
         pub(crate) favicon: RefCell<Option<gdk::Texture>>,
+        pub(crate) selected: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -50,6 +52,7 @@ pub mod imp {
                     ParamSpecObject::builder::<gdk::Texture>("favicon")
                         .readwrite()
                         .build(),
+                    ParamSpecBoolean::builder("selected").readwrite().build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -84,6 +87,10 @@ pub mod imp {
                     let favicon = value.get::<gdk::Texture>().ok();
                     self.favicon.set(favicon);
                 }
+                "selected" => {
+                    let selected = value.get::<bool>().unwrap();
+                    self.selected.set(selected);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -96,6 +103,7 @@ pub mod imp {
                 "body" => obj.body().to_value(),
                 "tags" => obj.tags().to_value(),
                 "favicon" => obj.favicon().to_value(),
+                "selected" => obj.selected().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -125,6 +133,9 @@ impl BookmarkItem {
     pub fn favicon(&self) -> Option<gdk::Texture> {
         self.imp().favicon.borrow().clone()
     }
+    pub fn selected(&self) -> bool {
+        self.imp().selected.get()
+    }
     pub fn update(&self, bookmark: Bookmark, window: Window) {
         let ctx = glib::MainContext::default();
         let this = self.clone();
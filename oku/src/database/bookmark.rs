@@ -2,7 +2,10 @@ use super::{BrowserDatabase, DATABASE};
 use miette::IntoDiagnostic;
 use native_db::*;
 use native_model::{native_model, Model};
-use oku_core::{database::posts::core::OkuNote, fs::FS_PATH};
+use oku_core::{
+    database::posts::core::{OkuNote, OkuVisibility},
+    fs::FS_PATH,
+};
 use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -72,6 +75,9 @@ impl TryFrom<Bookmark> for OkuNote {
             title: value.title,
             body: value.body,
             tags: value.tags,
+            content_warning: None,
+            sensitive: false,
+            visibility: OkuVisibility::Public,
         })
     }
 }
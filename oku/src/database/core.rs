@@ -3,7 +3,7 @@ use crate::database::policy::PolicySettingRecord;
 use crate::{suggestion_item::SuggestionItem, DATA_DIR};
 use miette::IntoDiagnostic;
 use native_db::*;
-use oku_core::database::core::OkuDatabase;
+use oku_core::database::core::{OkuDatabase, DATABASE as OKU_DATABASE};
 use std::{path::PathBuf, sync::LazyLock};
 use webkit2gtk::FaviconDatabase;
 
@@ -16,6 +16,10 @@ pub(crate) static MODELS: LazyLock<Models> = LazyLock::new(|| {
     models.define::<HistoryRecord>().unwrap();
     models.define::<Bookmark>().unwrap();
     models.define::<PolicySettingRecord>().unwrap();
+    models.define::<Notification>().unwrap();
+    models.define::<NotificationRule>().unwrap();
+    models.define::<Feed>().unwrap();
+    models.define::<SiteExport>().unwrap();
     models
 });
 
@@ -23,6 +27,8 @@ pub struct BrowserDatabase {
     pub(super) database: Database<'static>,
     pub history_sender: tokio::sync::watch::Sender<()>,
     pub bookmark_sender: tokio::sync::watch::Sender<()>,
+    pub notification_sender: tokio::sync::watch::Sender<()>,
+    pub feed_sender: tokio::sync::watch::Sender<()>,
 }
 
 impl BrowserDatabase {
@@ -33,6 +39,8 @@ impl BrowserDatabase {
                 .into_diagnostic()?,
             history_sender: tokio::sync::watch::channel(()).0,
             bookmark_sender: tokio::sync::watch::channel(()).0,
+            notification_sender: tokio::sync::watch::channel(()).0,
+            feed_sender: tokio::sync::watch::channel(()).0,
         };
         if database.get_history_records()?.len() as u64
             != HISTORY_RECORD_INDEX_READER.searcher().num_docs()
@@ -53,6 +61,7 @@ impl BrowserDatabase {
         let history_records = Self::search_history_records(query_string.clone(), None)?;
         let bookmarks = Self::search_bookmarks(query_string.clone(), None)?;
         let okunet_posts = OkuDatabase::search_posts(&query_string, &None)?;
+        let okunet_users = OkuDatabase::search_users(&query_string, &None)?;
 
         let history_record_suggestions: Vec<_> = history_records
             .into_iter()
@@ -66,11 +75,22 @@ impl BrowserDatabase {
             .into_iter()
             .map(|x| SuggestionItem::new(x.note.title, x.note.url.to_string(), favicon_database))
             .collect();
+        let okunet_user_suggestions = okunet_users
+            .into_iter()
+            .map(|x| {
+                SuggestionItem::new(
+                    OKU_DATABASE.display_name(&x),
+                    format!("oku:{}", oku_core::fs::util::fmt(x.author_id)),
+                    favicon_database,
+                )
+            })
+            .collect();
 
         Ok([
             history_record_suggestions,
             bookmark_suggestions,
             okunet_post_suggestions,
+            okunet_user_suggestions,
         ]
         .concat())
     }
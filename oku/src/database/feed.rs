@@ -0,0 +1,149 @@
+use super::BrowserDatabase;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use oku_core::{
+    database::{mutes::OkuMuteRule, posts::search::OkuPostSearch},
+    iroh_docs::AuthorId,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[native_model(id = 6, version = 1)]
+#[native_db]
+/// A saved search of OkuNet posts, viewed as its own feed
+pub struct Feed {
+    #[primary_key]
+    pub name: String,
+    pub query: String,
+    pub tags: HashSet<String>,
+    pub authors: HashSet<AuthorId>,
+    pub mutes: Vec<OkuMuteRule>,
+    /// When the feed was last viewed, in microseconds since the Unix epoch
+    pub last_viewed: Option<u64>,
+}
+
+impl Feed {
+    pub fn new(name: String, search: &OkuPostSearch) -> Self {
+        Self {
+            name,
+            query: search.query.clone(),
+            tags: search.tags.clone(),
+            authors: search.authors.clone(),
+            mutes: search.mutes.clone(),
+            last_viewed: None,
+        }
+    }
+
+    /// The search finding this feed's posts, from newest to oldest
+    pub fn search(&self) -> OkuPostSearch {
+        OkuPostSearch {
+            query: self.query.clone(),
+            tags: self.tags.clone(),
+            authors: self.authors.clone(),
+            mutes: self.mutes.clone(),
+            newest_first: true,
+            ..Default::default()
+        }
+    }
+
+    /// The search finding this feed's posts written since it was last viewed
+    pub fn unread_search(&self) -> OkuPostSearch {
+        OkuPostSearch {
+            after: self.last_viewed.map(|x| x + 1),
+            limit: 0,
+            ..self.search()
+        }
+    }
+
+    pub fn description(&self) -> String {
+        let mut description = Vec::new();
+        if !self.query.trim().is_empty() {
+            description.push(format!("“{}”", self.query.trim()));
+        }
+        let mut tags: Vec<_> = self.tags.iter().map(|x| format!("#{x}")).collect();
+        tags.sort_unstable();
+        description.extend(tags);
+        match self.authors.len() {
+            0 => (),
+            1 => description.push("1 author".to_string()),
+            n => description.push(format!("{n} authors")),
+        }
+        match self.mutes.len() {
+            0 => (),
+            1 => description.push("1 mute rule".to_string()),
+            n => description.push(format!("{n} mute rules")),
+        }
+        match description.is_empty() {
+            true => "All posts".to_string(),
+            false => description.join(" · "),
+        }
+    }
+}
+
+impl BrowserDatabase {
+    pub fn upsert_feed(&self, feed: Feed) -> miette::Result<Option<Feed>> {
+        if feed.name.trim().is_empty() || feed.name.contains(['/', '?', '&', '#']) {
+            return Err(miette::miette!(
+                "Feed name {:?} must not be empty, nor contain '/', '?', '&', or '#' … ",
+                feed.name
+            ));
+        }
+        for rule in feed.mutes.iter() {
+            rule.validate()?;
+        }
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<Feed> = rw.upsert(feed).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+        self.feed_sender.send_replace(());
+
+        Ok(old_value)
+    }
+
+    pub fn delete_feed(&self, name: String) -> miette::Result<Option<Feed>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let feed: Option<Feed> = rw.get().primary(name).into_diagnostic()?;
+        let removed_feed = match feed {
+            Some(feed) => Some(rw.remove(feed).into_diagnostic()?),
+            None => None,
+        };
+        rw.commit().into_diagnostic()?;
+        self.feed_sender.send_replace(());
+
+        Ok(removed_feed)
+    }
+
+    pub fn get_feeds(&self) -> miette::Result<Vec<Feed>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<Feed>, _>>()
+            .into_diagnostic()
+    }
+
+    pub fn get_feed(&self, name: String) -> miette::Result<Option<Feed>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.get().primary(name).into_diagnostic()
+    }
+
+    /// Records that a feed has been viewed, so only posts written afterwards are unread
+    pub fn mark_feed_viewed(&self, name: String) -> miette::Result<Option<Feed>> {
+        let feed = self.get_feed(name)?;
+        if let Some(feed) = feed.clone() {
+            self.upsert_feed(Feed {
+                last_viewed: Some(
+                    chrono::Utc::now()
+                        .timestamp_micros()
+                        .try_into()
+                        .unwrap_or(0),
+                ),
+                ..feed
+            })?;
+        }
+        Ok(feed)
+    }
+}
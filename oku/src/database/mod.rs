@@ -1,10 +1,19 @@
 mod bookmark;
 mod core;
+mod feed;
 mod history_record;
+mod notification;
+mod site_export;
 #[allow(unused_imports)]
 pub use self::bookmark::*;
 #[allow(unused_imports)]
 pub use self::core::*;
 #[allow(unused_imports)]
+pub use self::feed::*;
+#[allow(unused_imports)]
 pub use self::history_record::*;
+#[allow(unused_imports)]
+pub use self::notification::*;
+#[allow(unused_imports)]
+pub use self::site_export::*;
 pub mod policy;
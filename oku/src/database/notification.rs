@@ -0,0 +1,307 @@
+use super::BrowserDatabase;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use oku_core::{
+    database::{core::DATABASE as OKU_DATABASE, posts::core::OkuPost},
+    iroh_docs::AuthorId,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum NotificationKind {
+    Post,
+    Follower,
+    Mention,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[native_model(id = 4, version = 1)]
+#[native_db]
+pub struct Notification {
+    #[primary_key]
+    pub id: String,
+    pub kind: NotificationKind,
+    pub author_id: AuthorId,
+    pub title: String,
+    pub body: String,
+    pub url: Option<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub read: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum NotificationTrigger {
+    /// A new post from a particular user
+    PostsBy(AuthorId),
+    /// Someone following the local user
+    Followers,
+    /// A mention of the local user's author ID in a note body
+    Mentions,
+}
+
+impl std::fmt::Display for NotificationTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PostsBy(author_id) => {
+                write!(f, "posts:{}", oku_core::fs::util::fmt(author_id))
+            }
+            Self::Followers => write!(f, "followers"),
+            Self::Mentions => write!(f, "mentions"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[native_model(id = 5, version = 1)]
+#[native_db(
+    primary_key(id_string -> String)
+)]
+pub struct NotificationRule {
+    pub trigger: NotificationTrigger,
+    pub enabled: bool,
+    /// Whether the content matching this rule when it was enabled has been recorded, so only later content notifies
+    pub primed: bool,
+}
+
+impl NotificationRule {
+    fn id_string(&self) -> String {
+        self.trigger.to_string()
+    }
+
+    pub fn new(trigger: NotificationTrigger, enabled: bool) -> Self {
+        Self {
+            trigger,
+            enabled,
+            primed: false,
+        }
+    }
+}
+
+fn post_notification(kind: NotificationKind, post: &OkuPost) -> Notification {
+    let author_id = post.entry.author();
+    let author_name = OKU_DATABASE
+        .get_petname(&author_id)
+        .or(post.user().identity.map(|x| x.name))
+        .unwrap_or(oku_core::fs::util::fmt_short(author_id));
+    let (prefix, title) = match kind {
+        NotificationKind::Mention => ("mention", format!("{author_name} mentioned you")),
+        _ => ("post", format!("New post from {author_name}")),
+    };
+    Notification {
+        id: format!(
+            "{prefix}:{}:{}",
+            oku_core::fs::util::fmt(author_id),
+            String::from_utf8_lossy(post.entry.key())
+        ),
+        kind,
+        author_id,
+        title,
+        body: post.note.title.clone(),
+        url: Some(
+            oku_core::fs::util::entry_key_to_path(post.entry.key())
+                .map(|x| {
+                    format!(
+                        "oku:{}{}",
+                        oku_core::fs::util::fmt(author_id),
+                        x.to_string_lossy()
+                    )
+                })
+                .unwrap_or(post.note.url.to_string()),
+        ),
+        timestamp: chrono::Utc::now(),
+        read: false,
+    }
+}
+
+impl BrowserDatabase {
+    /// Finds content matching the enabled notification rules which has not notified before.
+    ///
+    /// Content matching a rule when it is first checked is recorded as read, so enabling a rule does not notify about past content.
+    pub fn check_for_notifications(&self, me: &AuthorId) -> miette::Result<Vec<Notification>> {
+        let known_notifications: HashSet<_> = self
+            .get_notifications()?
+            .into_par_iter()
+            .map(|x| x.id)
+            .collect();
+        let mut new_notifications = Vec::new();
+        for mut rule in self.get_notification_rules()? {
+            if !rule.enabled {
+                continue;
+            }
+            let matches: Vec<_> = match rule.trigger {
+                NotificationTrigger::PostsBy(author_id) => OKU_DATABASE
+                    .get_posts_by_author(&author_id)?
+                    .iter()
+                    .map(|x| post_notification(NotificationKind::Post, x))
+                    .collect(),
+                NotificationTrigger::Followers => OKU_DATABASE
+                    .get_users()?
+                    .into_iter()
+                    .filter(|x| {
+                        x.identity
+                            .as_ref()
+                            .is_some_and(|y| y.following.contains(me))
+                    })
+                    .map(|x| {
+                        let author_name = OKU_DATABASE
+                            .get_petname(&x.author_id)
+                            .or(x.identity.map(|y| y.name))
+                            .unwrap_or(oku_core::fs::util::fmt_short(x.author_id));
+                        Notification {
+                            id: format!("follower:{}", oku_core::fs::util::fmt(x.author_id)),
+                            kind: NotificationKind::Follower,
+                            author_id: x.author_id,
+                            title: format!("{author_name} followed you"),
+                            body: oku_core::fs::util::fmt(x.author_id),
+                            url: Some(format!("oku:{}", oku_core::fs::util::fmt(x.author_id))),
+                            timestamp: chrono::Utc::now(),
+                            read: false,
+                        }
+                    })
+                    .collect(),
+                NotificationTrigger::Mentions => OKU_DATABASE
+                    .get_posts_mentioning(me)?
+                    .into_par_iter()
+                    .filter(|x| x.entry.author() != *me)
+                    .map(|x| post_notification(NotificationKind::Mention, &x))
+                    .collect(),
+            };
+            let mut matches: Vec<_> = matches
+                .into_iter()
+                .filter(|x| !known_notifications.contains(&x.id))
+                .collect();
+            if !rule.primed {
+                for notification in matches.iter_mut() {
+                    notification.read = true;
+                }
+                self.upsert_notifications(matches)?;
+                rule.primed = true;
+                self.upsert_notification_rule(rule)?;
+            } else {
+                self.upsert_notifications(matches.clone())?;
+                new_notifications.extend(matches);
+            }
+        }
+        Ok(new_notifications)
+    }
+
+    pub fn upsert_notifications(
+        &self,
+        notifications: Vec<Notification>,
+    ) -> miette::Result<Vec<Option<Notification>>> {
+        if notifications.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_notifications: Vec<_> = notifications
+            .into_iter()
+            .filter_map(|notification| rw.upsert(notification).ok())
+            .collect();
+        rw.commit().into_diagnostic()?;
+        self.notification_sender.send_replace(());
+
+        Ok(old_notifications)
+    }
+
+    pub fn mark_notifications_read(&self) -> miette::Result<()> {
+        let unread_notifications: Vec<_> = self
+            .get_notifications()?
+            .into_iter()
+            .filter(|x| !x.read)
+            .map(|x| Notification { read: true, ..x })
+            .collect();
+        self.upsert_notifications(unread_notifications)?;
+        Ok(())
+    }
+
+    pub fn mark_notification_read(&self, id: String) -> miette::Result<Option<Notification>> {
+        let notification = self.get_notification(id)?;
+        if let Some(notification) = notification.clone() {
+            if !notification.read {
+                self.upsert_notifications(vec![Notification {
+                    read: true,
+                    ..notification
+                }])?;
+            }
+        }
+        Ok(notification)
+    }
+
+    pub fn get_notifications(&self) -> miette::Result<Vec<Notification>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let mut notifications = r
+            .scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<Notification>, _>>()
+            .into_diagnostic()?;
+        notifications.sort_unstable_by_key(|x| std::cmp::Reverse(x.timestamp));
+        Ok(notifications)
+    }
+
+    pub fn get_notification(&self, id: String) -> miette::Result<Option<Notification>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.get().primary(id).into_diagnostic()
+    }
+
+    pub fn upsert_notification_rule(
+        &self,
+        notification_rule: NotificationRule,
+    ) -> miette::Result<Option<NotificationRule>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<NotificationRule> = rw.upsert(notification_rule).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+
+        Ok(old_value)
+    }
+
+    pub fn delete_notification_rule(
+        &self,
+        trigger: NotificationTrigger,
+    ) -> miette::Result<Option<NotificationRule>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let notification_rule: Option<NotificationRule> =
+            rw.get().primary(trigger.to_string()).into_diagnostic()?;
+        let removed_notification_rule = match notification_rule {
+            Some(notification_rule) => Some(rw.remove(notification_rule).into_diagnostic()?),
+            None => None,
+        };
+        rw.commit().into_diagnostic()?;
+
+        Ok(removed_notification_rule)
+    }
+
+    /// Retrieves the notification rules, including the default rules for followers and mentions if they have not been changed.
+    pub fn get_notification_rules(&self) -> miette::Result<Vec<NotificationRule>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        let mut notification_rules = r
+            .scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<NotificationRule>, _>>()
+            .into_diagnostic()?;
+        for trigger in [
+            NotificationTrigger::Followers,
+            NotificationTrigger::Mentions,
+        ] {
+            if !notification_rules.iter().any(|x| x.trigger == trigger) {
+                notification_rules.push(NotificationRule::new(trigger, true));
+            }
+        }
+        Ok(notification_rules)
+    }
+
+    pub fn get_notification_rule(&self, trigger: NotificationTrigger) -> Option<NotificationRule> {
+        self.get_notification_rules()
+            .ok()?
+            .into_iter()
+            .find(|x| x.trigger == trigger)
+    }
+}
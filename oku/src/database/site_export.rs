@@ -0,0 +1,66 @@
+use super::BrowserDatabase;
+use miette::IntoDiagnostic;
+use native_db::*;
+use native_model::{native_model, Model};
+use oku_core::iroh_docs::{AuthorId, NamespaceId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[native_model(id = 7, version = 1)]
+#[native_db]
+/// A replica into which a user's OkuNet posts are exported as a static website
+pub struct SiteExport {
+    #[primary_key]
+    pub replica_id: String,
+    /// The user whose posts are exported; if unspecified, the local user
+    pub author_id: Option<AuthorId>,
+}
+
+impl SiteExport {
+    pub fn namespace_id(&self) -> miette::Result<NamespaceId> {
+        Ok(NamespaceId::from(
+            oku_core::fs::util::parse_array_hex_or_base32::<32>(&self.replica_id)?,
+        ))
+    }
+}
+
+impl BrowserDatabase {
+    pub fn upsert_site_export(
+        &self,
+        site_export: SiteExport,
+    ) -> miette::Result<Option<SiteExport>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let old_value: Option<SiteExport> = rw.upsert(site_export).into_diagnostic()?;
+        rw.commit().into_diagnostic()?;
+
+        Ok(old_value)
+    }
+
+    pub fn delete_site_export(&self, replica_id: String) -> miette::Result<Option<SiteExport>> {
+        let rw = self.database.rw_transaction().into_diagnostic()?;
+        let site_export: Option<SiteExport> = rw.get().primary(replica_id).into_diagnostic()?;
+        let removed_site_export = match site_export {
+            Some(site_export) => Some(rw.remove(site_export).into_diagnostic()?),
+            None => None,
+        };
+        rw.commit().into_diagnostic()?;
+
+        Ok(removed_site_export)
+    }
+
+    pub fn get_site_exports(&self) -> miette::Result<Vec<SiteExport>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.scan()
+            .primary()
+            .into_diagnostic()?
+            .all()
+            .into_diagnostic()?
+            .collect::<Result<Vec<SiteExport>, _>>()
+            .into_diagnostic()
+    }
+
+    pub fn get_site_export(&self, replica_id: String) -> miette::Result<Option<SiteExport>> {
+        let r = self.database.r_transaction().into_diagnostic()?;
+        r.get().primary(replica_id).into_diagnostic()
+    }
+}
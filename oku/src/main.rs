@@ -8,6 +8,7 @@ pub mod bookmark_item;
 pub mod config;
 pub mod database;
 pub mod history_item;
+pub mod notification_item;
 pub mod okunet;
 pub mod replica_item;
 pub mod scheme_handlers;
@@ -234,6 +235,11 @@ async fn main() {
     debug!("Creating web context … ");
     let (web_context, mount_handle, ipfs) = create_web_context().await;
     debug!("Web context created");
+    okunet::notifications::setup_notification_actions(&application, &web_context);
+    glib::MainContext::default().spawn_local(okunet::notifications::watch_notifications(
+        application.clone(),
+    ));
+    glib::MainContext::default().spawn_local(okunet::sites::watch_site_exports());
     application.connect_activate(clone!(move |application| {
         let ctx = glib::MainContext::default();
         ctx.spawn_local(clone!(
@@ -0,0 +1,123 @@
+use crate::database::Notification;
+use glib::object::ObjectExt;
+use glib::property::PropertySet;
+use glib::subclass::object::ObjectImpl;
+use glib::subclass::types::ObjectSubclass;
+use glib::subclass::types::ObjectSubclassExt;
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::value::ToValue;
+use glib::ParamSpec;
+use glib::ParamSpecBoolean;
+use glib::ParamSpecBuilderExt;
+use glib::ParamSpecString;
+use glib::Value;
+use std::cell::RefCell;
+use std::sync::LazyLock;
+
+pub mod imp {
+    use super::*;
+
+    #[derive(Default, Debug)]
+    pub struct NotificationItem {
+        pub(crate) id: RefCell<String>,
+        pub(crate) title: RefCell<String>,
+        pub(crate) body: RefCell<String>,
+        pub(crate) timestamp: RefCell<String>,
+        pub(crate) read: RefCell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for NotificationItem {
+        const NAME: &'static str = "OkuNotificationItem";
+        type Type = super::NotificationItem;
+    }
+
+    impl ObjectImpl for NotificationItem {
+        fn properties() -> &'static [ParamSpec] {
+            static PROPERTIES: LazyLock<Vec<ParamSpec>> = LazyLock::new(|| {
+                vec![
+                    ParamSpecString::builder("id").readwrite().build(),
+                    ParamSpecString::builder("title").readwrite().build(),
+                    ParamSpecString::builder("body").readwrite().build(),
+                    ParamSpecString::builder("timestamp").readwrite().build(),
+                    ParamSpecBoolean::builder("read").readwrite().build(),
+                ]
+            });
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &Value, pspec: &ParamSpec) {
+            match pspec.name() {
+                "id" => {
+                    let id = value.get::<String>().unwrap();
+                    self.id.set(id);
+                }
+                "title" => {
+                    let title = value.get::<String>().unwrap();
+                    self.title.set(html_escape::encode_text(&title).to_string());
+                }
+                "body" => {
+                    let body = value.get::<String>().unwrap();
+                    self.body.set(html_escape::encode_text(&body).to_string());
+                }
+                "timestamp" => {
+                    let timestamp = value.get::<String>().unwrap();
+                    self.timestamp
+                        .set(html_escape::encode_text(&timestamp).to_string());
+                }
+                "read" => {
+                    let read = value.get::<bool>().unwrap();
+                    self.read.set(read);
+                }
+                _ => unimplemented!(),
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &ParamSpec) -> Value {
+            let obj = self.obj();
+            match pspec.name() {
+                "id" => obj.id().to_value(),
+                "title" => obj.title().to_value(),
+                "body" => obj.body().to_value(),
+                "timestamp" => obj.timestamp().to_value(),
+                "read" => obj.read().to_value(),
+                _ => unimplemented!(),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct NotificationItem(ObjectSubclass<imp::NotificationItem>);
+}
+
+unsafe impl Send for NotificationItem {}
+unsafe impl Sync for NotificationItem {}
+
+impl NotificationItem {
+    pub fn id(&self) -> String {
+        self.imp().id.borrow().to_string()
+    }
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().to_string()
+    }
+    pub fn body(&self) -> String {
+        self.imp().body.borrow().to_string()
+    }
+    pub fn timestamp(&self) -> String {
+        self.imp().timestamp.borrow().to_string()
+    }
+    pub fn read(&self) -> bool {
+        self.imp().read.borrow().to_owned()
+    }
+
+    pub fn new(notification: &Notification) -> Self {
+        glib::Object::builder::<Self>()
+            .property("id", &notification.id)
+            .property("title", &notification.title)
+            .property("body", &notification.body)
+            .property("timestamp", notification.timestamp.to_rfc2822())
+            .property("read", notification.read)
+            .build()
+    }
+}
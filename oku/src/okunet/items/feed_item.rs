@@ -0,0 +1,117 @@
+use crate::database::Feed;
+use glib::object::ObjectExt;
+use glib::property::PropertySet;
+use glib::subclass::object::ObjectImpl;
+use glib::subclass::types::ObjectSubclass;
+use glib::subclass::types::ObjectSubclassExt;
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::value::ToValue;
+use glib::ParamSpec;
+use glib::ParamSpecBuilderExt;
+use glib::ParamSpecString;
+use glib::ParamSpecUInt;
+use glib::Value;
+use std::cell::RefCell;
+use std::sync::LazyLock;
+
+pub mod imp {
+    use super::*;
+
+    #[derive(Default, Debug)]
+    pub struct FeedItem {
+        pub(crate) name: RefCell<String>,
+        pub(crate) title: RefCell<String>,
+        pub(crate) description: RefCell<String>,
+        pub(crate) unread: RefCell<u32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FeedItem {
+        const NAME: &'static str = "OkuNetFeedItem";
+        type Type = super::FeedItem;
+    }
+
+    impl ObjectImpl for FeedItem {
+        fn properties() -> &'static [ParamSpec] {
+            static PROPERTIES: LazyLock<Vec<ParamSpec>> = LazyLock::new(|| {
+                vec![
+                    ParamSpecString::builder("name").readwrite().build(),
+                    ParamSpecString::builder("title").readwrite().build(),
+                    ParamSpecString::builder("description").readwrite().build(),
+                    ParamSpecUInt::builder("unread").readwrite().build(),
+                ]
+            });
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &Value, pspec: &ParamSpec) {
+            match pspec.name() {
+                "name" => {
+                    let name = value.get::<String>().unwrap();
+                    self.name.set(name);
+                }
+                "title" => {
+                    let title = value.get::<String>().unwrap();
+                    self.title.set(html_escape::encode_text(&title).to_string());
+                }
+                "description" => {
+                    let description = value.get::<String>().unwrap();
+                    self.description
+                        .set(html_escape::encode_text(&description).to_string());
+                }
+                "unread" => {
+                    let unread = value.get::<u32>().unwrap();
+                    self.unread.set(unread);
+                }
+                _ => unimplemented!(),
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &ParamSpec) -> Value {
+            let obj = self.obj();
+            match pspec.name() {
+                "name" => obj.name().to_value(),
+                "title" => obj.title().to_value(),
+                "description" => obj.description().to_value(),
+                "unread" => obj.unread().to_value(),
+                _ => unimplemented!(),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct FeedItem(ObjectSubclass<imp::FeedItem>);
+}
+
+unsafe impl Send for FeedItem {}
+unsafe impl Sync for FeedItem {}
+
+impl FeedItem {
+    pub fn name(&self) -> String {
+        self.imp().name.borrow().to_string()
+    }
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().to_string()
+    }
+    pub fn description(&self) -> String {
+        self.imp().description.borrow().to_string()
+    }
+    pub fn unread(&self) -> u32 {
+        self.imp().unread.borrow().to_owned()
+    }
+
+    /// Create an item for a feed, with the number of its posts written since it was last viewed.
+    pub fn new(feed: &Feed, unread: usize) -> Self {
+        let title = match unread {
+            0 => feed.name.clone(),
+            _ => format!("{} ({unread})", feed.name),
+        };
+        glib::Object::builder::<Self>()
+            .property("name", &feed.name)
+            .property("title", title)
+            .property("description", feed.description())
+            .property("unread", unread as u32)
+            .build()
+    }
+}
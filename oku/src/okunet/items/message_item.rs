@@ -0,0 +1,139 @@
+use glib::object::ObjectExt;
+use glib::property::PropertySet;
+use glib::subclass::object::ObjectImpl;
+use glib::subclass::types::ObjectSubclass;
+use glib::subclass::types::ObjectSubclassExt;
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::value::ToValue;
+use glib::ParamSpec;
+use glib::ParamSpecBoolean;
+use glib::ParamSpecBuilderExt;
+use glib::ParamSpecString;
+use glib::Value;
+use oku_core::database::messages::OkuMessage;
+use oku_core::iroh_docs::AuthorId;
+use std::cell::RefCell;
+use std::sync::LazyLock;
+
+pub mod imp {
+    use super::*;
+
+    #[derive(Default, Debug)]
+    pub struct MessageItem {
+        pub(crate) author_id: RefCell<String>,
+        pub(crate) name: RefCell<String>,
+        pub(crate) body: RefCell<String>,
+        pub(crate) timestamp: RefCell<String>,
+        pub(crate) outgoing: RefCell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MessageItem {
+        const NAME: &'static str = "OkuNetMessageItem";
+        type Type = super::MessageItem;
+    }
+
+    impl ObjectImpl for MessageItem {
+        fn properties() -> &'static [ParamSpec] {
+            static PROPERTIES: LazyLock<Vec<ParamSpec>> = LazyLock::new(|| {
+                vec![
+                    ParamSpecString::builder("author-id").readwrite().build(),
+                    ParamSpecString::builder("name").readwrite().build(),
+                    ParamSpecString::builder("body").readwrite().build(),
+                    ParamSpecString::builder("timestamp").readwrite().build(),
+                    ParamSpecBoolean::builder("outgoing").readwrite().build(),
+                ]
+            });
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &Value, pspec: &ParamSpec) {
+            match pspec.name() {
+                "author-id" => {
+                    let author_id = value.get::<String>().unwrap();
+                    self.author_id.set(author_id);
+                }
+                "name" => {
+                    let name = value.get::<String>().unwrap();
+                    self.name.set(html_escape::encode_text(&name).to_string());
+                }
+                "body" => {
+                    let body = value.get::<String>().unwrap();
+                    self.body.set(html_escape::encode_text(&body).to_string());
+                }
+                "timestamp" => {
+                    let timestamp = value.get::<String>().unwrap();
+                    self.timestamp.set(timestamp);
+                }
+                "outgoing" => {
+                    let outgoing = value.get::<bool>().unwrap();
+                    self.outgoing.set(outgoing);
+                }
+                _ => unimplemented!(),
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &ParamSpec) -> Value {
+            let obj = self.obj();
+            match pspec.name() {
+                "author-id" => obj.author_id().to_value(),
+                "name" => obj.name().to_value(),
+                "body" => obj.body().to_value(),
+                "timestamp" => obj.timestamp().to_value(),
+                "outgoing" => obj.outgoing().to_value(),
+                _ => unimplemented!(),
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct MessageItem(ObjectSubclass<imp::MessageItem>);
+}
+
+unsafe impl Send for MessageItem {}
+unsafe impl Sync for MessageItem {}
+
+impl MessageItem {
+    pub fn author_id(&self) -> String {
+        self.imp().author_id.borrow().to_string()
+    }
+    pub fn name(&self) -> String {
+        self.imp().name.borrow().to_string()
+    }
+    pub fn body(&self) -> String {
+        self.imp().body.borrow().to_string()
+    }
+    pub fn timestamp(&self) -> String {
+        self.imp().timestamp.borrow().to_string()
+    }
+    pub fn outgoing(&self) -> bool {
+        self.imp().outgoing.borrow().to_owned()
+    }
+
+    /// Create an item for a message, shown from the perspective of the local user.
+    pub fn new(message: &OkuMessage, me: &AuthorId) -> Self {
+        let correspondent = message.correspondent(me);
+        let name = oku_core::database::core::DATABASE
+            .get_petname(&correspondent)
+            .or(oku_core::database::core::DATABASE
+                .get_user(&correspondent)
+                .ok()
+                .flatten()
+                .and_then(|x| x.identity)
+                .map(|x| x.name))
+            .unwrap_or(oku_core::fs::util::fmt_short(correspondent));
+        let timestamp = chrono::DateTime::from_timestamp_micros(
+            message.entry.timestamp().try_into().unwrap_or(0),
+        )
+        .map(|x| x.to_rfc2822())
+        .unwrap_or_default();
+        glib::Object::builder::<Self>()
+            .property("author-id", oku_core::fs::util::fmt(correspondent))
+            .property("name", name)
+            .property("body", &message.body)
+            .property("timestamp", timestamp)
+            .property("outgoing", message.sender() == *me)
+            .build()
+    }
+}
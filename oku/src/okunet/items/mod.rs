@@ -1 +1,3 @@
+pub mod feed_item;
+pub mod message_item;
 pub mod post_item;
@@ -12,6 +12,7 @@ use glib::ParamSpecBoxed;
 use glib::ParamSpecBuilderExt;
 use glib::ParamSpecString;
 use glib::Value;
+use oku_core::database::core::DATABASE;
 use oku_core::database::posts::core::OkuPost;
 use std::cell::RefCell;
 use std::sync::LazyLock;
@@ -29,6 +30,7 @@ pub mod imp {
         pub(crate) tags: RefCell<Vec<String>>,
         pub(crate) author_id: RefCell<String>,
         pub(crate) author_name: RefCell<Option<String>>,
+        pub(crate) content_warning: RefCell<Option<String>>,
     }
 
     #[glib::object_subclass]
@@ -49,6 +51,9 @@ pub mod imp {
                         .build(),
                     ParamSpecString::builder("author-id").readwrite().build(),
                     ParamSpecString::builder("author-name").readwrite().build(),
+                    ParamSpecString::builder("content-warning")
+                        .readwrite()
+                        .build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -93,6 +98,11 @@ pub mod imp {
                         None => self.author_name.set(None),
                     }
                 }
+                "content-warning" => {
+                    let content_warning = value.get::<Option<String>>().ok().flatten();
+                    self.content_warning
+                        .set(content_warning.map(|x| html_escape::encode_text(&x).to_string()));
+                }
                 _ => unimplemented!(),
             }
         }
@@ -106,6 +116,7 @@ pub mod imp {
                 "tags" => obj.tags().to_value(),
                 "author-id" => obj.author_id().to_value(),
                 "author-name" => obj.author_name().to_value(),
+                "content-warning" => obj.content_warning().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -130,7 +141,8 @@ impl From<&OkuPost> for PostItem {
                 value.note.tags.clone().into_iter().collect::<Vec<String>>(),
             )
             .property("author-id", oku_core::fs::util::fmt(value.entry.author()))
-            .property("author-name", value.user().identity.map(|x| x.name))
+            .property("author-name", Some(DATABASE.display_name(&value.user())))
+            .property("content-warning", value.note.warning())
             .build()
     }
 }
@@ -160,6 +172,9 @@ impl PostItem {
     pub fn author_name(&self) -> Option<String> {
         self.imp().author_name.borrow().to_owned()
     }
+    pub fn content_warning(&self) -> Option<String> {
+        self.imp().content_warning.borrow().to_owned()
+    }
 
     pub fn update(&self, post: OkuPost) {
         let ctx = glib::MainContext::default();
@@ -174,7 +189,8 @@ impl PostItem {
                     &post.note.tags.clone().into_iter().collect::<Vec<String>>(),
                 ),
                 ("author-id", &oku_core::fs::util::fmt(post.entry.author())),
-                ("author-name", &post.user().identity.map(|x| x.name)),
+                ("author-name", &Some(DATABASE.display_name(&post.user()))),
+                ("content-warning", &post.note.warning()),
             ]);
         });
     }
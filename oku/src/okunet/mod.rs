@@ -1 +1,3 @@
 pub mod items;
+pub mod notifications;
+pub mod sites;
@@ -0,0 +1,99 @@
+use crate::database::{Notification, DATABASE};
+use crate::widgets::window::{NewTabArguments, NewWebTabArguments, Window};
+use crate::NODE;
+use gio::prelude::*;
+use glib::clone;
+use gtk::prelude::GtkWindowExt;
+use log::error;
+use webkit2gtk::prelude::WebViewExt;
+use webkit2gtk::WebContext;
+
+pub const OPEN_NOTIFICATION_ACTION: &str = "open-notification";
+
+fn send_notification(application: &libadwaita::Application, notification: &Notification) {
+    let desktop_notification = gio::Notification::new(&notification.title);
+    desktop_notification.set_body(Some(&notification.body));
+    desktop_notification.set_default_action_and_target_value(
+        &format!("app.{OPEN_NOTIFICATION_ACTION}"),
+        Some(&notification.id.to_variant()),
+    );
+    application.send_notification(Some(&notification.id), &desktop_notification);
+}
+
+pub async fn watch_notifications(application: libadwaita::Application) {
+    if let Some(node) = NODE.get() {
+        let mut post_rx = node.okunet_post_sender.subscribe();
+        let mut user_rx = node.okunet_user_sender.subscribe();
+        loop {
+            post_rx.borrow_and_update();
+            user_rx.borrow_and_update();
+            let me = node.default_author().await;
+            match tokio::task::spawn_blocking(move || DATABASE.check_for_notifications(&me)).await {
+                Ok(Ok(notifications)) => {
+                    for notification in notifications.iter() {
+                        send_notification(&application, notification);
+                    }
+                }
+                Ok(Err(e)) => error!("{}", e),
+                Err(e) => error!("{}", e),
+            }
+            let changed = tokio::select! {
+                changed = post_rx.changed() => changed,
+                changed = user_rx.changed() => changed,
+            };
+            if let Err(e) = changed {
+                error!("{}", e);
+                break;
+            }
+            // Posts and users tend to change in bursts while fetching from the OkuNet
+            glib::timeout_future_seconds(5).await;
+        }
+    }
+}
+
+pub fn setup_notification_actions(application: &libadwaita::Application, web_context: &WebContext) {
+    let action_open_notification = gio::ActionEntry::builder(OPEN_NOTIFICATION_ACTION)
+        .parameter_type(Some(glib::VariantTy::STRING))
+        .activate(clone!(
+            #[weak]
+            web_context,
+            move |application: &libadwaita::Application, _, parameter| {
+                let Some(id) = parameter.and_then(|x| x.get::<String>()) else {
+                    return;
+                };
+                let notification = match DATABASE.mark_notification_read(id) {
+                    Ok(notification) => notification,
+                    Err(e) => {
+                        error!("{}", e);
+                        None
+                    }
+                };
+                let Some(url) = notification.and_then(|x| x.url) else {
+                    return;
+                };
+                match application
+                    .windows()
+                    .last()
+                    .and_then(|x| x.clone().downcast::<Window>().ok())
+                {
+                    Some(window) => {
+                        let web_view = window
+                            .new_tab(&Some(&NewTabArguments::Web(&NewWebTabArguments {
+                                web_context: &web_context,
+                                related_view: None,
+                                initial_request: None,
+                            })))
+                            .as_web()
+                            .expect("New tab to be Web tab")
+                            .web_view
+                            .clone();
+                        web_view.load_uri(&url);
+                        window.present();
+                    }
+                    None => application.open(&[gio::File::for_uri(&url)], "false,false"),
+                }
+            }
+        ))
+        .build();
+    application.add_action_entries([action_open_notification]);
+}
@@ -0,0 +1,41 @@
+use crate::database::{SiteExport, DATABASE};
+use crate::vox_providers::okunet_provider::core::OkuNetProvider;
+use crate::NODE;
+use log::{error, info};
+
+pub async fn export_site(site_export: &SiteExport) -> miette::Result<usize> {
+    let changed =
+        OkuNetProvider::export_site(&site_export.namespace_id()?, &site_export.author_id).await?;
+    info!(
+        "Exported website into {} ({} files changed) … ",
+        site_export.replica_id, changed
+    );
+    Ok(changed)
+}
+
+/// Rebuild exported websites whenever posts or users change
+pub async fn watch_site_exports() {
+    if let Some(node) = NODE.get() {
+        let mut post_rx = node.okunet_post_sender.subscribe();
+        let mut user_rx = node.okunet_user_sender.subscribe();
+        loop {
+            post_rx.borrow_and_update();
+            user_rx.borrow_and_update();
+            for site_export in DATABASE.get_site_exports().unwrap_or_default() {
+                if let Err(e) = export_site(&site_export).await {
+                    error!("{}: {}", site_export.replica_id, e);
+                }
+            }
+            let changed = tokio::select! {
+                changed = post_rx.changed() => changed,
+                changed = user_rx.changed() => changed,
+            };
+            if let Err(e) = changed {
+                error!("{}", e);
+                break;
+            }
+            // Posts and users tend to change in bursts while fetching from the OkuNet
+            glib::timeout_future_seconds(5).await;
+        }
+    }
+}
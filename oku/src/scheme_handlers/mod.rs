@@ -5,3 +5,5 @@ pub mod oku;
 pub mod oku_path;
 pub mod util;
 pub mod view_source;
+
+mod oku_path_test;
@@ -1,5 +1,9 @@
-use super::{oku_path::OkuPath, util::SchemeRequest};
+use super::{
+    oku_path::{OkuPath, SyndicatedFeed},
+    util::SchemeRequest,
+};
 use crate::{
+    database::DATABASE,
     vox_providers::{oku_provider::core::OkuProvider, okunet_provider::core::OkuNetProvider},
     window_util::get_window_from_widget,
     HOME_REPLICA_SET, NODE,
@@ -11,9 +15,21 @@ use libadwaita::{
     ResponseAppearance,
 };
 use log::error;
-use oku_core::iroh_docs::AuthorId;
+use oku_core::{
+    database::{
+        mutes::OkuMuteRule,
+        posts::{
+            core::OkuTimelineCursor,
+            syndication::{OkuFeedFormat, OkuFeedSource},
+        },
+    },
+    fs::util::encode_search_value,
+    iroh_docs::AuthorId,
+};
 use std::{path::PathBuf, sync::atomic::Ordering};
-use webkit2gtk::{functions::uri_for_display, prelude::WebViewExt};
+use webkit2gtk::prelude::WebViewExt;
+
+const SYNDICATION_LIMIT: usize = 50;
 
 pub async fn oku_scheme(request: SchemeRequest) {
     let bytes_result: miette::Result<Bytes> =
@@ -29,13 +45,7 @@ pub async fn post_oku_scheme_handler(request: SchemeRequest) -> miette::Result<B
         "Could read request URI ({:?}) … ",
         request.uri()
     ))?;
-    let decoded_url = uri_for_display(&request_uri)
-        .ok_or(miette::miette!(
-            "Could display request URI safely ({}) … ",
-            request_uri
-        ))?
-        .replacen("oku:", "", 1);
-    let url_path = OkuPath::parse(decoded_url)?;
+    let url_path = OkuPath::parse_uri(&request_uri)?;
     match url_path {
         OkuPath::ToggleFollow(author_id) => match toggle_follow(author_id).await {
             Ok(_) => Ok("Ok".into()),
@@ -52,6 +62,14 @@ pub async fn post_oku_scheme_handler(request: SchemeRequest) -> miette::Result<B
             Ok(_) => Ok("Ok".into()),
             Err(e) => Err(miette::miette!("{}", e)),
         },
+        OkuPath::MuteInFeed(name, rule) => match set_feed_mute(name, rule, true) {
+            Ok(_) => Ok("Ok".into()),
+            Err(e) => Err(e),
+        },
+        OkuPath::UnmuteInFeed(name, rule) => match set_feed_mute(name, rule, false) {
+            Ok(_) => Ok("Ok".into()),
+            Err(e) => Err(e),
+        },
         _ => Err(miette::miette!(
             "Operation {:?} not supported for POST requests to Oku scheme … ",
             url_path
@@ -136,21 +154,30 @@ pub async fn toggle_block(author_id: AuthorId) -> miette::Result<()> {
     Ok(())
 }
 
+pub fn set_feed_mute(name: String, rule: OkuMuteRule, muted: bool) -> miette::Result<()> {
+    let mut feed = DATABASE
+        .get_feed(name.clone())?
+        .ok_or(miette::miette!("No feed named {:?} … ", name))?;
+    feed.mutes.retain(|x| *x != rule);
+    if muted {
+        feed.mutes.push(rule);
+    }
+    DATABASE.upsert_feed(feed)?;
+    Ok(())
+}
+
 pub async fn get_oku_scheme_handler(request: SchemeRequest) -> miette::Result<Bytes> {
     let request_uri = request.uri().ok_or(miette::miette!(
         "Could read request URI ({:?}) … ",
         request.uri()
     ))?;
-    let decoded_url = uri_for_display(&request_uri)
-        .ok_or(miette::miette!(
-            "Could display request URI safely ({}) … ",
-            request_uri
-        ))?
-        .replacen("oku:", "", 1);
-    let url_path = OkuPath::parse(decoded_url)?;
+    let url_path = OkuPath::parse_uri(&request_uri)?;
     match url_path {
-        OkuPath::Home => home().await,
-        OkuPath::Tags => OkuNetProvider::new().view_tags().await.map(|x| x.into()),
+        OkuPath::Home(before) => home(before).await,
+        OkuPath::Tags(window, sort) => OkuNetProvider::new()
+            .view_tags(window, sort)
+            .await
+            .map(|x| x.into()),
         OkuPath::Tag(tag) => OkuNetProvider::new().view_tag(tag).await.map(|x| x.into()),
         OkuPath::Me(replica_path) => match replica_path {
             Some(replica_path) => OkuNetProvider::new()
@@ -169,7 +196,12 @@ pub async fn get_oku_scheme_handler(request: SchemeRequest) -> miette::Result<By
                 .await
                 .map(|x| x.into()),
         },
-        OkuPath::Search(query) => OkuNetProvider::new().search(query).await.map(|x| x.into()),
+        OkuPath::Search(search) => OkuNetProvider::new().search(search).await.map(|x| x.into()),
+        OkuPath::Feed(name, page) => OkuNetProvider::new()
+            .view_feed(name, page)
+            .await
+            .map(|x| x.into()),
+        OkuPath::Syndicate(format, feed) => syndicate(format, feed).await,
         _ => Err(miette::miette!(
             "Operation {:?} not supported for GET requests to Oku scheme … ",
             url_path
@@ -177,11 +209,39 @@ pub async fn get_oku_scheme_handler(request: SchemeRequest) -> miette::Result<By
     }
 }
 
-pub async fn home() -> miette::Result<Bytes> {
+pub async fn syndicate(format: OkuFeedFormat, feed: SyndicatedFeed) -> miette::Result<Bytes> {
+    let node = NODE
+        .get()
+        .ok_or(miette::miette!("No running Oku node … "))?;
+    let (source, saved_feed) = match feed {
+        SyndicatedFeed::Me => (OkuFeedSource::User(node.default_author().await), None),
+        SyndicatedFeed::Source(source) => (source, None),
+        SyndicatedFeed::Saved(name) => {
+            let saved_feed = DATABASE
+                .get_feed(name.clone())?
+                .ok_or(miette::miette!("No feed named {:?} … ", name))?;
+            (OkuFeedSource::Search(saved_feed.search()), Some(saved_feed))
+        }
+    };
+    let mut syndication_feed = node.syndicate(&source, SYNDICATION_LIMIT).await?;
+    if let Some(saved_feed) = saved_feed {
+        syndication_feed.title = saved_feed.name.clone();
+        syndication_feed.id = format!(
+            "oku:feed.atom?feed={}",
+            encode_search_value(&saved_feed.name)
+        );
+    }
+    syndication_feed.render(format).map(|x| x.into())
+}
+
+pub async fn home(before: Option<OkuTimelineCursor>) -> miette::Result<Bytes> {
     match HOME_REPLICA_SET.load(Ordering::Relaxed) {
         false => OkuProvider::new()
             .render_and_get("output/home.html")
             .map(|x| x.into()),
-        true => OkuNetProvider::new().view_home().await.map(|x| x.into()),
+        true => OkuNetProvider::new()
+            .view_home(before)
+            .await
+            .map(|x| x.into()),
     }
 }
@@ -1,21 +1,79 @@
 use miette::IntoDiagnostic;
+use oku_core::database::mutes::OkuMuteRule;
+use oku_core::database::posts::core::OkuTimelineCursor;
+use oku_core::database::posts::search::OkuPostSearch;
+use oku_core::database::posts::statistics::OkuTimeWindow;
+use oku_core::database::posts::syndication::{OkuFeedFormat, OkuFeedSource};
+use oku_core::fs::util::{decode_search_value, encode_search_value, parse_date};
 use oku_core::iroh_docs::AuthorId;
 use std::path::PathBuf;
+use webkit2gtk::functions::uri_for_display;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum TagSort {
+    #[default]
+    Count,
+    Name,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum SyndicatedFeed {
+    Me,
+    Source(OkuFeedSource),
+    Saved(String),
+}
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum OkuPath {
-    Home,
+    Home(Option<OkuTimelineCursor>),
     Me(Option<PathBuf>),
     Tag(String),
-    Tags,
+    Tags(OkuTimeWindow, TagSort),
     User(AuthorId, Option<PathBuf>),
     ToggleFollow(AuthorId),
     ToggleBlock(AuthorId),
     Delete(PathBuf),
-    Search(String),
+    Search(OkuPostSearch),
+    Feed(String, usize),
+    MuteInFeed(String, OkuMuteRule),
+    UnmuteInFeed(String, OkuMuteRule),
+    Syndicate(OkuFeedFormat, SyndicatedFeed),
 }
 
 impl OkuPath {
+    pub fn parse_uri(uri: &str) -> miette::Result<Self> {
+        // Search and feed values are decoded individually, as they may contain separators once decoded
+        if let Some(search) = uri
+            .replacen("oku:", "", 1)
+            .strip_prefix("search")
+            .filter(|x| x.is_empty() || x.starts_with(['/', '?']))
+        {
+            return Ok(OkuPath::Search(Self::parse_search(
+                search.trim_start_matches('/'),
+            )?));
+        }
+        for format in [OkuFeedFormat::Atom, OkuFeedFormat::Json] {
+            if let Some(parameters) = uri
+                .replacen("oku:", "", 1)
+                .strip_prefix(&format!("feed.{}", format.extension()))
+                .filter(|x| x.is_empty() || x.starts_with('?'))
+            {
+                return Ok(OkuPath::Syndicate(
+                    format,
+                    Self::parse_syndicated_feed(parameters.trim_start_matches('?'))?,
+                ));
+            }
+        }
+        Self::parse(
+            uri_for_display(uri)
+                .ok_or(miette::miette!(
+                    "Could display request URI safely ({}) … ",
+                    uri
+                ))?
+                .replacen("oku:", "", 1),
+        )
+    }
+
     pub fn parse(path: impl AsRef<std::path::Path>) -> miette::Result<Self> {
         let url_components: Vec<_> = path
             .as_ref()
@@ -26,64 +84,217 @@ impl OkuPath {
             .first()
             .map(|x| x.to_path_buf())
             .unwrap_or(PathBuf::from("home"));
+        let first_component_string = first_component.as_os_str().to_string_lossy().to_string();
+        let (first_component_name, query) = first_component_string
+            .split_once('?')
+            .unwrap_or((&first_component_string, ""));
         let second_component = url_components.get(1);
         let replica_path = second_component
             .and_then(|_x| path.as_ref().strip_prefix(first_component.clone()).ok())
             .map(|x| x.to_path_buf());
-        Ok(
-            match first_component
-                .as_os_str()
-                .to_string_lossy()
-                .to_string()
-                .as_str()
-            {
-                "home" => OkuPath::Home,
-                "tags" => OkuPath::Tags,
-                "tag" => second_component
-                    .map(|x| OkuPath::Tag(x.to_string_lossy().to_string()))
-                    .unwrap_or(OkuPath::Tags),
-                "me" => OkuPath::Me(replica_path),
-                "follow" => OkuPath::ToggleFollow(AuthorId::from(
-                    oku_core::fs::util::parse_array_hex_or_base32::<32>(
-                        second_component
-                            .ok_or(miette::miette!("Missing author ID … "))?
-                            .as_os_str()
-                            .to_string_lossy()
-                            .to_string()
-                            .as_str(),
-                    )?,
-                )),
-                "block" => OkuPath::ToggleBlock(AuthorId::from(
-                    oku_core::fs::util::parse_array_hex_or_base32::<32>(
-                        second_component
-                            .ok_or(miette::miette!("Missing author ID … "))?
-                            .as_os_str()
-                            .to_string_lossy()
-                            .to_string()
-                            .as_str(),
-                    )?,
-                )),
-                "delete" => {
-                    OkuPath::Delete(replica_path.ok_or(miette::miette!("Missing post path … "))?)
-                }
-                "search" => OkuPath::Search(
-                    path.as_ref()
-                        .strip_prefix("search/")
-                        .into_diagnostic()?
+        Ok(match first_component_name {
+            "home" => OkuPath::Home(
+                query
+                    .split('&')
+                    .find_map(|x| x.strip_prefix("before="))
+                    .and_then(|x| x.parse().ok()),
+            ),
+            "tags" => {
+                let parameters: Vec<_> =
+                    query.split('&').filter_map(|x| x.split_once('=')).collect();
+                OkuPath::Tags(
+                    parameters
+                        .iter()
+                        .find(|x| x.0 == "since")
+                        .and_then(|x| x.1.parse().ok())
+                        .unwrap_or(OkuTimeWindow::All),
+                    match parameters.iter().find(|x| x.0 == "sort") {
+                        Some((_, "name")) => TagSort::Name,
+                        _ => TagSort::Count,
+                    },
+                )
+            }
+            "tag" => second_component
+                .map(|x| OkuPath::Tag(x.to_string_lossy().to_string()))
+                .unwrap_or(OkuPath::Tags(OkuTimeWindow::All, TagSort::default())),
+            "me" => OkuPath::Me(replica_path),
+            "follow" => OkuPath::ToggleFollow(AuthorId::from(
+                oku_core::fs::util::parse_array_hex_or_base32::<32>(
+                    second_component
+                        .ok_or(miette::miette!("Missing author ID … "))?
+                        .as_os_str()
                         .to_string_lossy()
-                        .to_string(),
-                ),
-                _ => OkuPath::User(
-                    AuthorId::from(oku_core::fs::util::parse_array_hex_or_base32::<32>(
-                        first_component
-                            .as_os_str()
-                            .to_string_lossy()
-                            .to_string()
-                            .as_str(),
-                    )?),
-                    replica_path,
-                ),
-            },
-        )
+                        .to_string()
+                        .as_str(),
+                )?,
+            )),
+            "block" => OkuPath::ToggleBlock(AuthorId::from(
+                oku_core::fs::util::parse_array_hex_or_base32::<32>(
+                    second_component
+                        .ok_or(miette::miette!("Missing author ID … "))?
+                        .as_os_str()
+                        .to_string_lossy()
+                        .to_string()
+                        .as_str(),
+                )?,
+            )),
+            "delete" => {
+                OkuPath::Delete(replica_path.ok_or(miette::miette!("Missing post path … "))?)
+            }
+            "search" => OkuPath::Search(Self::parse_search(
+                &path
+                    .as_ref()
+                    .to_string_lossy()
+                    .trim_start_matches("search")
+                    .trim_start_matches('/'),
+            )?),
+            "feed.atom" => {
+                OkuPath::Syndicate(OkuFeedFormat::Atom, Self::parse_syndicated_feed(query)?)
+            }
+            "feed.json" => {
+                OkuPath::Syndicate(OkuFeedFormat::Json, Self::parse_syndicated_feed(query)?)
+            }
+            "feed" => Self::parse_feed(
+                &path
+                    .as_ref()
+                    .to_string_lossy()
+                    .trim_start_matches("feed")
+                    .trim_start_matches('/'),
+            )?,
+            _ => OkuPath::User(
+                AuthorId::from(oku_core::fs::util::parse_array_hex_or_base32::<32>(
+                    first_component
+                        .as_os_str()
+                        .to_string_lossy()
+                        .to_string()
+                        .as_str(),
+                )?),
+                replica_path,
+            ),
+        })
+    }
+
+    fn parse_search(path: &str) -> miette::Result<OkuPostSearch> {
+        let (query, parameters) = path.split_once('?').unwrap_or((path, ""));
+        let mut search = OkuPostSearch {
+            query: decode_search_value(query),
+            ..Default::default()
+        };
+        let mut page = 1;
+        for (key, value) in parameters.split('&').filter_map(|x| x.split_once('=')) {
+            let value = decode_search_value(value);
+            match key {
+                "tag" => {
+                    search.tags.insert(value);
+                }
+                "author" => {
+                    search.authors.insert(AuthorId::from(
+                        oku_core::fs::util::parse_array_hex_or_base32::<32>(&value)?,
+                    ));
+                }
+                "domain" => search.domain = Some(value).filter(|x| !x.is_empty()),
+                "after" => search.after = parse_date(&value).ok(),
+                "before" => search.before = parse_date(&value).ok(),
+                "sort" => search.newest_first = value == "newest",
+                "page" => page = value.parse::<usize>().into_diagnostic()?.max(1),
+                _ => (),
+            }
+        }
+        search.offset = (page - 1) * search.limit;
+        Ok(search)
+    }
+
+    fn parse_feed(path: &str) -> miette::Result<Self> {
+        let (name, parameters) = path.split_once('?').unwrap_or((path, ""));
+        if name.is_empty() {
+            return Err(miette::miette!("Missing feed name … "));
+        }
+        // Mute rules may contain '&', so they are the only parameter when given
+        if let Some(rule) = parameters.strip_prefix("mute=") {
+            return Ok(OkuPath::MuteInFeed(name.to_string(), rule.parse()?));
+        }
+        if let Some(rule) = parameters.strip_prefix("unmute=") {
+            return Ok(OkuPath::UnmuteInFeed(name.to_string(), rule.parse()?));
+        }
+        let page = match parameters.split('&').find_map(|x| x.strip_prefix("page=")) {
+            Some(page) => page.parse::<usize>().into_diagnostic()?.max(1),
+            None => 1,
+        };
+        Ok(OkuPath::Feed(name.to_string(), page))
+    }
+
+    fn parse_syndicated_feed(parameters: &str) -> miette::Result<SyndicatedFeed> {
+        let mut search = OkuPostSearch::default();
+        for (key, value) in parameters.split('&').filter_map(|x| x.split_once('=')) {
+            let value = decode_search_value(value);
+            match key {
+                "feed" => return Ok(SyndicatedFeed::Saved(value)),
+                "query" => search.query = value,
+                "tag" => {
+                    search.tags.insert(value);
+                }
+                "author" => {
+                    search.authors.insert(AuthorId::from(
+                        oku_core::fs::util::parse_array_hex_or_base32::<32>(&value)?,
+                    ));
+                }
+                "domain" => search.domain = Some(value).filter(|x| !x.is_empty()),
+                "after" => search.after = parse_date(&value).ok(),
+                "before" => search.before = parse_date(&value).ok(),
+                _ => (),
+            }
+        }
+        let tags: Vec<_> = search.tags.iter().cloned().collect();
+        let authors: Vec<_> = search.authors.iter().copied().collect();
+        let unfiltered = search.query.trim().is_empty()
+            && search.domain.is_none()
+            && search.after.is_none()
+            && search.before.is_none();
+        Ok(match (unfiltered, tags.as_slice(), authors.as_slice()) {
+            (true, [], []) => SyndicatedFeed::Me,
+            (true, [], [author_id]) => SyndicatedFeed::Source(OkuFeedSource::User(*author_id)),
+            (true, [tag], []) => SyndicatedFeed::Source(OkuFeedSource::Tag(tag.clone())),
+            _ => SyndicatedFeed::Source(OkuFeedSource::Search(search)),
+        })
+    }
+
+    pub fn feed_path(name: &str, page: usize) -> String {
+        match page > 1 {
+            true => format!("feed/{name}?page={page}"),
+            false => format!("feed/{name}"),
+        }
+    }
+
+    pub fn search_path(search: &OkuPostSearch, page: usize) -> String {
+        let mut parameters = Vec::new();
+        let mut tags: Vec<_> = search.tags.iter().collect();
+        tags.sort_unstable();
+        parameters.extend(
+            tags.into_iter()
+                .map(|x| format!("tag={}", encode_search_value(x))),
+        );
+        let mut authors: Vec<_> = search.authors.iter().map(oku_core::fs::util::fmt).collect();
+        authors.sort_unstable();
+        parameters.extend(authors.into_iter().map(|x| format!("author={x}")));
+        if let Some(domain) = &search.domain {
+            parameters.push(format!("domain={}", encode_search_value(domain)));
+        }
+        if let Some(after) = search.after {
+            parameters.push(format!("after={after}"));
+        }
+        if let Some(before) = search.before {
+            parameters.push(format!("before={before}"));
+        }
+        if search.newest_first {
+            parameters.push("sort=newest".to_string());
+        }
+        if page > 1 {
+            parameters.push(format!("page={page}"));
+        }
+        let query = encode_search_value(&search.query);
+        match parameters.is_empty() {
+            true => format!("search/{query}"),
+            false => format!("search/{query}?{}", parameters.join("&")),
+        }
     }
 }
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use crate::scheme_handlers::oku_path::{OkuPath, SyndicatedFeed};
+    use oku_core::database::posts::search::OkuPostSearch;
+    use oku_core::database::posts::syndication::{OkuFeedFormat, OkuFeedSource};
+    use oku_core::fs::util::parse_date;
+    use oku_core::iroh_docs::AuthorId;
+
+    fn parse_search(uri: &str) -> miette::Result<OkuPostSearch> {
+        match OkuPath::parse_uri(uri)? {
+            OkuPath::Search(search) => Ok(search),
+            path => Err(miette::miette!("Expected a search, found {:?}", path)),
+        }
+    }
+
+    #[test]
+    fn test_parse_search() -> miette::Result<()> {
+        let search = parse_search(
+            "oku:search/peer%20to%20peer?tag=rust&tag=p2p&domain=example.com&after=2024-01-02&sort=newest&page=3",
+        )?;
+        assert_eq!(search.query, "peer to peer");
+        assert_eq!(
+            search.tags,
+            ["rust".to_string(), "p2p".to_string()]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(search.domain, Some("example.com".to_string()));
+        assert_eq!(search.after, Some(parse_date("2024-01-02")?));
+        assert_eq!(search.before, None);
+        assert!(search.newest_first);
+        assert_eq!(search.offset, 2 * search.limit);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_empty_search() -> miette::Result<()> {
+        assert_eq!(parse_search("oku:search")?, OkuPostSearch::default());
+        assert_eq!(parse_search("oku:search/")?, OkuPostSearch::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_search_invalid() {
+        assert!(parse_search("oku:search/rust?page=first").is_err());
+        assert!(parse_search("oku:search/rust?author=nobody").is_err());
+    }
+
+    #[test]
+    fn test_search_path_round_trip() -> miette::Result<()> {
+        let search = OkuPostSearch {
+            query: "tags:\"a&b\" 50% / c++?".to_string(),
+            authors: [AuthorId::from([7; 32])].into_iter().collect(),
+            tags: [
+                "a&b".to_string(),
+                "#hash".to_string(),
+                "Ünïcode".to_string(),
+            ]
+            .into_iter()
+            .collect(),
+            domain: Some("example.com".to_string()),
+            after: Some(1_700_000_000_000_000),
+            before: Some(1_800_000_000_000_000),
+            newest_first: true,
+            offset: 4 * OkuPostSearch::default().limit,
+            ..Default::default()
+        };
+        let path = OkuPath::search_path(&search, 5);
+        assert_eq!(parse_search(&format!("oku:{path}"))?, search);
+        Ok(())
+    }
+
+    fn parse_syndicated_feed(uri: &str) -> miette::Result<SyndicatedFeed> {
+        match OkuPath::parse_uri(uri)? {
+            OkuPath::Syndicate(_, feed) => Ok(feed),
+            path => Err(miette::miette!("Expected a feed, found {:?}", path)),
+        }
+    }
+
+    #[test]
+    fn test_syndicated_feed_round_trip() -> miette::Result<()> {
+        let search = OkuPostSearch {
+            query: "tags:\"a&b\" 50% / c++?".to_string(),
+            authors: [AuthorId::from([7; 32]), AuthorId::from([8; 32])]
+                .into_iter()
+                .collect(),
+            tags: ["a&b".to_string(), "#hash".to_string()]
+                .into_iter()
+                .collect(),
+            domain: Some("example.com".to_string()),
+            after: Some(1_700_000_000_000_000),
+            before: Some(1_800_000_000_000_000),
+            ..Default::default()
+        };
+        for source in [
+            OkuFeedSource::User(AuthorId::from([7; 32])),
+            OkuFeedSource::Tag("a&b=c d".to_string()),
+            OkuFeedSource::Search(search),
+            // Filters alone make a search, rather than a feed of one's own posts
+            OkuFeedSource::Search(OkuPostSearch {
+                domain: Some("example.com".to_string()),
+                ..Default::default()
+            }),
+        ] {
+            for format in [OkuFeedFormat::Atom, OkuFeedFormat::Json] {
+                assert_eq!(
+                    SyndicatedFeed::Source(source.clone()),
+                    parse_syndicated_feed(&source.uri(format))?
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_syndicated_feed() -> miette::Result<()> {
+        assert_eq!(SyndicatedFeed::Me, parse_syndicated_feed("oku:feed.atom")?);
+        assert_eq!(
+            SyndicatedFeed::Saved("my feed".to_string()),
+            parse_syndicated_feed("oku:feed.json?feed=my%20feed")?
+        );
+        assert_eq!(
+            SyndicatedFeed::Source(OkuFeedSource::Tag("a&b".to_string())),
+            parse_syndicated_feed("oku:feed.atom?tag=a%26b")?
+        );
+        Ok(())
+    }
+}
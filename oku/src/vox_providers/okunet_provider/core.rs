@@ -47,14 +47,30 @@ pub static OKUNET_VOX_FILES: LazyLock<HashMap<PathBuf, String>> = LazyLock::new(
             "snippets/tag.voxs".into(),
             include_str!("../../okunet_pages/snippets/tag.voxs").into(),
         ),
+        (
+            "snippets/activity.voxs".into(),
+            include_str!("../../okunet_pages/snippets/activity.voxs").into(),
+        ),
         (
             "snippets/tags.voxs".into(),
             include_str!("../../okunet_pages/snippets/tags.voxs").into(),
         ),
+        (
+            "snippets/timeline.voxs".into(),
+            include_str!("../../okunet_pages/snippets/timeline.voxs").into(),
+        ),
         (
             "snippets/search.voxs".into(),
             include_str!("../../okunet_pages/snippets/search.voxs").into(),
         ),
+        (
+            "snippets/search_result.voxs".into(),
+            include_str!("../../okunet_pages/snippets/search_result.voxs").into(),
+        ),
+        (
+            "snippets/feed.voxs".into(),
+            include_str!("../../okunet_pages/snippets/feed.voxs").into(),
+        ),
         (
             "snippets/follow_button.html".into(),
             include_str!("../../okunet_pages/snippets/follow_button.html").into(),
@@ -83,14 +99,6 @@ pub static OKUNET_VOX_FILES: LazyLock<HashMap<PathBuf, String>> = LazyLock::new(
             "snippets/user-trash-symbolic.svg".into(),
             include_str!("../../../data/hicolor/scalable/actions/user-trash-symbolic.svg").into(),
         ),
-        (
-            "tags.vox".into(),
-            include_str!("../../okunet_pages/tags.vox").into(),
-        ),
-        (
-            "home.vox".into(),
-            include_str!("../../okunet_pages/home.vox").into(),
-        ),
     ])
 });
 
@@ -107,7 +115,7 @@ impl OkuNetProvider {
     pub fn new() -> Self {
         Self(RamProvider::new(Some(OKUNET_VOX_FILES.clone())))
     }
-    pub fn render_and_get(&self, path: impl AsRef<std::path::Path>) -> miette::Result<String> {
+    pub fn render(&self) -> miette::Result<()> {
         let parser = self.0.create_liquid_parser()?;
         let global = self.0.get_global_context()?;
         let (dag, _pages, _layouts) = self.0.generate_dag()?;
@@ -119,6 +127,10 @@ impl OkuNetProvider {
             false,
             false,
         )?;
+        Ok(())
+    }
+    pub fn render_and_get(&self, path: impl AsRef<std::path::Path>) -> miette::Result<String> {
+        self.render()?;
         self.0.read_to_string(path)
     }
 }
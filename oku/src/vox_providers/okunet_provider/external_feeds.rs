@@ -0,0 +1,71 @@
+use super::core::OkuNetProvider;
+use miette::IntoDiagnostic;
+use oku_core::database::external_feeds::OkuExternalPost;
+use std::str::FromStr;
+use vox::provider::VoxProvider;
+
+impl OkuNetProvider {
+    pub fn get_external_post_id(&self, post: &OkuExternalPost) -> String {
+        bs58::encode(format!("{}\n{}", post.feed_source, post.item_id)).into_string()
+    }
+
+    pub fn get_external_post_frontmatter(
+        &self,
+        post: &OkuExternalPost,
+    ) -> miette::Result<toml::Table> {
+        let post_date = toml::value::Datetime::from_str(
+            &chrono::DateTime::from_timestamp_micros(post.published.try_into().unwrap_or(0))
+                .map(|x| x.to_rfc3339())
+                .unwrap_or_default(),
+        )
+        .into_diagnostic()?;
+        let mut author_table = toml::Table::new();
+        author_table.insert("name".into(), post.feed_title.clone().into());
+        let mut table = toml::Table::new();
+        table.insert("layout".into(), "post".into());
+        table.insert(
+            "permalink".into(),
+            format!("external/{}", self.get_external_post_id(post)).into(),
+        );
+        table.insert("date".into(), post_date.into());
+        table.insert("note_url".into(), post.url.to_string().into());
+        table.insert("title".into(), post.title.clone().into());
+        table.insert(
+            "tags".into(),
+            post.tags.clone().into_iter().collect::<Vec<_>>().into(),
+        );
+        table.insert("author_id".into(), "".into());
+        table.insert("author".into(), author_table.into());
+        table.insert("external".into(), true.into());
+        table.insert("source".into(), post.feed_source.clone().into());
+        table.insert("by_me".into(), false.into());
+        table.insert("author_impersonates".into(), "".into());
+        table.insert("sensitive".into(), false.into());
+        table.insert("collapsed".into(), false.into());
+        table.insert("hidden".into(), false.into());
+        table.insert("edited".into(), false.into());
+        table.insert("revisions".into(), Vec::<toml::Value>::new().into());
+        Ok(table)
+    }
+
+    pub fn create_external_post_page(
+        &self,
+        post: &OkuExternalPost,
+        collection: String,
+    ) -> miette::Result<()> {
+        let page_path = format!("{}/{}.vox", collection, self.get_external_post_id(post));
+        let table = self.get_external_post_frontmatter(post)?;
+        let page_contents = format!(
+            "---
+{}
+---
+{{% raw %}}
+<p>{}</p>
+{{% endraw %}}",
+            table,
+            html_escape::encode_text(&post.body)
+        );
+        self.0.write_file(page_path, page_contents)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use super::core::OkuNetProvider;
+use crate::database::{Feed, DATABASE as BROWSER_DATABASE};
+use crate::scheme_handlers::oku_path::OkuPath;
+use crate::NODE;
+use oku_core::{
+    config::OkuFsConfig,
+    database::{core::DATABASE, posts::search::OkuPostSearchResults},
+    fs::util::encode_search_value,
+};
+use vox::provider::VoxProvider;
+
+impl OkuNetProvider {
+    pub async fn get_feed_frontmatter(
+        &self,
+        feed: &Feed,
+        page: usize,
+        results: &OkuPostSearchResults,
+        new_count: usize,
+    ) -> miette::Result<toml::Table> {
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("No running Oku node … "))?;
+        let search = feed.search();
+        let mut table = toml::Table::new();
+        table.insert("layout".into(), "default".into());
+        table.insert("permalink".into(), "feed".into());
+        table.insert("title".into(), feed.name.clone().into());
+        table.insert("name".into(), feed.name.clone().into());
+        table.insert("description".into(), feed.description().into());
+        table.insert("search_url".into(), OkuPath::search_path(&search, 1).into());
+        table.insert(
+            "atom_url".into(),
+            format!("feed.atom?feed={}", encode_search_value(&feed.name)).into(),
+        );
+        table.insert(
+            "json_url".into(),
+            format!("feed.json?feed={}", encode_search_value(&feed.name)).into(),
+        );
+        let mut authors = Vec::new();
+        for author_id in feed.authors.iter() {
+            let name = match node.get_or_fetch_user(author_id).await {
+                Ok(user) => DATABASE.display_name(&user),
+                Err(_) => DATABASE
+                    .get_petname(author_id)
+                    .unwrap_or(oku_core::fs::util::fmt_short(author_id)),
+            };
+            let mut author_table = toml::Table::new();
+            author_table.insert("id".into(), oku_core::fs::util::fmt(author_id).into());
+            author_table.insert("name".into(), name.into());
+            authors.push(author_table);
+        }
+        table.insert("authors".into(), authors.into());
+        let mutes: Vec<_> = feed.mutes.iter().map(|x| x.to_string()).collect();
+        table.insert("mutes".into(), mutes.into());
+
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        let mut result_tables = Vec::new();
+        for result in results.results.iter() {
+            if let Some(mut result_table) = self
+                .get_search_result_frontmatter(result, content_warning_behaviour)
+                .await?
+            {
+                result_table.insert(
+                    "new".into(),
+                    feed.last_viewed
+                        .is_none_or(|x| result.post.entry.timestamp() > x)
+                        .into(),
+                );
+                result_tables.push(result_table);
+            }
+        }
+        table.insert("results".into(), result_tables.into());
+        table.insert("total".into(), (results.total as i64).into());
+        table.insert("new_count".into(), (new_count as i64).into());
+        table.insert(
+            "previous".into(),
+            match page > 1 {
+                true => OkuPath::feed_path(&feed.name, page - 1),
+                false => String::new(),
+            }
+            .into(),
+        );
+        table.insert(
+            "next".into(),
+            match page * search.limit < results.total {
+                true => OkuPath::feed_path(&feed.name, page + 1),
+                false => String::new(),
+            }
+            .into(),
+        );
+        Ok(table)
+    }
+
+    pub async fn view_feed(&self, name: String, page: usize) -> miette::Result<String> {
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("No running Oku node … "))?;
+        let feed = BROWSER_DATABASE
+            .get_feed(name.clone())?
+            .ok_or(miette::miette!("No feed named {:?} … ", name))?;
+        let mut search = feed.search();
+        search.offset = (page.max(1) - 1) * search.limit;
+        let results = node.search_posts(&search).await?;
+        let new_count = node.count_posts(&feed.unread_search()).await?;
+        let table = self
+            .get_feed_frontmatter(&feed, page.max(1), &results, new_count)
+            .await?;
+        let page_contents = format!(
+            "---
+{}
+---
+{{% include feed.voxs %}}
+",
+            table
+        );
+        self.0.write_file("feed.vox", page_contents)?;
+        let rendered_page = self.render_and_get("output/feed")?;
+        BROWSER_DATABASE.mark_feed_viewed(name)?;
+        Ok(rendered_page)
+    }
+}
@@ -1,24 +1,97 @@
 use super::core::OkuNetProvider;
 use crate::NODE;
-use rayon::prelude::*;
-use rayon::slice::ParallelSliceMut;
-use std::cmp::Reverse;
+use oku_core::config::OkuFsConfig;
+use oku_core::database::external_feeds::OkuTimelineItem;
+use oku_core::database::posts::core::OkuTimelineCursor;
+use oku_core::fs::net::core::DEFAULT_TIMELINE_PAGE_SIZE;
+use vox::provider::VoxProvider;
 
 impl OkuNetProvider {
-    pub async fn view_home(&self) -> miette::Result<String> {
+    pub fn get_home_frontmatter(
+        &self,
+        before: &Option<OkuTimelineCursor>,
+        next: &Option<OkuTimelineCursor>,
+    ) -> miette::Result<toml::Table> {
+        let mut table = toml::Table::new();
+        table.insert("layout".into(), "default".into());
+        table.insert("title".into(), "OkuNet".into());
+        table.insert("permalink".into(), "home".into());
+        table.insert("depends".into(), vec!["posts"].into());
+        table.insert(
+            "before".into(),
+            before
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or_default()
+                .into(),
+        );
+        table.insert(
+            "next".into(),
+            next.as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or_default()
+                .into(),
+        );
+        Ok(table)
+    }
+
+    pub fn create_home_page(
+        &self,
+        before: &Option<OkuTimelineCursor>,
+        next: &Option<OkuTimelineCursor>,
+    ) -> miette::Result<()> {
+        let table = self.get_home_frontmatter(before, next)?;
+        let page_contents = format!(
+            "---
+{}
+---
+{{% if posts[0] %}}
+{{% include timeline.voxs posts = posts %}}
+{{% else %}}
+{{% include timeline.voxs posts = \"\" %}}
+{{% endif %}}
+",
+            table
+        );
+        self.0.write_file("home.vox", page_contents)?;
+        Ok(())
+    }
+
+    pub async fn view_home(&self, before: Option<OkuTimelineCursor>) -> miette::Result<String> {
         let node = NODE
             .get()
             .ok_or(miette::miette!("No running Oku node … "))?;
 
         tokio::spawn(node.refresh_users());
 
-        // Posts
-        let mut posts = Vec::from_par_iter(node.all_posts().await);
-        posts.par_sort_unstable_by_key(|x| Reverse(x.entry.timestamp()));
-        for post in posts.iter() {
-            self.create_post_page(&post.user(), post, Some("posts".into()))
-                .await?;
+        // Only one page of posts is rendered at a time
+        let items = node
+            .home_timeline(&before, DEFAULT_TIMELINE_PAGE_SIZE)
+            .await?;
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        for item in items.iter() {
+            match item {
+                OkuTimelineItem::Post(post) => {
+                    self.create_post_page(
+                        &post.user(),
+                        post,
+                        Some("posts".into()),
+                        content_warning_behaviour,
+                    )
+                    .await?
+                }
+                OkuTimelineItem::External(post) => {
+                    self.create_external_post_page(post, "posts".into())?
+                }
+            }
         }
+        let next = match items.len() == DEFAULT_TIMELINE_PAGE_SIZE {
+            true => items.last().map(|x| x.cursor()),
+            false => None,
+        };
+        self.create_home_page(&before, &next)?;
 
         self.render_and_get("output/home")
     }
@@ -1,6 +1,9 @@
 pub mod core;
+pub mod external_feeds;
+pub mod feed;
 pub mod home;
 pub mod posts;
 pub mod search;
+pub mod site;
 pub mod tags;
 pub mod users;
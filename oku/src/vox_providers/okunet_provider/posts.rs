@@ -2,7 +2,9 @@ use super::core::OkuNetProvider;
 use crate::NODE;
 use miette::IntoDiagnostic;
 use oku_core::{
+    config::{ContentWarningBehaviour, OkuFsConfig},
     database::{
+        core::DATABASE,
         posts::core::OkuPost,
         users::{OkuIdentity, OkuUser},
     },
@@ -49,6 +51,7 @@ impl OkuNetProvider {
         &self,
         user: &OkuUser,
         post: &OkuPost,
+        content_warning_behaviour: ContentWarningBehaviour,
     ) -> miette::Result<toml::Table> {
         let node = NODE
             .get()
@@ -62,14 +65,9 @@ impl OkuNetProvider {
             .unwrap_or_default(),
         )
         .into_diagnostic()?;
-        let author_identity = if let Some(identity) = user.identity.clone() {
-            identity
-        } else {
-            OkuIdentity {
-                name: oku_core::fs::util::fmt(user.author_id),
-                following: HashSet::new(),
-                blocked: HashSet::new(),
-            }
+        let author_identity = OkuIdentity {
+            name: DATABASE.display_name(user),
+            ..user.identity.clone().unwrap_or_default()
         };
         let mut table = toml::Table::new();
         table.insert("layout".into(), "post".into());
@@ -91,6 +89,45 @@ impl OkuNetProvider {
             oku_core::fs::util::fmt(user.author_id).into(),
         );
         table.insert("by_me".into(), node.is_me(&user.author_id).await.into());
+        table.insert(
+            "author_impersonates".into(),
+            DATABASE
+                .impersonated_contact(user)
+                .and_then(|x| x.petname)
+                .unwrap_or_default()
+                .into(),
+        );
+        let flagged = post.note.is_flagged();
+        if let Some(warning) = post.note.warning() {
+            table.insert("content_warning".into(), warning.into());
+        }
+        table.insert("sensitive".into(), post.note.sensitive.into());
+        table.insert(
+            "collapsed".into(),
+            (flagged && content_warning_behaviour != ContentWarningBehaviour::Expand).into(),
+        );
+        table.insert(
+            "hidden".into(),
+            (flagged && content_warning_behaviour == ContentWarningBehaviour::Hide).into(),
+        );
+        let mut revisions = Vec::new();
+        for revision in node.post_history(post).await.unwrap_or_default() {
+            let mut revision_table = toml::Table::new();
+            revision_table.insert("title".into(), revision.note.title.into());
+            revision_table.insert("body".into(), revision.note.body.into());
+            revision_table.insert(
+                "date".into(),
+                chrono::DateTime::from_timestamp_micros(
+                    revision.entry.timestamp().try_into().unwrap_or(0),
+                )
+                .map(|x| x.to_rfc2822())
+                .unwrap_or_default()
+                .into(),
+            );
+            revisions.push(toml::Value::Table(revision_table));
+        }
+        table.insert("edited".into(), (!revisions.is_empty()).into());
+        table.insert("revisions".into(), revisions.into());
         table.insert(
             "author".into(),
             toml::Table::try_from(author_identity)
@@ -104,9 +141,26 @@ impl OkuNetProvider {
         user: &OkuUser,
         post: &OkuPost,
         tag: Option<String>,
+        content_warning_behaviour: ContentWarningBehaviour,
     ) -> miette::Result<()> {
         let page_path = self.get_post_path(post, tag);
-        let table = self.get_post_frontmatter(user, post).await?;
+        let table = self
+            .get_post_frontmatter(user, post, content_warning_behaviour)
+            .await?;
+        let body = post.note.replace_mentions(|author_id| {
+            let author_id_string = oku_core::fs::util::fmt(author_id);
+            let name = DATABASE
+                .get_petname(author_id)
+                .or(DATABASE
+                    .get_user(author_id)
+                    .ok()
+                    .flatten()
+                    .and_then(|x| x.identity)
+                    .map(|x| x.name))
+                .unwrap_or(oku_core::fs::util::fmt_short(author_id))
+                .replace(['[', ']'], "");
+            format!("[@{name}](oku:{author_id_string})")
+        });
         let page_contents = format!(
             "---
 {}
@@ -116,7 +170,7 @@ impl OkuNetProvider {
 {}
 {{% endraw %}}
 {{% endmarkdown %}}",
-            table, post.note.body
+            table, body
         );
         self.0.write_file(page_path, page_contents)?;
         Ok(())
@@ -136,7 +190,11 @@ impl OkuNetProvider {
         let post = node
             .get_or_fetch_post(&author_id, &post_path.into())
             .await?;
-        self.create_post_page(&user, &post, None).await?;
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        self.create_post_page(&user, &post, None, content_warning_behaviour)
+            .await?;
         self.render_and_get(format!("output/{}", self.get_post_permalink(&post).await?))
     }
 
@@ -152,7 +210,11 @@ impl OkuNetProvider {
         );
         let post = node.post(&post_path.into()).await?;
         let me = node.user().await?;
-        self.create_post_page(&me, &post, None).await?;
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        self.create_post_page(&me, &post, None, content_warning_behaviour)
+            .await?;
         self.render_and_get(format!("output/{}", self.get_post_permalink(&post).await?))
     }
 }
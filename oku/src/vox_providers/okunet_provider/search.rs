@@ -1,40 +1,232 @@
 use super::core::OkuNetProvider;
-use oku_core::database::core::OkuDatabase;
+use crate::scheme_handlers::oku_path::OkuPath;
+use crate::NODE;
+use oku_core::{
+    config::{ContentWarningBehaviour, OkuFsConfig},
+    database::{
+        core::{OkuDatabase, DATABASE},
+        posts::search::{OkuPostSearch, OkuPostSearchResult, OkuPostSearchResults},
+    },
+};
 use vox::provider::VoxProvider;
 
 impl OkuNetProvider {
-    pub fn get_search_frontmatter(&self, query: &str) -> miette::Result<toml::Table> {
+    pub async fn get_search_result_frontmatter(
+        &self,
+        result: &OkuPostSearchResult,
+        content_warning_behaviour: ContentWarningBehaviour,
+    ) -> miette::Result<Option<toml::Table>> {
+        let post = &result.post;
+        let flagged = post.note.is_flagged();
+        if flagged && content_warning_behaviour == ContentWarningBehaviour::Hide {
+            return Ok(None);
+        }
+        let mut result_table = toml::Table::new();
+        result_table.insert("title".into(), post.note.title.clone().into());
+        result_table.insert("note_url".into(), post.note.url.to_string().into());
+        result_table.insert(
+            "permalink".into(),
+            self.get_post_permalink(post).await?.into(),
+        );
+        result_table.insert(
+            "author_id".into(),
+            oku_core::fs::util::fmt(post.entry.author()).into(),
+        );
+        result_table.insert(
+            "author_name".into(),
+            DATABASE.display_name(&post.user()).into(),
+        );
+        result_table.insert(
+            "date".into(),
+            chrono::DateTime::from_timestamp_micros(post.entry.timestamp().try_into().unwrap_or(0))
+                .map(|x| x.format("%a, %-d %b %Y").to_string())
+                .unwrap_or_default()
+                .into(),
+        );
+        let mut tags: Vec<_> = post.note.tags.iter().cloned().collect();
+        tags.sort_unstable();
+        result_table.insert("tags".into(), tags.into());
+        result_table.insert(
+            "content_warning".into(),
+            post.note.warning().unwrap_or_default().into(),
+        );
+        result_table.insert(
+            "collapsed".into(),
+            (flagged && content_warning_behaviour != ContentWarningBehaviour::Expand).into(),
+        );
+        result_table.insert("snippet".into(), result.snippet_html.clone().into());
+        Ok(Some(result_table))
+    }
+
+    pub async fn get_search_frontmatter(
+        &self,
+        search: &OkuPostSearch,
+        results: &OkuPostSearchResults,
+        content_warning_behaviour: ContentWarningBehaviour,
+    ) -> miette::Result<toml::Table> {
+        let page = search.offset / search.limit.max(1) + 1;
         let mut table = toml::Table::new();
         table.insert("layout".into(), "default".into());
-        table.insert("depends".into(), vec!["search"].into());
         table.insert("permalink".into(), "search".into());
-        table.insert("title".into(), query.into());
+        table.insert("title".into(), search.query.clone().into());
+        let users: Vec<_> = match search.query.trim().is_empty() {
+            true => Vec::new(),
+            false => OkuDatabase::search_users(&search.query, &None)?,
+        }
+        .into_iter()
+        .map(|user| {
+            let mut user_table = toml::Table::new();
+            user_table.insert("id".into(), oku_core::fs::util::fmt(user.author_id).into());
+            user_table.insert("name".into(), DATABASE.display_name(&user).into());
+            user_table.insert(
+                "bio".into(),
+                user.identity.and_then(|x| x.bio).unwrap_or_default().into(),
+            );
+            user_table
+        })
+        .collect();
+        table.insert("users".into(), users.into());
+
+        // Posts hidden by their content warnings are not found by the search, so that they are not counted
+        let mut result_tables = Vec::new();
+        for result in results.results.iter() {
+            if let Some(result_table) = self
+                .get_search_result_frontmatter(result, content_warning_behaviour)
+                .await?
+            {
+                result_tables.push(result_table);
+            }
+        }
+        table.insert("results".into(), result_tables.into());
+        table.insert("total".into(), (results.total as i64).into());
+
+        // Items of imported feeds are only matched by the query, and are shown on the first page
+        let external_posts = match search.query.trim().is_empty() || page > 1 {
+            true => Vec::new(),
+            false => DATABASE
+                .search_external_posts(&search.query, &Some(search.limit))
+                .unwrap_or_default(),
+        };
+        let external_tables: Vec<_> = external_posts
+            .into_iter()
+            .map(|post| {
+                let mut external_table = toml::Table::new();
+                external_table.insert("title".into(), post.title.into());
+                external_table.insert("url".into(), post.url.to_string().into());
+                external_table.insert("source".into(), post.feed_title.into());
+                external_table.insert(
+                    "date".into(),
+                    chrono::DateTime::from_timestamp_micros(post.published.try_into().unwrap_or(0))
+                        .map(|x| x.format("%a, %-d %b %Y").to_string())
+                        .unwrap_or_default()
+                        .into(),
+                );
+                external_table.insert("body".into(), post.body.into());
+                external_table
+            })
+            .collect();
+        table.insert("external".into(), external_tables.into());
+
+        // Choosing a facet adds it to the search's filters, and choosing it again removes it
+        let tag_facets: Vec<_> = results
+            .tag_counts
+            .iter()
+            .map(|(tag, count)| {
+                let mut facet_search = search.clone();
+                let active = !facet_search.tags.insert(tag.clone());
+                if active {
+                    facet_search.tags.remove(tag);
+                }
+                let mut facet_table = toml::Table::new();
+                facet_table.insert("name".into(), tag.clone().into());
+                facet_table.insert("count".into(), (*count as i64).into());
+                facet_table.insert("active".into(), active.into());
+                facet_table.insert("url".into(), OkuPath::search_path(&facet_search, 1).into());
+                facet_table
+            })
+            .collect();
+        table.insert("tag_facets".into(), tag_facets.into());
+        let mut author_facets = Vec::new();
+        for (author_id, count) in results.author_counts.iter() {
+            let mut facet_search = search.clone();
+            let active = !facet_search.authors.insert(*author_id);
+            if active {
+                facet_search.authors.remove(author_id);
+            }
+            // Only users already known are named, so that a page of results does not wait on the network
+            let name = match DATABASE.get_user(author_id).ok().flatten() {
+                Some(user) => DATABASE.display_name(&user),
+                None => DATABASE
+                    .get_petname(author_id)
+                    .unwrap_or(oku_core::fs::util::fmt_short(author_id)),
+            };
+            let mut facet_table = toml::Table::new();
+            facet_table.insert("name".into(), name.into());
+            facet_table.insert("count".into(), (*count as i64).into());
+            facet_table.insert("active".into(), active.into());
+            facet_table.insert("url".into(), OkuPath::search_path(&facet_search, 1).into());
+            author_facets.push(facet_table);
+        }
+        table.insert("author_facets".into(), author_facets.into());
+
+        let mut sort_search = search.clone();
+        sort_search.newest_first = !search.newest_first;
+        table.insert("newest_first".into(), search.newest_first.into());
+        table.insert(
+            "sort_url".into(),
+            OkuPath::search_path(&sort_search, 1).into(),
+        );
+        table.insert(
+            "previous".into(),
+            match page > 1 {
+                true => OkuPath::search_path(search, page - 1),
+                false => String::new(),
+            }
+            .into(),
+        );
+        table.insert(
+            "next".into(),
+            match search.offset + search.limit < results.total {
+                true => OkuPath::search_path(search, page + 1),
+                false => String::new(),
+            }
+            .into(),
+        );
         Ok(table)
     }
 
-    pub fn create_search_page(&self, query: &str) -> miette::Result<()> {
-        let table = self.get_search_frontmatter(query)?;
+    pub async fn create_search_page(
+        &self,
+        search: &OkuPostSearch,
+        results: &OkuPostSearchResults,
+        content_warning_behaviour: ContentWarningBehaviour,
+    ) -> miette::Result<()> {
+        let table = self
+            .get_search_frontmatter(search, results, content_warning_behaviour)
+            .await?;
         let page_contents = format!(
             "---
 {}
 ---
-{{% if search[0] %}}
-{{% include search.voxs posts = search %}}
-{{% else %}}
-{{% include search.voxs posts = \"\" %}}
-{{% endif %}}
+{{% include search.voxs %}}
 ",
             table
         );
         self.0.write_file("search.vox", page_contents)?;
         Ok(())
     }
-    pub async fn search(&self, query: String) -> miette::Result<String> {
-        let search_results = OkuDatabase::search_posts(&query, &None)?;
-        for post in search_results.iter() {
-            self.create_post_page(&post.user(), post, None).await?;
-        }
-        self.create_search_page(&query)?;
+
+    pub async fn search(&self, mut search: OkuPostSearch) -> miette::Result<String> {
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("No running Oku node … "))?;
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        search.hide_flagged = content_warning_behaviour == ContentWarningBehaviour::Hide;
+        let results = node.search_posts(&search).await?;
+        self.create_search_page(&search, &results, content_warning_behaviour)
+            .await?;
         self.render_and_get("output/search")
     }
 }
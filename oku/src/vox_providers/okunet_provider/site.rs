@@ -0,0 +1,260 @@
+use super::core::{OkuNetProvider, OKUNET_VOX_FILES};
+use crate::NODE;
+use miette::IntoDiagnostic;
+use oku_core::{
+    config::{ContentWarningBehaviour, OkuFsConfig},
+    database::{
+        core::DATABASE,
+        posts::{
+            core::OkuPost,
+            syndication::{OkuFeedFormat, OkuSyndicationFeed},
+        },
+        users::OkuUser,
+    },
+    fs::util::entry_key_to_path,
+    iroh_docs::{AuthorId, NamespaceId},
+};
+use std::{cmp::Reverse, collections::HashMap, path::PathBuf};
+use vox::{provider::VoxProvider, ram_provider::RamProvider};
+
+/// The maximum number of posts in the feeds of an exported website
+pub const SITE_FEED_LIMIT: usize = 50;
+
+/// The name a user calls themselves, as petnames are only known to the local user
+fn site_user_name(user: &OkuUser) -> String {
+    user.identity
+        .as_ref()
+        .map(|x| x.name.clone())
+        .unwrap_or(oku_core::fs::util::fmt_short(user.author_id))
+}
+
+/// Name the users listed in a profile's frontmatter as they name themselves, rather than by petname
+fn use_site_user_names(table: &mut toml::Table) {
+    for list in ["following", "followers"] {
+        let Some(toml::Value::Array(users)) = table.get_mut(list) else {
+            continue;
+        };
+        for user_table in users.iter_mut().filter_map(|x| x.as_table_mut()) {
+            let user = user_table
+                .get("id")
+                .and_then(|x| x.as_str())
+                .and_then(|x| oku_core::fs::util::parse_array_hex_or_base32::<32>(x).ok())
+                .map(AuthorId::from)
+                .and_then(|x| DATABASE.get_user(&x).ok().flatten());
+            if let Some(user) = user {
+                user_table.insert("name".into(), site_user_name(&user).into());
+            }
+        }
+    }
+}
+
+impl OkuNetProvider {
+    /// A provider rendering a user's profile and posts as a static website, linking between its own pages rather than to `oku:` pages
+    pub fn new_site(user: &OkuUser) -> miette::Result<Self> {
+        let mut files = OKUNET_VOX_FILES.clone();
+        let mut global: toml::Table = files
+            .get(&PathBuf::from("global.toml"))
+            .map(|x| x.parse())
+            .transpose()
+            .into_diagnostic()?
+            .unwrap_or_default();
+        let user_name = site_user_name(user);
+        global.insert("site".into(), true.into());
+        global.insert("site_url".into(), "/".into());
+        global.insert("title".into(), user_name.clone().into());
+        global.insert("author".into(), user_name.into());
+        files.insert("global.toml".into(), global.to_string());
+        Ok(Self(RamProvider::new(Some(files))))
+    }
+
+    pub fn get_site_post_permalink(&self, post: &OkuPost) -> miette::Result<String> {
+        let key_path = entry_key_to_path(post.entry.key())?;
+        let key_path_str = key_path.to_string_lossy();
+        let post_url = key_path_str.strip_suffix(".toml").unwrap_or(&key_path_str);
+        Ok(format!(
+            "posts/{}.html",
+            post_url.trim_start_matches("/posts/")
+        ))
+    }
+
+    pub async fn create_site_post_page(
+        &self,
+        user: &OkuUser,
+        post: &OkuPost,
+        content_warning_behaviour: ContentWarningBehaviour,
+    ) -> miette::Result<()> {
+        let page_path = self.get_post_path(post, None);
+        let mut table = self
+            .get_post_frontmatter(user, post, content_warning_behaviour)
+            .await?;
+        table.insert(
+            "permalink".into(),
+            self.get_site_post_permalink(post)?.into(),
+        );
+        // Nothing on the website depends on who is viewing it
+        table.insert("by_me".into(), false.into());
+        table.insert("author_impersonates".into(), "".into());
+        if let Some(toml::Value::Table(author)) = table.get_mut("author") {
+            author.insert("name".into(), site_user_name(user).into());
+        }
+        // Mentions are named as the mentioned users name themselves, rather than by petname
+        let body = post.note.replace_mentions(|author_id| {
+            let name = DATABASE
+                .get_user(author_id)
+                .ok()
+                .flatten()
+                .and_then(|x| x.identity)
+                .map(|x| x.name)
+                .unwrap_or(oku_core::fs::util::fmt_short(author_id))
+                .replace(['[', ']'], "");
+            format!("[@{name}](oku:{})", oku_core::fs::util::fmt(author_id))
+        });
+        let page_contents = format!(
+            "---
+{}
+---
+{{% markdown %}}
+{{% raw %}}
+{}
+{{% endraw %}}
+{{% endmarkdown %}}",
+            table, body
+        );
+        self.0.write_file(page_path, page_contents)?;
+        Ok(())
+    }
+
+    pub async fn create_site_index_page(
+        &self,
+        user: &OkuUser,
+        posts: Vec<OkuPost>,
+    ) -> miette::Result<()> {
+        let include_argument = if !posts.is_empty() {
+            oku_core::fs::util::fmt(user.author_id)
+        } else {
+            "empty".into()
+        };
+        let mut table = self.get_user_frontmatter(user, posts).await?;
+        table.insert("permalink".into(), "index.html".into());
+        // Nothing on the website depends on who is viewing it
+        table.insert("title".into(), site_user_name(user).into());
+        table.insert("self_chosen_name".into(), "".into());
+        table.insert("notes".into(), "".into());
+        table.insert("impersonates".into(), "".into());
+        table.insert("follows_me".into(), false.into());
+        use_site_user_names(&mut table);
+        let page_contents = format!(
+            "---
+{}
+---
+{{% include profile.voxs posts = {} %}}
+",
+            table, include_argument
+        );
+        self.0.write_file("index.vox", page_contents)?;
+        Ok(())
+    }
+
+    /// Render a user's profile and posts as a static website
+    ///
+    /// Returns the files of the website, by their path
+    pub async fn render_site(
+        &self,
+        replica_id: &NamespaceId,
+        user: &OkuUser,
+        posts: Vec<OkuPost>,
+    ) -> miette::Result<HashMap<PathBuf, Vec<u8>>> {
+        let mut permalinks = vec!["index.html".to_string()];
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        for post in posts.iter() {
+            self.create_site_post_page(user, post, content_warning_behaviour)
+                .await?;
+            permalinks.push(self.get_site_post_permalink(post)?);
+        }
+        self.create_site_index_page(user, posts.clone()).await?;
+        self.render()?;
+
+        let mut files = HashMap::new();
+        for permalink in permalinks {
+            let page = self.0.read_to_string(format!("output/{permalink}"))?;
+            files.insert(PathBuf::from("/").join(permalink), page.into_bytes());
+        }
+        let mut feed_posts = posts;
+        feed_posts.sort_unstable_by_key(|x| Reverse(x.entry.timestamp()));
+        feed_posts.truncate(SITE_FEED_LIMIT);
+        let feed = OkuSyndicationFeed {
+            title: site_user_name(user),
+            id: format!("hive://{}/feed.atom", oku_core::fs::util::fmt(replica_id)),
+            posts: feed_posts,
+            author_names: HashMap::from([(user.author_id, site_user_name(user))]),
+        };
+        for format in [OkuFeedFormat::Atom, OkuFeedFormat::Json] {
+            files.insert(
+                PathBuf::from(format!("/feed.{}", format.extension())),
+                feed.render(format)?.into_bytes(),
+            );
+        }
+        Ok(files)
+    }
+
+    /// Export a user's profile and posts as a static website into a replica, viewable at `hive://<replica>/index.html`
+    ///
+    /// Only files which have changed since the last export are written, and pages of deleted posts are removed
+    ///
+    /// Returns the number of files written or removed
+    pub async fn export_site(
+        replica_id: &NamespaceId,
+        author_id: &Option<AuthorId>,
+    ) -> miette::Result<usize> {
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("No running Oku node … "))?;
+        if node.home_replica().await == Some(*replica_id) {
+            return Err(miette::miette!(
+                "A website cannot be exported into the home replica … "
+            ));
+        }
+        let user = match author_id {
+            Some(author_id) if !node.is_me(author_id).await => {
+                node.get_or_fetch_user(author_id).await?
+            }
+            _ => node.user().await?,
+        };
+        // Restricted posts are only for their readers, while the website may be read by anyone
+        let posts: Vec<_> = node
+            .posts_from_user(&user)
+            .await?
+            .into_iter()
+            .filter(|x| !x.note.is_restricted())
+            .collect();
+        let files = Self::new_site(&user)?
+            .render_site(replica_id, &user, posts)
+            .await?;
+
+        let mut changed = 0;
+        for (path, contents) in files.iter() {
+            let unchanged = node
+                .read_file(replica_id, path, &None, &None)
+                .await
+                .is_ok_and(|x| x == contents.as_slice());
+            if !unchanged {
+                node.create_or_replace_file(replica_id, path, contents.clone())
+                    .await?;
+                changed += 1;
+            }
+        }
+        for path in node
+            .list_files(replica_id, &Some("/posts/".into()))
+            .await
+            .unwrap_or_default()
+        {
+            if !files.contains_key(&path) {
+                node.delete_file(replica_id, &path).await?;
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+}
@@ -1,20 +1,35 @@
 use std::collections::HashSet;
 
 use super::core::OkuNetProvider;
+use crate::scheme_handlers::oku_path::TagSort;
 use crate::NODE;
-use oku_core::database::posts::core::OkuPost;
+use oku_core::config::{ContentWarningBehaviour, OkuFsConfig};
+use oku_core::database::{
+    core::DATABASE,
+    posts::{
+        core::OkuPost,
+        statistics::OkuTimeWindow,
+        syndication::{OkuFeedFormat, OkuFeedSource},
+    },
+};
 use rayon::iter::FromParallelIterator;
 use vox::provider::VoxProvider;
 
+const ACTIVITY_LIMIT: usize = 10;
+
 impl OkuNetProvider {
     pub async fn get_tag_frontmatter(
         &self,
         tag: String,
         posts: Vec<OkuPost>,
+        content_warning_behaviour: ContentWarningBehaviour,
     ) -> miette::Result<toml::Table> {
         let mut tag_post_frontmatter: Vec<toml::Table> = Vec::new();
         for post in posts.iter() {
-            if let Ok(post_frontmatter) = self.get_post_frontmatter(&post.user(), post).await {
+            if let Ok(post_frontmatter) = self
+                .get_post_frontmatter(&post.user(), post, content_warning_behaviour)
+                .await
+            {
                 tag_post_frontmatter.push(post_frontmatter);
             }
         }
@@ -22,6 +37,9 @@ impl OkuNetProvider {
         table.insert("layout".into(), "default".into());
         table.insert("depends".into(), vec![tag.clone()].into());
         table.insert("permalink".into(), format!("tag/{}", tag).into());
+        let source = OkuFeedSource::Tag(tag.clone());
+        table.insert("atom_url".into(), source.uri(OkuFeedFormat::Atom).into());
+        table.insert("json_url".into(), source.uri(OkuFeedFormat::Json).into());
         table.insert("title".into(), tag.into());
         table.insert("posts".into(), tag_post_frontmatter.into());
         Ok(table)
@@ -30,18 +48,32 @@ impl OkuNetProvider {
         let node = NODE
             .get()
             .ok_or(miette::miette!("No running Oku node … "))?;
-        let tag_posts = node
-            .posts_with_tags(
-                &Vec::from_par_iter(node.all_posts().await),
-                &HashSet::from_par_iter(vec![tag.clone()]),
+        let tag_posts = DATABASE.filter_muted_posts(
+            node.without_blocked_posts(
+                node.posts_with_tags(
+                    &Vec::from_par_iter(node.all_posts().await),
+                    &HashSet::from_par_iter(vec![tag.clone()]),
+                )
+                .await,
             )
-            .await;
+            .await,
+        );
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
         for post in tag_posts.iter() {
-            self.create_post_page(&post.user(), post, Some(tag.clone()))
-                .await?;
+            self.create_post_page(
+                &post.user(),
+                post,
+                Some(tag.clone()),
+                content_warning_behaviour,
+            )
+            .await?;
         }
         let page_path = format!("/tag/{}.vox", tag);
-        let table = self.get_tag_frontmatter(tag.clone(), tag_posts).await?;
+        let table = self
+            .get_tag_frontmatter(tag.clone(), tag_posts, content_warning_behaviour)
+            .await?;
         let page_contents = format!(
             "---
 {0}
@@ -63,14 +95,111 @@ impl OkuNetProvider {
         self.render_and_get(format!("output/tag/{}", tag))
     }
 
-    pub async fn view_tags(&self) -> miette::Result<String> {
+    pub async fn get_tags_frontmatter(
+        &self,
+        window: OkuTimeWindow,
+        sort: TagSort,
+    ) -> miette::Result<toml::Table> {
         let node = NODE
             .get()
             .ok_or(miette::miette!("No running Oku node … "))?;
-        let tags = node.all_tags(&node.all_posts().await).await;
-        for tag in tags {
-            self.create_tag_page(tag.clone()).await?;
+        let statistics = node.activity_statistics(&window.start()).await?;
+        let sort_path = match sort {
+            TagSort::Count => "",
+            TagSort::Name => "&sort=name",
+        };
+        let windows: Vec<_> = OkuTimeWindow::ALL
+            .iter()
+            .map(|x| {
+                let mut window_table = toml::Table::new();
+                window_table.insert(
+                    "label".into(),
+                    match x {
+                        OkuTimeWindow::Day => "Past day",
+                        OkuTimeWindow::Week => "Past week",
+                        OkuTimeWindow::Month => "Past month",
+                        OkuTimeWindow::Year => "Past year",
+                        OkuTimeWindow::All => "All time",
+                    }
+                    .into(),
+                );
+                window_table.insert("url".into(), format!("tags?since={x}{sort_path}").into());
+                window_table.insert("active".into(), (*x == window).into());
+                window_table
+            })
+            .collect();
+        let mut tags = statistics.tags;
+        if sort == TagSort::Name {
+            tags.sort_unstable_by(|a, b| a.0.cmp(&b.0));
         }
+        let tags: Vec<_> = tags
+            .into_iter()
+            .map(|(tag, count)| {
+                let mut tag_table = toml::Table::new();
+                tag_table.insert("name".into(), tag.into());
+                tag_table.insert("count".into(), (count as i64).into());
+                tag_table
+            })
+            .collect();
+        let urls: Vec<_> = statistics
+            .urls
+            .into_iter()
+            .take(ACTIVITY_LIMIT)
+            .map(|(url, count)| {
+                let mut url_table = toml::Table::new();
+                url_table.insert("url".into(), url.into());
+                url_table.insert("count".into(), (count as i64).into());
+                url_table
+            })
+            .collect();
+        let mut authors = Vec::new();
+        for (author_id, count) in statistics.authors.into_iter().take(ACTIVITY_LIMIT) {
+            let name = match node.get_or_fetch_user(&author_id).await {
+                Ok(user) => DATABASE.display_name(&user),
+                Err(_) => DATABASE
+                    .get_petname(&author_id)
+                    .unwrap_or(oku_core::fs::util::fmt_short(author_id)),
+            };
+            let mut author_table = toml::Table::new();
+            author_table.insert("id".into(), oku_core::fs::util::fmt(author_id).into());
+            author_table.insert("name".into(), name.into());
+            author_table.insert("count".into(), (count as i64).into());
+            authors.push(author_table);
+        }
+
+        let mut table = toml::Table::new();
+        table.insert("layout".into(), "default".into());
+        table.insert("title".into(), "Tags".into());
+        table.insert("permalink".into(), "tags".into());
+        table.insert("since".into(), window.to_string().into());
+        table.insert("sort_by_name".into(), (sort == TagSort::Name).into());
+        table.insert(
+            "sort_url".into(),
+            match sort {
+                TagSort::Count => format!("tags?since={window}&sort=name"),
+                TagSort::Name => format!("tags?since={window}"),
+            }
+            .into(),
+        );
+        table.insert("windows".into(), windows.into());
+        table.insert("post_count".into(), (statistics.posts as i64).into());
+        table.insert("tags".into(), tags.into());
+        table.insert("urls".into(), urls.into());
+        table.insert("authors".into(), authors.into());
+        Ok(table)
+    }
+
+    pub async fn view_tags(&self, window: OkuTimeWindow, sort: TagSort) -> miette::Result<String> {
+        let table = self.get_tags_frontmatter(window, sort).await?;
+        let page_contents = format!(
+            "---
+{}
+---
+{{% include activity.voxs %}}
+",
+            table
+        );
+        self.0.write_file("tags.vox", page_contents)?;
         self.render_and_get("output/tags")
     }
 }
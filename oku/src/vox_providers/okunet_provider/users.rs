@@ -1,7 +1,8 @@
 use super::core::OkuNetProvider;
 use crate::NODE;
 use oku_core::{
-    database::{posts::core::OkuPost, users::OkuUser},
+    config::OkuFsConfig,
+    database::{core::DATABASE, posts::core::OkuPost, users::OkuUser},
     iroh_docs::AuthorId,
 };
 use vox::provider::VoxProvider;
@@ -12,10 +13,22 @@ impl OkuNetProvider {
         user: &OkuUser,
         posts: Vec<OkuPost>,
     ) -> miette::Result<toml::Table> {
-        let user_name = match &user.identity {
-            Some(identity) => identity.name.clone(),
-            None => oku_core::fs::util::fmt(user.author_id),
-        };
+        let user_name = DATABASE.display_name(user);
+        let self_chosen_name = user
+            .identity
+            .as_ref()
+            .map(|x| x.name.clone())
+            .unwrap_or_default();
+        let contact = DATABASE.get_contact(&user.author_id).ok().flatten();
+        let impersonated_petname = DATABASE
+            .impersonated_contact(user)
+            .and_then(|x| x.petname)
+            .unwrap_or_default();
+        let user_bio = user
+            .identity
+            .as_ref()
+            .and_then(|x| x.bio.clone())
+            .unwrap_or_default();
         let node = NODE
             .get()
             .ok_or(miette::miette!("No running Oku node … "))?;
@@ -28,9 +41,12 @@ impl OkuNetProvider {
                     "id".into(),
                     oku_core::fs::util::fmt(followed_user_information.author_id).into(),
                 );
+                followed_user_table.insert(
+                    "name".into(),
+                    DATABASE.display_name(&followed_user_information).into(),
+                );
                 match followed_user_information.identity {
                     Some(discovered_identity) => {
-                        followed_user_table.insert("name".into(), discovered_identity.name.into());
                         followed_user_table.insert(
                             "following".into(),
                             discovered_identity
@@ -42,16 +58,30 @@ impl OkuNetProvider {
                         );
                     }
                     None => {
-                        followed_user_table.insert(
-                            "name".into(),
-                            oku_core::fs::util::fmt(followed_user_information.author_id).into(),
-                        );
                         followed_user_table.insert("following".into(), Vec::<String>::new().into());
                     }
                 };
                 following.push(followed_user_table);
             }
         }
+        let mutuals = node.mutuals(&user.author_id).await.unwrap_or_default();
+        let mut followers: Vec<_> = Vec::new();
+        for follower in node.followers_of(&user.author_id).await {
+            let follower_name = match node.is_me(&follower).await {
+                true => node.identity().await.map(|x| x.name),
+                false => DATABASE
+                    .get_user(&follower)
+                    .ok()
+                    .flatten()
+                    .map(|x| DATABASE.display_name(&x)),
+            }
+            .unwrap_or(oku_core::fs::util::fmt(follower));
+            let mut follower_table = toml::Table::new();
+            follower_table.insert("id".into(), oku_core::fs::util::fmt(follower).into());
+            follower_table.insert("name".into(), follower_name.into());
+            follower_table.insert("is_mutual".into(), mutuals.contains(&follower).into());
+            followers.push(follower_table);
+        }
         let mut table = toml::Table::new();
         table.insert("layout".into(), "default".into());
         table.insert(
@@ -59,6 +89,20 @@ impl OkuNetProvider {
             format!("{}.html", oku_core::fs::util::fmt(user.author_id)).into(),
         );
         table.insert("title".into(), user_name.into());
+        table.insert("bio".into(), user_bio.into());
+        table.insert(
+            "self_chosen_name".into(),
+            match self_chosen_name != user_name {
+                true => self_chosen_name,
+                false => String::new(),
+            }
+            .into(),
+        );
+        table.insert(
+            "notes".into(),
+            contact.and_then(|x| x.notes).unwrap_or_default().into(),
+        );
+        table.insert("impersonates".into(), impersonated_petname.into());
         table.insert(
             "author_id".into(),
             oku_core::fs::util::fmt(user.author_id).into(),
@@ -72,6 +116,10 @@ impl OkuNetProvider {
             node.is_blocked(&user.author_id).await.into(),
         );
         table.insert("is_me".into(), node.is_me(&user.author_id).await.into());
+        table.insert(
+            "follows_me".into(),
+            node.follows_me(&user.author_id).await.into(),
+        );
         if !posts.is_empty() {
             table.insert(
                 "depends".into(),
@@ -81,6 +129,7 @@ impl OkuNetProvider {
             table.insert("empty".into(), Vec::<String>::new().into());
         }
         table.insert("following".into(), following.into());
+        table.insert("followers".into(), followers.into());
         Ok(table)
     }
     pub async fn create_profile_page(
@@ -89,12 +138,16 @@ impl OkuNetProvider {
         posts: Option<Vec<OkuPost>>,
     ) -> miette::Result<()> {
         let user_posts = posts.unwrap_or(
-            oku_core::database::core::DATABASE
+            DATABASE
                 .get_posts_by_author(&user.author_id)
                 .unwrap_or_default(),
         );
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
         for post in user_posts.iter() {
-            self.create_post_page(user, post, None).await?;
+            self.create_post_page(user, post, None, content_warning_behaviour)
+                .await?;
         }
         let page_path = format!("{}.vox", oku_core::fs::util::fmt(user.author_id));
         let include_argument = if !user_posts.is_empty() {
@@ -2,20 +2,24 @@ use crate::bookmark_item::BookmarkItem;
 use crate::database::Bookmark;
 use crate::database::DATABASE;
 use crate::window_util::get_window_from_widget;
+use crate::NODE;
 use glib::clone;
 use glib::object::Cast;
 use glib::object::CastNone;
+use glib::object::ObjectExt;
 use glib::subclass::object::ObjectImpl;
 use glib::subclass::types::ObjectSubclass;
 use glib::subclass::types::ObjectSubclassExt;
 use glib::subclass::types::ObjectSubclassIsExt;
 use glib::value::ToValue;
 use glib::ParamSpec;
+use glib::ParamSpecBoolean;
 use glib::ParamSpecBoxed;
 use glib::ParamSpecObject;
 use glib::ParamSpecString;
 use glib::Value;
 use gtk::prelude::ButtonExt;
+use gtk::prelude::CheckButtonExt;
 use gtk::prelude::ListBoxRowExt;
 use gtk::prelude::WidgetExt;
 use gtk::prelude::{BoxExt, GObjectPropertyExpressionExt};
@@ -24,9 +28,12 @@ use libadwaita::prelude::ActionRowExt;
 use libadwaita::prelude::PreferencesRowExt;
 use libadwaita::subclass::prelude::*;
 use log::error;
-use std::cell::RefCell;
+use oku_core::database::drafts::OkuDraft;
+use oku_core::database::posts::core::OkuNote;
+use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::sync::LazyLock;
+use std::time::SystemTime;
 use webkit2gtk::functions::uri_for_display;
 
 pub mod imp {
@@ -39,6 +46,9 @@ pub mod imp {
         pub(crate) body: RefCell<String>,
         pub(crate) tags: RefCell<Vec<String>>,
         pub(crate) favicon: gtk::Image,
+        pub(crate) draft: Cell<bool>,
+        pub(crate) selected: Cell<bool>,
+        pub(crate) select_button: gtk::CheckButton,
         pub(crate) edit_button: gtk::Button,
         pub(crate) delete_button: gtk::Button,
         pub(crate) button_box: gtk::Box,
@@ -79,6 +89,8 @@ pub mod imp {
                     ParamSpecString::builder("body").build(),
                     ParamSpecBoxed::builder::<Vec<String>>("tags").build(),
                     ParamSpecObject::builder::<gdk::Texture>("favicon").build(),
+                    ParamSpecBoolean::builder("draft").build(),
+                    ParamSpecBoolean::builder("selected").build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -106,6 +118,14 @@ pub mod imp {
                     let favicon = value.get::<gdk::Texture>().ok();
                     self.obj().set_favicon(favicon);
                 }
+                "draft" => {
+                    let draft = value.get::<bool>().unwrap();
+                    self.obj().set_draft(draft);
+                }
+                "selected" => {
+                    let selected = value.get::<bool>().unwrap();
+                    self.obj().set_selected(selected);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -117,6 +137,8 @@ pub mod imp {
                 "body" => self.obj().body().to_value(),
                 "tags" => self.obj().tags().to_value(),
                 "favicon" => self.obj().favicon().to_value(),
+                "draft" => self.obj().draft().to_value(),
+                "selected" => self.obj().selected().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -178,15 +200,39 @@ impl BookmarkRow {
             #[weak(rename_to = this)]
             self,
             move |_| {
-                crate::widgets::note_editor::NoteEditor::new(
-                    Some(&get_window_from_widget(&this)),
-                    Some(Bookmark {
-                        url: this.url(),
-                        title: this.title_property(),
-                        body: this.body(),
-                        tags: HashSet::from_iter(this.tags().into_iter()),
-                    }),
-                );
+                let bookmark = Bookmark {
+                    url: this.url(),
+                    title: this.title_property(),
+                    body: this.body(),
+                    tags: HashSet::from_iter(this.tags().into_iter()),
+                };
+                match this.draft() {
+                    true => match OkuNote::try_from(bookmark) {
+                        Ok(note) => {
+                            let draft = oku_core::database::core::DATABASE
+                                .get_draft(&note.url)
+                                .ok()
+                                .flatten()
+                                .unwrap_or(OkuDraft {
+                                    note,
+                                    last_modified: SystemTime::now(),
+                                });
+                            crate::widgets::note_editor::NoteEditor::new(
+                                Some(&get_window_from_widget(&this)),
+                                None,
+                                Some(draft),
+                            );
+                        }
+                        Err(e) => error!("{}", e),
+                    },
+                    false => {
+                        crate::widgets::note_editor::NoteEditor::new(
+                            Some(&get_window_from_widget(&this)),
+                            Some(bookmark),
+                            None,
+                        );
+                    }
+                }
             }
         ));
 
@@ -198,10 +244,24 @@ impl BookmarkRow {
         imp.delete_button.connect_clicked(clone!(
             #[weak(rename_to = this)]
             self,
-            move |_| {
-                if let Some(bookmark) = DATABASE.get_bookmark(this.url()).ok().flatten() {
-                    if let Err(e) = DATABASE.delete_bookmark(bookmark) {
-                        error!("{}", e)
+            move |_| match this.draft() {
+                true => match url::Url::parse(&this.url()) {
+                    Ok(url) => {
+                        tokio::spawn(async move {
+                            if let Some(node) = NODE.get() {
+                                if let Err(e) = node.discard_draft(&url).await {
+                                    error!("{}", e)
+                                }
+                            }
+                        });
+                    }
+                    Err(e) => error!("{}", e),
+                },
+                false => {
+                    if let Some(bookmark) = DATABASE.get_bookmark(this.url()).ok().flatten() {
+                        if let Err(e) = DATABASE.delete_bookmark(bookmark) {
+                            error!("{}", e)
+                        }
                     }
                 }
             }
@@ -214,9 +274,24 @@ impl BookmarkRow {
         imp.button_box.set_halign(gtk::Align::End);
         imp.button_box.add_css_class("linked");
 
+        imp.select_button.set_visible(false);
+        imp.select_button.set_valign(gtk::Align::Center);
+        imp.select_button
+            .set_tooltip_text(Some("Select for publishing to OkuNet"));
+        imp.select_button.connect_toggled(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |select_button| {
+                if this.selected() != select_button.is_active() {
+                    this.set_property("selected", select_button.is_active());
+                }
+            }
+        ));
+
         let content_box: gtk::Box = self.child().and_downcast().unwrap();
         content_box.set_hexpand(true);
 
+        self.add_prefix(&imp.select_button);
         self.add_prefix(&imp.favicon);
         self.add_suffix(&imp.button_box);
         self.set_margin_bottom(4);
@@ -293,6 +368,29 @@ impl BookmarkRow {
         self.update_title();
     }
 
+    pub fn draft(&self) -> bool {
+        self.imp().draft.get()
+    }
+
+    pub fn set_draft(&self, draft: bool) {
+        self.imp().draft.set(draft);
+    }
+
+    pub fn set_selectable(&self, selectable: bool) {
+        self.imp().select_button.set_visible(selectable);
+    }
+
+    pub fn selected(&self) -> bool {
+        self.imp().selected.get()
+    }
+
+    pub fn set_selected(&self, selected: bool) {
+        let imp = self.imp();
+
+        imp.selected.set(selected);
+        imp.select_button.set_active(selected);
+    }
+
     pub fn set_favicon(&self, favicon: Option<gdk::Texture>) {
         let imp = self.imp();
 
@@ -0,0 +1,206 @@
+use crate::HOME_REPLICA_SET;
+use crate::NODE;
+use glib::clone;
+use glib::subclass::object::ObjectImpl;
+use glib::subclass::types::ObjectSubclass;
+use glib::subclass::types::ObjectSubclassIsExt;
+use gtk::glib;
+use gtk::prelude::BoxExt;
+use gtk::prelude::WidgetExt;
+use gtk::subclass::prelude::*;
+use libadwaita::prelude::*;
+use libadwaita::subclass::dialog::AdwDialogImpl;
+use log::error;
+use oku_core::iroh_docs::AuthorId;
+use std::cell::RefCell;
+use std::sync::atomic::Ordering;
+
+pub mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub struct ConversationDialog {
+        pub(crate) author_id: RefCell<Option<AuthorId>>,
+        pub(crate) main_box: gtk::Box,
+        pub(crate) headerbar: libadwaita::HeaderBar,
+        pub(crate) content_box: gtk::Box,
+        pub(crate) message_list_box: gtk::ListBox,
+        pub(crate) scrolled_window: gtk::ScrolledWindow,
+        pub(crate) placeholder: gtk::Label,
+        pub(crate) entry_list_box: gtk::ListBox,
+        pub(crate) message_entry: libadwaita::EntryRow,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ConversationDialog {
+        const NAME: &'static str = "OkuConversationDialog";
+        type Type = super::ConversationDialog;
+        type ParentType = libadwaita::Dialog;
+    }
+
+    impl ObjectImpl for ConversationDialog {}
+    impl WidgetImpl for ConversationDialog {}
+    impl AdwDialogImpl for ConversationDialog {}
+}
+
+glib::wrapper! {
+    pub struct ConversationDialog(ObjectSubclass<imp::ConversationDialog>)
+    @extends libadwaita::Dialog, gtk::Widget, gtk::ConstraintTarget, gtk::Buildable, gtk::Accessible;
+}
+
+unsafe impl Send for ConversationDialog {}
+unsafe impl Sync for ConversationDialog {}
+
+impl ConversationDialog {
+    pub fn new(window: Option<&super::window::Window>, author_id: AuthorId) -> Self {
+        let this: Self = glib::Object::builder::<Self>().build();
+        let imp = this.imp();
+
+        imp.author_id.replace(Some(author_id));
+        let name = oku_core::database::core::DATABASE
+            .get_user(&author_id)
+            .ok()
+            .flatten()
+            .and_then(|x| x.identity)
+            .map(|x| x.name)
+            .unwrap_or(oku_core::fs::util::fmt_short(author_id));
+        this.set_title(&name);
+
+        imp.message_list_box
+            .set_selection_mode(gtk::SelectionMode::None);
+        imp.message_list_box.add_css_class("boxed-list-separate");
+        imp.scrolled_window.set_child(Some(&imp.message_list_box));
+        imp.scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.scrolled_window.set_min_content_height(320);
+        imp.scrolled_window.set_vexpand(true);
+
+        imp.placeholder.set_label("No messages … ");
+        imp.placeholder.set_margin_top(24);
+        imp.placeholder.set_margin_bottom(24);
+        imp.placeholder.add_css_class("title-2");
+
+        imp.message_entry.set_title("Message");
+        imp.message_entry.set_show_apply_button(true);
+        imp.message_entry.connect_apply(clone!(
+            #[weak]
+            this,
+            move |message_entry| {
+                let body = message_entry.text().trim().to_string();
+                if body.is_empty() {
+                    return;
+                }
+                message_entry.set_text("");
+                let author_id = this.author_id();
+                tokio::spawn(async move {
+                    if let (Some(node), Some(author_id)) = (NODE.get(), author_id) {
+                        if let Err(e) = node.send_message(&author_id, &body).await {
+                            error!("{}", e);
+                        }
+                    }
+                });
+            }
+        ));
+        if !HOME_REPLICA_SET.load(Ordering::Relaxed) {
+            imp.message_entry.set_sensitive(false);
+            imp.message_entry
+                .set_tooltip_text(Some("A home replica is required to send messages."));
+        }
+        imp.entry_list_box.append(&imp.message_entry);
+        imp.entry_list_box.add_css_class("boxed-list");
+
+        imp.content_box.set_orientation(gtk::Orientation::Vertical);
+        imp.content_box.set_spacing(8);
+        imp.content_box.add_css_class("toolbar");
+        imp.content_box.set_width_request(400);
+        imp.content_box.append(&imp.placeholder);
+        imp.content_box.append(&imp.scrolled_window);
+        imp.content_box.append(&imp.entry_list_box);
+
+        imp.headerbar.add_css_class("flat");
+        imp.main_box.set_orientation(gtk::Orientation::Vertical);
+        imp.main_box.append(&imp.headerbar);
+        imp.main_box.append(&imp.content_box);
+        this.set_child(Some(&imp.main_box));
+
+        // Messages are shown as they are sent or received, until the dialog is closed
+        let weak_this = this.downgrade();
+        glib::spawn_future_local(async move {
+            if let Some(node) = NODE.get() {
+                let mut message_rx = node.okunet_message_sender.subscribe();
+                loop {
+                    message_rx.borrow_and_update();
+                    match weak_this.upgrade() {
+                        Some(this) => this.messages_updated().await,
+                        None => break,
+                    }
+                    if let Err(e) = message_rx.changed().await {
+                        error!("{}", e);
+                        break;
+                    }
+                }
+            }
+        });
+        tokio::spawn(async move {
+            if let Some(node) = NODE.get() {
+                match node.resolve_author_id(&author_id).await {
+                    Ok(ticket) => {
+                        if let Err(e) = node.fetch_messages(&ticket).await {
+                            error!("{}", e);
+                        }
+                    }
+                    Err(e) => error!("{}", e),
+                }
+            }
+        });
+
+        this.set_follows_content_size(true);
+        this.set_visible(true);
+        this.present(window);
+
+        this
+    }
+
+    pub fn author_id(&self) -> Option<AuthorId> {
+        self.imp().author_id.borrow().to_owned()
+    }
+
+    pub async fn messages_updated(&self) {
+        let imp = self.imp();
+
+        let (Some(node), Some(author_id)) = (NODE.get(), self.author_id()) else {
+            return;
+        };
+        let me = node.default_author().await;
+        let messages = match node.conversation(&author_id).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        };
+        imp.message_list_box.remove_all();
+        imp.placeholder.set_visible(messages.is_empty());
+        imp.scrolled_window.set_visible(!messages.is_empty());
+        for message in messages {
+            let item = crate::okunet::items::message_item::MessageItem::new(&message, &me);
+            let row = libadwaita::ActionRow::new();
+            row.set_title(&item.body());
+            row.set_subtitle(&match item.outgoing() {
+                true => format!("You · {}", item.timestamp()),
+                false => format!("{} · {}", item.name(), item.timestamp()),
+            });
+            row.set_subtitle_lines(1);
+            row.add_css_class("property");
+            if item.outgoing() {
+                row.add_css_class("accent");
+            }
+            imp.message_list_box.append(&row);
+        }
+        // Keep the newest message in view
+        let adjustment = imp.scrolled_window.vadjustment();
+        glib::idle_add_local_once(move || {
+            adjustment.set_value(adjustment.upper());
+        });
+    }
+}
@@ -1,5 +1,6 @@
 pub mod address_entry;
 pub mod bookmark_row;
+pub mod conversation;
 pub mod download_row;
 pub mod history_row;
 pub mod note_editor;
@@ -9,6 +9,7 @@ use glib::subclass::types::ObjectSubclassExt;
 use glib::subclass::types::ObjectSubclassIsExt;
 use glib::value::ToValue;
 use glib::ParamSpec;
+use glib::ParamSpecBoolean;
 use glib::ParamSpecBoxed;
 use glib::ParamSpecString;
 use glib::Value;
@@ -21,8 +22,11 @@ use libadwaita::prelude::PreferencesRowExt;
 use libadwaita::prelude::*;
 use libadwaita::subclass::dialog::AdwDialogImpl;
 use log::error;
-use oku_core::database::posts::core::OkuNote;
-use std::cell::RefCell;
+use oku_core::database::core::DATABASE as OKU_DATABASE;
+use oku_core::database::drafts::OkuDraft;
+use oku_core::database::posts::core::OkuVisibility;
+use oku_core::iroh_docs::AuthorId;
+use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::sync::atomic::Ordering;
 use std::sync::LazyLock;
@@ -38,12 +42,19 @@ pub mod imp {
         pub(crate) title: RefCell<String>,
         pub(crate) body: RefCell<String>,
         pub(crate) tags: RefCell<Vec<String>>,
+        pub(crate) content_warning: RefCell<String>,
+        pub(crate) sensitive: Cell<bool>,
         pub(crate) main_box: gtk::Box,
         pub(crate) headerbar: libadwaita::HeaderBar,
         pub(crate) content_box: gtk::Box,
         pub(crate) url_entry: libadwaita::EntryRow,
         pub(crate) title_entry: libadwaita::EntryRow,
         pub(crate) tag_entry: libadwaita::EntryRow,
+        pub(crate) content_warning_entry: libadwaita::EntryRow,
+        pub(crate) sensitive_switch: libadwaita::SwitchRow,
+        pub(crate) visibility_row: libadwaita::ComboRow,
+        pub(crate) visibility_list: gtk::StringList,
+        pub(crate) circle_names: RefCell<Vec<String>>,
         pub(crate) row_list_box: gtk::ListBox,
         pub(crate) body_buffer: gtk::TextBuffer,
         pub(crate) body_entry: gtk::TextView,
@@ -53,6 +64,8 @@ pub mod imp {
         pub(crate) save_bookmark_button: gtk::Button,
         pub(crate) save_post_button_content: libadwaita::ButtonContent,
         pub(crate) save_post_button: gtk::Button,
+        pub(crate) save_draft_button_content: libadwaita::ButtonContent,
+        pub(crate) save_draft_button: gtk::Button,
         pub(crate) save_buttons: gtk::Box,
         pub(crate) tag_box: gtk::Box,
         pub(crate) tag_list: gtk::StringList,
@@ -60,6 +73,9 @@ pub mod imp {
         pub(crate) tag_model: gtk::SingleSelection,
         pub(crate) tag_view: gtk::ListView,
         pub(crate) tag_scrolled_window: gtk::ScrolledWindow,
+        pub(crate) mention_popover: gtk::Popover,
+        pub(crate) mention_list_box: gtk::ListBox,
+        pub(crate) mention_suggestions: RefCell<Vec<AuthorId>>,
     }
 
     impl NoteEditor {}
@@ -79,6 +95,8 @@ pub mod imp {
                     ParamSpecString::builder("title-property").build(),
                     ParamSpecString::builder("body").build(),
                     ParamSpecBoxed::builder::<Vec<String>>("tags").build(),
+                    ParamSpecString::builder("content-warning").build(),
+                    ParamSpecBoolean::builder("sensitive").build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -102,6 +120,14 @@ pub mod imp {
                     let tags = value.get::<Vec<String>>().unwrap();
                     self.obj().set_tags(tags);
                 }
+                "content-warning" => {
+                    let content_warning = value.get::<String>().unwrap();
+                    self.obj().set_content_warning(content_warning);
+                }
+                "sensitive" => {
+                    let sensitive = value.get::<bool>().unwrap();
+                    self.obj().set_sensitive_content(sensitive);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -112,9 +138,15 @@ pub mod imp {
                 "title-property" => self.obj().title_property().to_value(),
                 "body" => self.obj().body().to_value(),
                 "tags" => self.obj().tags().to_value(),
+                "content-warning" => self.obj().content_warning().unwrap_or_default().to_value(),
+                "sensitive" => self.obj().sensitive_content().to_value(),
                 _ => unimplemented!(),
             }
         }
+
+        fn dispose(&self) {
+            self.mention_popover.unparent();
+        }
     }
     impl WidgetImpl for NoteEditor {}
     impl AdwDialogImpl for NoteEditor {}
@@ -129,7 +161,11 @@ unsafe impl Send for NoteEditor {}
 unsafe impl Sync for NoteEditor {}
 
 impl NoteEditor {
-    pub fn new(window: Option<&super::window::Window>, bookmark: Option<Bookmark>) -> Self {
+    pub fn new(
+        window: Option<&super::window::Window>,
+        bookmark: Option<Bookmark>,
+        draft: Option<OkuDraft>,
+    ) -> Self {
         let this: Self = glib::Object::builder::<Self>().build();
         let imp = this.imp();
 
@@ -165,9 +201,46 @@ impl NoteEditor {
             }
         ));
 
+        imp.content_warning_entry.property_expression("text").bind(
+            &this,
+            "content-warning",
+            gtk::Widget::NONE,
+        );
+        imp.content_warning_entry.set_title("Content warning");
+
+        imp.sensitive_switch.property_expression("active").bind(
+            &this,
+            "sensitive",
+            gtk::Widget::NONE,
+        );
+        imp.sensitive_switch.set_title("Sensitive");
+        imp.sensitive_switch
+            .set_subtitle("Collapse this post for viewers until revealed");
+
         imp.row_list_box.append(&imp.url_entry);
         imp.row_list_box.append(&imp.title_entry);
         imp.row_list_box.append(&imp.tag_entry);
+        imp.row_list_box.append(&imp.content_warning_entry);
+        imp.visibility_list.append("Public");
+        imp.visibility_list.append("Followers");
+        let circle_names: Vec<_> = OKU_DATABASE
+            .get_circles()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|x| x.name)
+            .collect();
+        for circle_name in circle_names.iter() {
+            imp.visibility_list
+                .append(&format!("Circle: {circle_name}"));
+        }
+        imp.circle_names.replace(circle_names);
+        imp.visibility_row.set_model(Some(&imp.visibility_list));
+        imp.visibility_row.set_title("Visibility");
+        imp.visibility_row
+            .set_subtitle("Who is able to read this post on OkuNet");
+
+        imp.row_list_box.append(&imp.sensitive_switch);
+        imp.row_list_box.append(&imp.visibility_row);
         imp.row_list_box.add_css_class("boxed-list");
 
         imp.body_entry.set_buffer(Some(&imp.body_buffer));
@@ -237,12 +310,74 @@ impl NoteEditor {
         imp.save_post_button.add_css_class("linked");
         // imp.save_post_button.add_css_class("pill");
 
+        imp.save_draft_button_content
+            .set_icon_name("document-edit-symbolic");
+        imp.save_draft_button_content.set_label("Save draft");
+        imp.save_draft_button
+            .set_child(Some(&imp.save_draft_button_content));
+        imp.save_draft_button.add_css_class("linked");
+        imp.save_draft_button.connect_clicked(clone!(
+            #[weak]
+            this,
+            #[strong]
+            draft,
+            move |_| {
+                tokio::spawn(clone!(
+                    #[weak]
+                    this,
+                    #[strong]
+                    draft,
+                    async move {
+                        if let Some(node) = NODE.get() {
+                            match url::Url::parse(&this.url()) {
+                                Ok(parsed_url) => {
+                                    if let Some(draft) = &draft {
+                                        if draft.note.url != parsed_url {
+                                            if let Err(e) =
+                                                node.discard_draft(&draft.note.url).await
+                                            {
+                                                error!("{}", e);
+                                            }
+                                        }
+                                    }
+                                    match node
+                                        .save_draft(
+                                            &parsed_url,
+                                            &this.title_property(),
+                                            &this.body(),
+                                            &HashSet::from_iter(this.tags().into_iter()),
+                                            &this.content_warning(),
+                                            this.sensitive_content(),
+                                            &this.post_visibility(),
+                                        )
+                                        .await
+                                    {
+                                        Ok(_) => {
+                                            this.close();
+                                        }
+                                        Err(e) => {
+                                            error!("{}", e);
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    error!("{}", e);
+                                }
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
+
         imp.save_buttons.append(&imp.save_bookmark_button);
+        imp.save_buttons.append(&imp.save_draft_button);
         imp.save_buttons.append(&imp.save_post_button);
         imp.save_buttons.set_halign(gtk::Align::Center);
         imp.save_buttons.add_css_class("linked");
 
         this.setup_tag_list();
+        this.setup_mention_completion();
 
         imp.content_box.set_orientation(gtk::Orientation::Vertical);
         imp.content_box.set_spacing(8);
@@ -265,6 +400,15 @@ impl NoteEditor {
             imp.title_entry.set_text(&bookmark.title);
             imp.body_buffer.set_text(&bookmark.body);
             this.set_tags(bookmark.tags.into_iter().collect());
+        } else if let Some(draft) = draft.clone() {
+            imp.url_entry.set_text(draft.note.url.as_ref());
+            imp.title_entry.set_text(&draft.note.title);
+            imp.body_buffer.set_text(&draft.note.body);
+            imp.content_warning_entry
+                .set_text(&draft.note.content_warning.unwrap_or_default());
+            imp.sensitive_switch.set_active(draft.note.sensitive);
+            this.set_post_visibility(&draft.note.visibility);
+            this.set_tags(draft.note.tags.into_iter().collect());
         } else if let Some(window) = window {
             let view = window.get_view();
             let url = view
@@ -313,14 +457,18 @@ impl NoteEditor {
                         } else {
                             None
                         };
-                        let post_from_url = {
-                            let path = OkuNote::post_path_from_url(&url);
-                            node.post(&path.to_string().into()).await.ok()
+                        let post_from_url = match url::Url::parse(&url) {
+                            Ok(url) => node.post_about(&url).await.ok(),
+                            Err(_) => None,
                         };
                         if let Some(oku_post) = post_at_url.or(post_from_url) {
                             imp.url_entry.set_text(oku_post.note.url.as_ref());
                             imp.title_entry.set_text(&oku_post.note.title);
                             imp.body_buffer.set_text(&oku_post.note.body);
+                            imp.content_warning_entry
+                                .set_text(&oku_post.note.content_warning.unwrap_or_default());
+                            imp.sensitive_switch.set_active(oku_post.note.sensitive);
+                            this.set_post_visibility(&oku_post.note.visibility);
                             this.set_tags(oku_post.note.tags.into_iter().collect());
                         }
                     }
@@ -333,6 +481,8 @@ impl NoteEditor {
                 imp.save_post_button.connect_clicked(clone!(
                     #[weak]
                     this,
+                    #[strong]
+                    draft,
                     move |_| {
                         // let ctx = glib::MainContext::default();
                         // ctx.spawn_local_with_priority(
@@ -340,6 +490,8 @@ impl NoteEditor {
                         tokio::spawn(clone!(
                             #[weak]
                             this,
+                            #[strong]
+                            draft,
                             async move {
                                 if let Some(node) = NODE.get() {
                                     match url::Url::parse(&this.url()) {
@@ -350,10 +502,21 @@ impl NoteEditor {
                                                     &this.title_property(),
                                                     &this.body(),
                                                     &HashSet::from_iter(this.tags().into_iter()),
+                                                    &this.content_warning(),
+                                                    this.sensitive_content(),
+                                                    &this.post_visibility(),
                                                 )
                                                 .await
                                             {
                                                 Ok(_) => {
+                                                    if let Some(draft) = &draft {
+                                                        if let Err(e) = node
+                                                            .discard_draft(&draft.note.url)
+                                                            .await
+                                                        {
+                                                            error!("{}", e);
+                                                        }
+                                                    }
                                                     this.close();
                                                 }
                                                 Err(e) => {
@@ -396,6 +559,54 @@ impl NoteEditor {
     pub fn tags(&self) -> Vec<String> {
         self.imp().tags.borrow().clone()
     }
+    pub fn content_warning(&self) -> Option<String> {
+        let content_warning = self.imp().content_warning.borrow().trim().to_string();
+        match content_warning.is_empty() {
+            true => None,
+            false => Some(content_warning),
+        }
+    }
+    pub fn sensitive_content(&self) -> bool {
+        self.imp().sensitive.get()
+    }
+    pub fn post_visibility(&self) -> OkuVisibility {
+        let imp = self.imp();
+        match imp.visibility_row.selected() {
+            0 => OkuVisibility::Public,
+            1 => OkuVisibility::Followers,
+            selected => imp
+                .circle_names
+                .borrow()
+                .get((selected as usize).saturating_sub(2))
+                .map(|x| OkuVisibility::Circle(x.to_owned()))
+                .unwrap_or_default(),
+        }
+    }
+    pub fn set_post_visibility(&self, visibility: &OkuVisibility) {
+        let imp = self.imp();
+        let selected = match visibility {
+            OkuVisibility::Public => 0,
+            OkuVisibility::Followers => 1,
+            OkuVisibility::Circle(circle_name) => {
+                let position = imp
+                    .circle_names
+                    .borrow()
+                    .iter()
+                    .position(|x| x == circle_name);
+                match position {
+                    Some(position) => position + 2,
+                    // The circle has since been deleted; it is kept selectable so the post is not made public by accident
+                    None => {
+                        imp.visibility_list
+                            .append(&format!("Circle: {circle_name}"));
+                        imp.circle_names.borrow_mut().push(circle_name.to_owned());
+                        imp.circle_names.borrow().len() + 1
+                    }
+                }
+            }
+        };
+        imp.visibility_row.set_selected(selected as u32);
+    }
     pub fn set_url(&self, url: String) {
         let imp = self.imp();
 
@@ -411,6 +622,16 @@ impl NoteEditor {
 
         imp.body.replace(body);
     }
+    pub fn set_content_warning(&self, content_warning: String) {
+        let imp = self.imp();
+
+        imp.content_warning.replace(content_warning);
+    }
+    pub fn set_sensitive_content(&self, sensitive: bool) {
+        let imp = self.imp();
+
+        imp.sensitive.set(sensitive);
+    }
     pub fn set_tags(&self, tags: Vec<String>) {
         for tag in self.tags() {
             self.delete_tag(tag);
@@ -439,6 +660,123 @@ impl NoteEditor {
             imp.tags.borrow_mut().retain(|x| *x != tag);
         }
     }
+    fn mention_prefix(&self) -> Option<(String, i32)> {
+        let imp = self.imp();
+
+        let cursor = imp.body_buffer.iter_at_mark(&imp.body_buffer.get_insert());
+        let line_start = imp.body_buffer.iter_at_line(cursor.line())?;
+        let text = imp.body_buffer.text(&line_start, &cursor, false);
+        let index = text.rfind('@')?;
+        if text[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|x| x.is_alphanumeric())
+        {
+            return None;
+        }
+        let prefix = text[index + 1..].to_string();
+        let offset = line_start.offset() + text[..index].chars().count() as i32;
+        Some((prefix, offset))
+    }
+    pub fn update_mention_suggestions(&self) {
+        let imp = self.imp();
+
+        while let Some(row) = imp.mention_list_box.row_at_index(0) {
+            imp.mention_list_box.remove(&row);
+        }
+        let suggestions: Vec<_> = match self.mention_prefix() {
+            Some((prefix, _)) => {
+                let prefix = prefix.to_lowercase();
+                OKU_DATABASE
+                    .get_users()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|x| x.identity.map(|y| (x.author_id, y.name)))
+                    .filter(|x| x.1.to_lowercase().starts_with(&prefix))
+                    .take(5)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        if suggestions.is_empty() {
+            imp.mention_suggestions.borrow_mut().clear();
+            imp.mention_popover.popdown();
+            return;
+        }
+        for (author_id, name) in suggestions.iter() {
+            let row = libadwaita::ActionRow::new();
+            row.set_title(&html_escape::encode_text(name));
+            row.set_subtitle(&oku_core::fs::util::fmt_short(author_id));
+            row.set_activatable(true);
+            imp.mention_list_box.append(&row);
+        }
+        imp.mention_suggestions
+            .replace(suggestions.into_iter().map(|x| x.0).collect());
+
+        let cursor = imp.body_buffer.iter_at_mark(&imp.body_buffer.get_insert());
+        let location = imp.body_entry.iter_location(&cursor);
+        let (x, y) = imp.body_entry.buffer_to_window_coords(
+            gtk::TextWindowType::Widget,
+            location.x(),
+            location.y() + location.height(),
+        );
+        imp.mention_popover
+            .set_pointing_to(Some(&gtk::gdk::Rectangle::new(x, y, 1, 1)));
+        imp.mention_popover.popup();
+    }
+    pub fn complete_mention(&self, author_id: &AuthorId) {
+        let imp = self.imp();
+
+        if let Some((_, offset)) = self.mention_prefix() {
+            let mut start = imp.body_buffer.iter_at_offset(offset);
+            let mut end = imp.body_buffer.iter_at_mark(&imp.body_buffer.get_insert());
+            imp.body_buffer.delete(&mut start, &mut end);
+            imp.body_buffer.insert(
+                &mut start,
+                &format!("@{} ", oku_core::fs::util::fmt(author_id)),
+            );
+        }
+        imp.mention_popover.popdown();
+        imp.body_entry.grab_focus();
+    }
+    pub fn setup_mention_completion(&self) {
+        let imp = self.imp();
+
+        imp.mention_list_box
+            .set_selection_mode(gtk::SelectionMode::None);
+        imp.mention_list_box.add_css_class("navigation-sidebar");
+        imp.mention_list_box.connect_row_activated(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_, row| {
+                let author_id = this
+                    .imp()
+                    .mention_suggestions
+                    .borrow()
+                    .get(row.index() as usize)
+                    .copied();
+                if let Some(author_id) = author_id {
+                    this.complete_mention(&author_id);
+                }
+            }
+        ));
+
+        imp.mention_popover.set_child(Some(&imp.mention_list_box));
+        imp.mention_popover.set_position(gtk::PositionType::Bottom);
+        imp.mention_popover.set_has_arrow(false);
+        // Typing continues in the body while suggestions are shown
+        imp.mention_popover.set_autohide(false);
+        imp.mention_popover.set_can_focus(false);
+        imp.mention_popover.set_parent(&imp.body_entry);
+
+        imp.body_buffer.connect_changed(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                this.update_mention_suggestions();
+            }
+        ));
+    }
     pub fn setup_tag_list(&self) {
         let imp = self.imp();
 
@@ -9,7 +9,9 @@ use glib::subclass::object::ObjectImpl;
 use glib::subclass::types::ObjectSubclass;
 use glib::subclass::types::ObjectSubclassExt;
 use glib::subclass::types::ObjectSubclassIsExt;
-use rayon::iter::FromParallelIterator;
+use oku_core::config::{ContentWarningBehaviour, OkuFsConfig};
+use oku_core::database::core::DATABASE;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rayon::prelude::ParallelSliceMut;
 
 use glib::Object;
@@ -147,7 +149,25 @@ impl Net {
         let node = NODE.get().expect("Node initialised");
         tokio::spawn(node.refresh_users());
 
-        let mut posts = Vec::from_par_iter(node.all_posts().await);
+        let content_warning_behaviour = OkuFsConfig::load_or_create_config()
+            .unwrap_or_default()
+            .get_content_warning_behaviour();
+        let mut posts: Vec<_> = DATABASE
+            .filter_muted_posts(
+                node.without_blocked_posts(node.all_posts().await.into_par_iter().collect())
+                    .await,
+            )
+            .into_par_iter()
+            .filter_map(|mut post| match content_warning_behaviour {
+                ContentWarningBehaviour::Collapse => Some(post),
+                ContentWarningBehaviour::Hide => (!post.note.is_flagged()).then_some(post),
+                ContentWarningBehaviour::Expand => {
+                    post.note.content_warning = None;
+                    post.note.sensitive = false;
+                    Some(post)
+                }
+            })
+            .collect();
         posts.par_sort_unstable_by_key(|x| Reverse(x.entry.timestamp()));
         let posts_store = self.posts_store();
         let old_store = posts_store.snapshot();
@@ -246,6 +266,10 @@ impl Net {
                 .property_expression("item")
                 .chain_property::<crate::okunet::items::post_item::PostItem>("author-name")
                 .bind(&row, "author-name", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<crate::okunet::items::post_item::PostItem>("content-warning")
+                .bind(&row, "content-warning", gtk::Widget::NONE);
         }));
 
         imp.posts_view.set_model(Some(&imp.posts_model));
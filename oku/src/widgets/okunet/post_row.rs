@@ -1,3 +1,7 @@
+use crate::database::{Bookmark, DATABASE as BROWSER_DATABASE};
+use crate::okunet::items::post_item::PostItem;
+use crate::window_util::get_window_from_widget;
+use glib::clone;
 use glib::object::ObjectExt;
 use glib::property::PropertySet;
 use glib::subclass::object::ObjectImpl;
@@ -12,11 +16,17 @@ use glib::Value;
 use glib::{closure, Object};
 use gtk::glib;
 use gtk::prelude::BoxExt;
+use gtk::prelude::ButtonExt;
 use gtk::prelude::ListBoxRowExt;
 use gtk::prelude::WidgetExt;
 use gtk::subclass::prelude::*;
 use libadwaita::prelude::*;
+use log::error;
+use miette::IntoDiagnostic;
+use oku_core::database::core::DATABASE;
+use oku_core::iroh_docs::AuthorId;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::sync::LazyLock;
 
 pub mod imp {
@@ -33,6 +43,7 @@ pub mod imp {
         pub(crate) tags: RefCell<Vec<String>>,
         pub(crate) author_id: RefCell<String>,
         pub(crate) author_name: RefCell<Option<String>>,
+        pub(crate) content_warning: RefCell<Option<String>>,
         // Widgets
         pub(crate) url_label: gtk::Label,
         pub(crate) title_label: gtk::Label,
@@ -40,6 +51,11 @@ pub mod imp {
         pub(crate) tags_label: gtk::Label,
         pub(crate) author_label: gtk::Label,
         pub(crate) author_avatar: libadwaita::Avatar,
+        pub(crate) content_warning_label: gtk::Label,
+        pub(crate) reveal_button: gtk::ToggleButton,
+        pub(crate) content_warning_box: gtk::Box,
+        pub(crate) body_revealer: gtk::Revealer,
+        pub(crate) bookmark_button: gtk::Button,
         pub(crate) main: gtk::Box,
     }
 
@@ -81,6 +97,7 @@ pub mod imp {
                         .build(),
                     ParamSpecString::builder("author-id").build(),
                     ParamSpecString::builder("author-name").build(),
+                    ParamSpecString::builder("content-warning").build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -116,6 +133,10 @@ pub mod imp {
                     let author_name = value.get::<Option<&str>>().unwrap();
                     self.obj().set_author_name(&author_name);
                 }
+                "content-warning" => {
+                    let content_warning = value.get::<Option<&str>>().unwrap();
+                    self.obj().set_content_warning(&content_warning);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -128,6 +149,7 @@ pub mod imp {
                 "tags" => self.obj().tags().to_value(),
                 "author-id" => self.obj().author_id().to_value(),
                 "author-name" => self.obj().author_name().to_value(),
+                "content-warning" => self.obj().content_warning().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -151,6 +173,26 @@ impl Default for PostRow {
     }
 }
 
+impl From<&PostItem> for PostRow {
+    fn from(post_item: &PostItem) -> Self {
+        let obj = Self::default();
+        for property in [
+            "url",
+            "title",
+            "body",
+            "tags",
+            "author-id",
+            "author-name",
+            "content-warning",
+        ] {
+            post_item
+                .property_expression(property)
+                .bind(&obj, property, gtk::Widget::NONE);
+        }
+        obj
+    }
+}
+
 impl PostRow {
     pub fn new() -> Self {
         Self::default()
@@ -194,11 +236,65 @@ impl PostRow {
             ))
             .bind(&imp.author_avatar, "text", gtk::Widget::NONE);
 
+        imp.content_warning_label.add_css_class("warning");
+        imp.content_warning_label.set_hexpand(true);
+        imp.content_warning_label.set_xalign(0.0);
+        imp.reveal_button.set_label("Show");
+        imp.reveal_button
+            .bind_property("active", &imp.body_revealer, "reveal-child")
+            .build();
+        imp.reveal_button
+            .property_expression("active")
+            .chain_closure::<String>(closure!(|_: Option<Object>, active: bool| {
+                match active {
+                    true => "Hide".to_string(),
+                    false => "Show".to_string(),
+                }
+            }))
+            .bind(&imp.reveal_button, "label", gtk::Widget::NONE);
+        imp.content_warning_box
+            .set_orientation(gtk::Orientation::Horizontal);
+        imp.content_warning_box.set_spacing(4);
+        imp.content_warning_box.append(&imp.content_warning_label);
+        imp.content_warning_box.append(&imp.reveal_button);
+        imp.content_warning_box.set_visible(false);
+        imp.body_revealer.set_child(Some(&imp.body_label));
+        imp.body_revealer.set_reveal_child(true);
+
+        imp.bookmark_button
+            .set_icon_name("bookmark-filled-symbolic");
+        imp.bookmark_button
+            .set_tooltip_text(Some("Save as bookmark"));
+        imp.bookmark_button.add_css_class("flat");
+        imp.bookmark_button.set_halign(gtk::Align::End);
+        imp.bookmark_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| match this.save_as_bookmark() {
+                Ok(previous_bookmark) => {
+                    let window = get_window_from_widget(&this);
+                    let app = window.application().unwrap();
+                    let notification = match previous_bookmark {
+                        Some(_) => gio::Notification::new("Bookmark updated"),
+                        None => gio::Notification::new("Bookmark saved"),
+                    };
+                    notification.set_body(Some(&format!(
+                        "This post has been saved as a bookmark of {}.",
+                        html_escape::decode_html_entities(&this.url())
+                    )));
+                    app.send_notification(None, &notification);
+                }
+                Err(e) => error!("{}", e),
+            }
+        ));
+
+        imp.main.append(&imp.bookmark_button);
         imp.main.append(&imp.title_label);
         imp.main.append(&imp.url_label);
         imp.main.append(&imp.author_label);
         imp.main.append(&imp.author_avatar);
-        imp.main.append(&imp.body_label);
+        imp.main.append(&imp.content_warning_box);
+        imp.main.append(&imp.body_revealer);
         imp.main.append(&imp.tags_label);
         imp.main.set_vexpand(true);
         imp.main.set_hexpand(true);
@@ -230,6 +326,42 @@ impl PostRow {
             .map(|x| x.to_string())
     }
 
+    pub fn content_warning(&self) -> Option<String> {
+        self.imp().content_warning.borrow().clone()
+    }
+
+    /// Save this post as a bookmark; if the URL is already bookmarked, the post's tags are added to the bookmark, and its title and body are kept unless empty
+    pub fn save_as_bookmark(&self) -> miette::Result<Option<Bookmark>> {
+        let decode = |x: &str| html_escape::decode_html_entities(x).to_string();
+        let url = url::Url::parse(&decode(&self.url()))
+            .into_diagnostic()?
+            .to_string();
+        let title = decode(&self.title());
+        let body = decode(&self.body());
+        let tags: HashSet<String> = self.tags().iter().map(|x| decode(x)).collect();
+        let bookmark = match BROWSER_DATABASE.get_bookmark(url.clone())? {
+            Some(existing_bookmark) => Bookmark {
+                url,
+                title: match existing_bookmark.title.trim().is_empty() {
+                    true => title,
+                    false => existing_bookmark.title,
+                },
+                body: match existing_bookmark.body.trim().is_empty() {
+                    true => body,
+                    false => existing_bookmark.body,
+                },
+                tags: existing_bookmark.tags.union(&tags).cloned().collect(),
+            },
+            None => Bookmark {
+                url,
+                title,
+                body,
+                tags,
+            },
+        };
+        BROWSER_DATABASE.upsert_bookmark(bookmark)
+    }
+
     fn set_url(&self, url: &str) {
         let imp = self.imp();
 
@@ -258,11 +390,48 @@ impl PostRow {
         let imp = self.imp();
 
         imp.author_id.replace(author_id.to_string());
+        self.update_impersonation_warning();
     }
     pub fn set_author_name(&self, author_name: &Option<&str>) {
         let imp = self.imp();
 
         imp.author_name
             .replace(author_name.map(|x| x.to_string()).clone());
+        self.update_impersonation_warning();
+    }
+    fn update_impersonation_warning(&self) {
+        let imp = self.imp();
+
+        let impersonated_petname = oku_core::fs::util::parse_array_hex_or_base32(&self.author_id())
+            .ok()
+            .map(AuthorId::from)
+            .and_then(|x| DATABASE.get_user(&x).ok().flatten())
+            .and_then(|x| DATABASE.impersonated_contact(&x))
+            .and_then(|x| x.petname);
+        match impersonated_petname {
+            Some(petname) => {
+                imp.author_label.add_css_class("warning");
+                imp.author_label.set_tooltip_text(Some(&format!(
+                    "This name is the same as your contact '{petname}', but this is a different user"
+                )));
+            }
+            None => {
+                imp.author_label.remove_css_class("warning");
+                imp.author_label.set_tooltip_text(None);
+            }
+        }
+    }
+    pub fn set_content_warning(&self, content_warning: &Option<&str>) {
+        let imp = self.imp();
+
+        imp.content_warning
+            .replace(content_warning.map(|x| x.to_string()));
+        imp.content_warning_label
+            .set_label(content_warning.unwrap_or_default());
+        imp.content_warning_box
+            .set_visible(content_warning.is_some());
+        imp.reveal_button.set_active(false);
+        imp.body_revealer
+            .set_reveal_child(content_warning.is_none());
     }
 }
@@ -1,3 +1,5 @@
+use crate::database::{SiteExport, DATABASE};
+use crate::okunet::sites::export_site;
 use crate::window_util::get_window_from_widget;
 use crate::MOUNT_DIR;
 use crate::NODE;
@@ -46,6 +48,7 @@ pub mod imp {
         pub(crate) write_ticket_button: gtk::Button,
         pub(crate) fetch_button: gtk::Button,
         pub(crate) sync_button: gtk::Button,
+        pub(crate) site_button: gtk::Button,
         pub(crate) delete_button: gtk::Button,
         pub(crate) button_box: gtk::Box,
     }
@@ -327,6 +330,57 @@ impl ReplicaRow {
             }
         ));
 
+        imp.site_button.set_icon_name("globe-symbolic");
+        imp.site_button.add_css_class("linked");
+        imp.site_button.set_vexpand(false);
+        imp.site_button.set_hexpand(false);
+        imp.site_button.connect_clicked(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| {
+                let ctx = glib::MainContext::default();
+                ctx.spawn_local_with_priority(
+                    glib::source::Priority::HIGH,
+                    clone!(
+                        #[weak]
+                        this,
+                        async move {
+                            let window = get_window_from_widget(&this);
+                            let app = window.application().unwrap();
+                            match DATABASE.get_site_export(this.id()).ok().flatten() {
+                                Some(site_export) => {
+                                    if let Err(e) = DATABASE.delete_site_export(site_export.replica_id) {
+                                        error!("{}", e);
+                                    }
+                                    let notification = gio::Notification::new("Website no longer exported");
+                                    notification.set_body(Some(&format!("Your OkuNet posts will no longer be exported into a replica ({}); the website already exported remains.", this.id())));
+                                    app.send_notification(None, &notification);
+                                }
+                                None => {
+                                    let site_export = SiteExport {
+                                        replica_id: this.id(),
+                                        author_id: None,
+                                    };
+                                    match export_site(&site_export).await {
+                                        Ok(_) => {
+                                            if let Err(e) = DATABASE.upsert_site_export(site_export) {
+                                                error!("{}", e);
+                                            }
+                                            let notification = gio::Notification::new("Website exported");
+                                            notification.set_body(Some(&format!("Your OkuNet posts have been exported as a website, viewable at hive://{}/index.html, and will be kept up to date.", this.id())));
+                                            app.send_notification(None, &notification);
+                                        }
+                                        Err(e) => error!("{}", e),
+                                    }
+                                }
+                            }
+                            this.update_site_button();
+                        }
+                    ),
+                );
+            }
+        ));
+
         imp.delete_button.set_icon_name("user-trash-symbolic");
         // imp.delete_button.add_css_class("circular");
         imp.delete_button.add_css_class("destructive-action");
@@ -370,6 +424,7 @@ impl ReplicaRow {
         imp.button_box.append(&imp.write_ticket_button);
         imp.button_box.append(&imp.fetch_button);
         imp.button_box.append(&imp.sync_button);
+        imp.button_box.append(&imp.site_button);
         imp.button_box.append(&imp.delete_button);
         imp.button_box.set_homogeneous(false);
         imp.button_box.set_valign(gtk::Align::Center);
@@ -386,6 +441,11 @@ impl ReplicaRow {
         self.bind_property("writable", &imp.write_ticket_button, "visible")
             .bidirectional()
             .build();
+        self.bind_property("writable", &imp.site_button, "visible")
+            .build();
+        self.bind_property("home", &imp.site_button, "sensitive")
+            .invert_boolean()
+            .build();
 
         self.bind_property("id", self, "title")
             .bidirectional()
@@ -424,6 +484,24 @@ impl ReplicaRow {
         let imp = self.imp();
 
         imp.id.replace(id.to_string());
+        self.update_site_button();
+    }
+
+    pub fn update_site_button(&self) {
+        let imp = self.imp();
+
+        match DATABASE.get_site_export(self.id()).ok().flatten() {
+            Some(_) => {
+                imp.site_button
+                    .set_tooltip_text(Some("Stop exporting OkuNet posts as a website"));
+                imp.site_button.add_css_class("accent");
+            }
+            None => {
+                imp.site_button
+                    .set_tooltip_text(Some("Export OkuNet posts as a website"));
+                imp.site_button.remove_css_class("accent");
+            }
+        }
     }
 
     pub fn set_writable(&self, writable: bool) {
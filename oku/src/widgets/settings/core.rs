@@ -3,6 +3,7 @@ use gtk::glib;
 use gtk::subclass::prelude::*;
 use libadwaita::subclass::{dialog::AdwDialogImpl, preferences_dialog::PreferencesDialogImpl};
 use libadwaita::{prelude::*, StyleManager};
+use std::cell::RefCell;
 
 pub mod imp {
     use super::*;
@@ -27,6 +28,20 @@ pub mod imp {
         pub(crate) import_export_buttons: gtk::Box,
         pub(crate) author_buttons: gtk::Box,
         pub(crate) display_name_row: libadwaita::EntryRow,
+        pub(crate) bio_row: libadwaita::EntryRow,
+        pub(crate) content_warning_row: libadwaita::ComboRow,
+        pub(crate) content_warning_list: gtk::StringList,
+        pub(crate) blocklist_subscriptions_row: libadwaita::ExpanderRow,
+        pub(crate) blocklist_subscription_entry: libadwaita::EntryRow,
+        pub(crate) blocklist_subscription_rows: RefCell<Vec<libadwaita::ActionRow>>,
+        pub(crate) allowed_row: libadwaita::ExpanderRow,
+        pub(crate) allowed_entry: libadwaita::EntryRow,
+        pub(crate) allowed_rows: RefCell<Vec<libadwaita::ActionRow>>,
+        pub(crate) follower_notifications_row: libadwaita::SwitchRow,
+        pub(crate) mention_notifications_row: libadwaita::SwitchRow,
+        pub(crate) notified_users_row: libadwaita::ExpanderRow,
+        pub(crate) notified_user_entry: libadwaita::EntryRow,
+        pub(crate) notified_user_rows: RefCell<Vec<libadwaita::ActionRow>>,
     }
 
     impl Settings {}
@@ -1,4 +1,5 @@
 use super::core::Settings;
+use crate::database::{NotificationRule, NotificationTrigger, DATABASE as BROWSER_DATABASE};
 use crate::window_util::get_window_from_widget;
 use crate::{HOME_REPLICA_SET, NODE};
 use glib::clone;
@@ -6,14 +7,27 @@ use gtk::glib;
 use gtk::subclass::prelude::*;
 use libadwaita::{prelude::*, ResponseAppearance};
 use log::error;
+use oku_core::config::{ContentWarningBehaviour, OkuFsConfig};
+use oku_core::database::core::DATABASE;
+use oku_core::iroh_docs::AuthorId;
+use std::collections::HashSet;
 use std::sync::atomic::Ordering;
 
+#[derive(Clone, Copy)]
+enum AuthorList {
+    BlocklistSubscriptions,
+    Allowed,
+    NotifiedUsers,
+}
+
 impl Settings {
     pub fn setup_okunet_group(&self) {
         let imp = self.imp();
 
         imp.display_name_row.set_title("Display name");
         imp.display_name_row.set_show_apply_button(true);
+        imp.bio_row.set_title("Biography");
+        imp.bio_row.set_show_apply_button(true);
 
         imp.import_author_button
             .set_icon_name("system-switch-user-symbolic");
@@ -90,6 +104,38 @@ impl Settings {
         imp.author_row.set_subtitle_lines(1);
         imp.author_row.add_suffix(&imp.author_buttons);
 
+        imp.content_warning_list.append("Collapse");
+        imp.content_warning_list.append("Expand");
+        imp.content_warning_list.append("Hide");
+        imp.content_warning_row.set_title("Content warnings");
+        imp.content_warning_row
+            .set_subtitle("How posts with content warnings are shown");
+        imp.content_warning_row
+            .set_model(Some(&imp.content_warning_list));
+        let fs_config = OkuFsConfig::load_or_create_config().unwrap_or_default();
+        imp.content_warning_row
+            .set_selected(match fs_config.get_content_warning_behaviour() {
+                ContentWarningBehaviour::Collapse => 0_u32,
+                ContentWarningBehaviour::Expand => 1_u32,
+                ContentWarningBehaviour::Hide => 2_u32,
+            });
+        imp.content_warning_row
+            .connect_selected_notify(move |content_warning_row| {
+                let content_warning_behaviour = match content_warning_row.selected() {
+                    1 => ContentWarningBehaviour::Expand,
+                    2 => ContentWarningBehaviour::Hide,
+                    _ => ContentWarningBehaviour::Collapse,
+                };
+                if let Err(e) = fs_config
+                    .set_content_warning_behaviour(&Some(content_warning_behaviour))
+                    .and_then(|_| fs_config.save())
+                {
+                    error!("{}", e);
+                }
+            });
+
+        self.setup_blocklist_rows();
+        self.setup_notification_rows();
         self.initialise_okunet_information();
 
         imp.okunet_group.set_title("OkuNet");
@@ -97,6 +143,13 @@ impl Settings {
             .set_description(Some("Settings affecting the use of OkuNet"));
         imp.okunet_group.add(&imp.author_row);
         imp.okunet_group.add(&imp.display_name_row);
+        imp.okunet_group.add(&imp.bio_row);
+        imp.okunet_group.add(&imp.content_warning_row);
+        imp.okunet_group.add(&imp.blocklist_subscriptions_row);
+        imp.okunet_group.add(&imp.allowed_row);
+        imp.okunet_group.add(&imp.follower_notifications_row);
+        imp.okunet_group.add(&imp.mention_notifications_row);
+        imp.okunet_group.add(&imp.notified_users_row);
     }
 
     pub fn initialise_okunet_information(&self) {
@@ -123,9 +176,23 @@ impl Settings {
                         async move {
                             if let Some(current_identity) = node.identity().await {
                                 imp.display_name_row.set_text(&current_identity.name);
+                                imp.bio_row
+                                    .set_text(&current_identity.bio.unwrap_or_default());
                             }
                         }
                     ));
+                    imp.bio_row.connect_apply(clone!(move |bio_row| {
+                        let ctx = glib::MainContext::default();
+                        ctx.spawn_local(clone!(
+                            #[weak]
+                            bio_row,
+                            async move {
+                                if let Err(e) = node.set_bio(&Some(bio_row.text().into())).await {
+                                    error!("{}", e);
+                                }
+                            }
+                        ));
+                    }));
                     imp.display_name_row
                         .connect_apply(clone!(move |display_name_row| {
                             ctx.spawn_local(clone!(
@@ -141,10 +208,239 @@ impl Settings {
                             ));
                         }));
                 }
-                false => imp.display_name_row.set_text(""),
+                false => {
+                    imp.display_name_row.set_text("");
+                    imp.bio_row.set_text("");
+                }
             }
             imp.display_name_row.set_sensitive(home_replica_set);
+            imp.bio_row.set_sensitive(home_replica_set);
+            imp.blocklist_subscriptions_row
+                .set_sensitive(home_replica_set);
+            imp.allowed_row.set_sensitive(home_replica_set);
+            self.initialise_author_lists();
+        }
+    }
+
+    pub fn setup_blocklist_rows(&self) {
+        let imp = self.imp();
+
+        imp.blocklist_subscriptions_row
+            .set_title("Subscribed blocklists");
+        imp.blocklist_subscriptions_row
+            .set_subtitle("Also block the users these users have blocked");
+        imp.blocklist_subscription_entry.set_title("Author ID");
+        imp.blocklist_subscription_entry.set_show_apply_button(true);
+        imp.blocklist_subscription_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |entry| {
+                let author_id = entry.text().trim().to_string();
+                entry.set_text("");
+                glib::spawn_future_local(async move {
+                    if let Err(e) = this
+                        .add_author(AuthorList::BlocklistSubscriptions, &author_id)
+                        .await
+                    {
+                        error!("{}", e);
+                    }
+                    this.initialise_author_lists();
+                });
+            }
+        ));
+        imp.blocklist_subscriptions_row
+            .add_row(&imp.blocklist_subscription_entry);
+
+        imp.allowed_row.set_title("Allowed users");
+        imp.allowed_row
+            .set_subtitle("Never block these users through a subscribed blocklist");
+        imp.allowed_entry.set_title("Author ID");
+        imp.allowed_entry.set_show_apply_button(true);
+        imp.allowed_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |entry| {
+                let author_id = entry.text().trim().to_string();
+                entry.set_text("");
+                glib::spawn_future_local(async move {
+                    if let Err(e) = this.add_author(AuthorList::Allowed, &author_id).await {
+                        error!("{}", e);
+                    }
+                    this.initialise_author_lists();
+                });
+            }
+        ));
+        imp.allowed_row.add_row(&imp.allowed_entry);
+    }
+
+    pub fn setup_notification_rows(&self) {
+        let imp = self.imp();
+
+        for (row, title, subtitle, trigger) in [
+            (
+                &imp.follower_notifications_row,
+                "Follower notifications",
+                "Notify when someone follows you",
+                NotificationTrigger::Followers,
+            ),
+            (
+                &imp.mention_notifications_row,
+                "Mention notifications",
+                "Notify when a post mentions your author ID",
+                NotificationTrigger::Mentions,
+            ),
+        ] {
+            row.set_title(title);
+            row.set_subtitle(subtitle);
+            row.set_active(
+                BROWSER_DATABASE
+                    .get_notification_rule(trigger)
+                    .is_some_and(|x| x.enabled),
+            );
+            row.connect_active_notify(move |row| {
+                if let Err(e) = BROWSER_DATABASE
+                    .upsert_notification_rule(NotificationRule::new(trigger, row.is_active()))
+                {
+                    error!("{}", e);
+                }
+            });
+        }
+
+        imp.notified_users_row.set_title("Post notifications");
+        imp.notified_users_row
+            .set_subtitle("Notify when these users post");
+        imp.notified_user_entry.set_title("Author ID");
+        imp.notified_user_entry.set_show_apply_button(true);
+        imp.notified_user_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |entry| {
+                let author_id = entry.text().trim().to_string();
+                entry.set_text("");
+                glib::spawn_future_local(async move {
+                    if let Err(e) = this.add_author(AuthorList::NotifiedUsers, &author_id).await {
+                        error!("{}", e);
+                    }
+                    this.initialise_author_lists();
+                });
+            }
+        ));
+        imp.notified_users_row.add_row(&imp.notified_user_entry);
+    }
+
+    pub fn initialise_author_lists(&self) {
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = this)]
+            self,
+            async move {
+                if let Some(node) = NODE.get() {
+                    let identity = node.identity().await.unwrap_or_default();
+                    this.replace_author_rows(
+                        AuthorList::BlocklistSubscriptions,
+                        identity.blocklist_subscriptions,
+                    );
+                    this.replace_author_rows(AuthorList::Allowed, identity.allowed);
+                }
+                let notified_users = BROWSER_DATABASE
+                    .get_notification_rules()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|x| match x.trigger {
+                        NotificationTrigger::PostsBy(author_id) if x.enabled => Some(author_id),
+                        _ => None,
+                    })
+                    .collect();
+                this.replace_author_rows(AuthorList::NotifiedUsers, notified_users);
+            }
+        ));
+    }
+
+    fn replace_author_rows(&self, list: AuthorList, author_ids: HashSet<AuthorId>) {
+        let imp = self.imp();
+
+        let (expander_row, rows) = match list {
+            AuthorList::BlocklistSubscriptions => (
+                &imp.blocklist_subscriptions_row,
+                &imp.blocklist_subscription_rows,
+            ),
+            AuthorList::Allowed => (&imp.allowed_row, &imp.allowed_rows),
+            AuthorList::NotifiedUsers => (&imp.notified_users_row, &imp.notified_user_rows),
+        };
+        for row in rows.take() {
+            expander_row.remove(&row);
+        }
+        let mut new_rows = Vec::new();
+        for author_id in author_ids {
+            let author_id_string = oku_core::fs::util::fmt(author_id);
+            let name = DATABASE
+                .get_user(&author_id)
+                .ok()
+                .flatten()
+                .and_then(|x| x.identity)
+                .map(|x| x.name)
+                .unwrap_or(author_id_string.clone());
+            let row = libadwaita::ActionRow::new();
+            row.set_title(&html_escape::encode_text(&name));
+            row.set_subtitle(&author_id_string);
+            row.set_subtitle_lines(1);
+            row.add_css_class("property");
+            let remove_button = gtk::Button::from_icon_name("list-remove-symbolic");
+            remove_button.add_css_class("flat");
+            remove_button.set_valign(gtk::Align::Center);
+            remove_button.set_tooltip_text(Some("Remove"));
+            remove_button.connect_clicked(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| {
+                    glib::spawn_future_local(async move {
+                        if let AuthorList::NotifiedUsers = list {
+                            if let Err(e) = BROWSER_DATABASE
+                                .delete_notification_rule(NotificationTrigger::PostsBy(author_id))
+                            {
+                                error!("{}", e);
+                            }
+                        } else if let Some(node) = NODE.get() {
+                            let removed = match list {
+                                AuthorList::BlocklistSubscriptions => {
+                                    node.unsubscribe_from_blocklist(&author_id).await
+                                }
+                                AuthorList::Allowed => node.disallow(&author_id).await,
+                                AuthorList::NotifiedUsers => Ok(()),
+                            };
+                            if let Err(e) = removed {
+                                error!("{}", e);
+                            }
+                        }
+                        this.initialise_author_lists();
+                    });
+                }
+            ));
+            row.add_suffix(&remove_button);
+            expander_row.add_row(&row);
+            new_rows.push(row);
+        }
+        rows.replace(new_rows);
+    }
+
+    async fn add_author(&self, list: AuthorList, author_id: &str) -> miette::Result<()> {
+        let author_id =
+            oku_core::fs::util::parse_array_hex_or_base32(author_id).map(AuthorId::from)?;
+        if let AuthorList::NotifiedUsers = list {
+            BROWSER_DATABASE.upsert_notification_rule(NotificationRule::new(
+                NotificationTrigger::PostsBy(author_id),
+                true,
+            ))?;
+            return Ok(());
         }
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("Oku node has not yet started … "))?;
+        match list {
+            AuthorList::BlocklistSubscriptions => node.subscribe_to_blocklist(&author_id).await?,
+            AuthorList::Allowed => node.allow(&author_id).await?,
+            AuthorList::NotifiedUsers => (),
+        };
+        Ok(())
     }
 
     pub async fn save_exported_user(&self) -> miette::Result<()> {
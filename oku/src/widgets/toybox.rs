@@ -2,8 +2,10 @@ use crate::bookmark_item::BookmarkItem;
 use crate::database::policy::PolicyDecision;
 use crate::database::policy::PolicySetting;
 use crate::database::policy::PolicySettingRecord;
+use crate::okunet::items::post_item::PostItem;
 use crate::scheme_handlers::oku_path::OkuPath;
 use crate::widgets::bookmark_row::BookmarkRow;
+use crate::widgets::okunet::post_row::PostRow;
 use crate::NODE;
 use glib::clone;
 use glib::closure;
@@ -25,12 +27,77 @@ use libadwaita::prelude::PreferencesRowExt;
 use libadwaita::prelude::*;
 use libadwaita::subclass::dialog::AdwDialogImpl;
 use log::error;
+use oku_core::database::core::DATABASE as OKU_DATABASE;
 use oku_core::database::posts::core::OkuPost;
 use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::HashSet;
 use std::sync::LazyLock;
+use url::Url;
 use webkit2gtk::functions::uri_for_display;
 use webkit2gtk::prelude::WebViewExt;
 
+const SIMILAR_POST_LIMIT: usize = 10;
+
+/// The text of a web page, without its markup, scripts, or styles.
+fn page_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(tag_start) = rest.find('<') {
+        text.push_str(&rest[..tag_start]);
+        text.push(' ');
+        let tag = &rest[tag_start..];
+        let tag_name = tag[1..]
+            .chars()
+            .take_while(|x| x.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        let closing_tag = format!("</{tag_name}");
+        let tag_end = match tag_name.as_str() {
+            "script" | "style" => tag
+                .to_ascii_lowercase()
+                .find(&closing_tag)
+                .and_then(|x| tag[x..].find('>').map(|y| x + y)),
+            _ => tag.find('>'),
+        };
+        match tag_end {
+            Some(tag_end) => rest = &tag[tag_end + 1..],
+            None => {
+                rest = "";
+                break;
+            }
+        }
+    }
+    text.push_str(rest);
+    html_escape::decode_html_entities(&text).to_string()
+}
+
+/// The canonical URL a web page declares for itself, if it differs from the URL it was loaded from.
+fn canonical_url(html: &str, url: &Url) -> Option<Url> {
+    let lowercase_html = html.to_ascii_lowercase();
+    lowercase_html
+        .match_indices("<link")
+        .find_map(|(tag_start, _)| {
+            let tag_end = tag_start + lowercase_html[tag_start..].find('>')?;
+            let tag = &lowercase_html[tag_start..tag_end];
+            if !["rel=\"canonical\"", "rel='canonical'", "rel=canonical"]
+                .iter()
+                .any(|x| tag.contains(x))
+            {
+                return None;
+            }
+            // The link is read from the original markup, as URLs are case-sensitive
+            let href_start = tag_start + tag.find("href=")? + "href=".len();
+            let href = html[href_start..tag_end].trim_start();
+            let href = match href.chars().next()? {
+                quote @ ('"' | '\'') => href[1..].split(quote).next()?,
+                _ => href.split_whitespace().next()?,
+            };
+            let canonical_url = url.join(&html_escape::decode_html_entities(href)).ok()?;
+            (canonical_url != *url).then_some(canonical_url)
+        })
+}
+
 pub mod imp {
     use super::*;
 
@@ -50,6 +117,11 @@ pub mod imp {
         pub(crate) recommendation_carousel: libadwaita::Carousel,
         pub(crate) recommendation_carousel_indicators: libadwaita::CarouselIndicatorDots,
         pub(crate) no_recommendation_label: gtk::Label,
+        // Posts about this page
+        pub(crate) posts_about_box: gtk::Box,
+        pub(crate) posts_about_scrolled_window: gtk::ScrolledWindow,
+        pub(crate) posts_about_list: gtk::ListBox,
+        pub(crate) no_posts_about_label: gtk::Label,
         // OkuNet
         pub(crate) okunet_box: gtk::Box,
         pub(crate) okunet_refresh_button: gtk::Button,
@@ -167,7 +239,7 @@ impl Toybox {
                 let uri = &x.replacen("oku:", "", 1);
                 matches!(
                     OkuPath::parse(uri),
-                    Ok(OkuPath::Home) | Ok(OkuPath::User(_, _))
+                    Ok(OkuPath::Home(_)) | Ok(OkuPath::User(_, _))
                 )
             }))
             .bind(&imp.okunet_refresh_button, "visible", gtk::Widget::NONE);
@@ -180,10 +252,6 @@ impl Toybox {
                 .bind(&imp.okunet_refresh_button, "sensitive", gtk::Widget::NONE);
         };
 
-        this.setup_content();
-        this.setup_toolbox();
-        this.setup_recommendations(&window);
-
         let uri = window
             .and_then(|x| {
                 x.get_view()
@@ -194,6 +262,11 @@ impl Toybox {
             .unwrap_or("about:blank".into());
         this.set_property("uri", uri);
 
+        this.setup_content();
+        this.setup_toolbox();
+        this.setup_recommendations(&window);
+        this.setup_posts_about(&window);
+
         this.set_visible(true);
         this.present(window);
 
@@ -215,6 +288,12 @@ impl Toybox {
             "Suggestions",
             "explore2-symbolic",
         );
+        imp.view_stack.add_titled_with_icon(
+            &imp.posts_about_box,
+            Some("posts-about"),
+            "Network",
+            "people-symbolic",
+        );
 
         imp.view_switcher.set_stack(Some(&imp.view_stack));
         imp.view_switcher
@@ -234,35 +313,57 @@ impl Toybox {
     }
 
     pub fn add_similar(&self, window_opt: &Option<&super::window::Window>) {
-        if let Some(_node) = NODE.get() {
+        if let Some(node) = NODE.get() {
             if let Some(window_obj) = *window_opt {
                 let this = self.clone();
                 let window = window_obj.clone();
+                let uri = self.imp().uri.borrow().to_string();
                 tokio::spawn(async move {
-                    let _data = bytes::Bytes::from(window.get_data().await.unwrap_or_default());
-                    let similar_posts: Vec<OkuPost> = vec![]; // TODO: implement finding posts similar to current page
-                    if similar_posts.is_empty() {
-                        this.imp()
-                            .no_recommendation_label
-                            .set_label("No browsing suggestions … ");
-                        this.imp().no_recommendation_label.set_margin_top(24);
-                        this.imp().no_recommendation_label.set_margin_bottom(24);
-                        this.imp().no_recommendation_label.add_css_class("title-2");
-                        this.imp().recommendation_carousel.set_visible(false);
-                    } else {
-                        this.imp().no_recommendation_label.set_visible(false);
-                    }
-                    for post in similar_posts {
-                        let item = BookmarkItem::new(
-                            post.note.url.to_string(),
-                            post.note.title,
-                            post.note.body,
-                            post.note.tags,
-                            &window.favicon_database(),
-                        );
-                        let row = BookmarkRow::from(&item);
-                        this.imp().recommendation_carousel.append(&row);
-                    }
+                    let posted_about = Url::parse(&uri).is_ok_and(|url| {
+                        !OKU_DATABASE
+                            .get_posts_about(&url, &HashSet::new())
+                            .unwrap_or_default()
+                            .is_empty()
+                    });
+                    // Pages that have not been posted about are compared to posts by their text
+                    let url_or_text = match posted_about {
+                        true => uri,
+                        false => page_text(&String::from_utf8_lossy(
+                            &window.get_data().await.unwrap_or_default(),
+                        )),
+                    };
+                    let similar_posts: Vec<OkuPost> = node
+                        .similar_posts(&url_or_text, SIMILAR_POST_LIMIT)
+                        .await
+                        .unwrap_or_else(|e| {
+                            error!("{}", e);
+                            Vec::new()
+                        });
+                    let ctx = glib::MainContext::default();
+                    ctx.invoke(move || {
+                        let imp = this.imp();
+                        if similar_posts.is_empty() {
+                            imp.no_recommendation_label
+                                .set_label("No browsing suggestions … ");
+                            imp.no_recommendation_label.set_margin_top(24);
+                            imp.no_recommendation_label.set_margin_bottom(24);
+                            imp.no_recommendation_label.add_css_class("title-2");
+                            imp.recommendation_carousel.set_visible(false);
+                        } else {
+                            imp.no_recommendation_label.set_visible(false);
+                        }
+                        for post in similar_posts {
+                            let item = BookmarkItem::new(
+                                post.note.url.to_string(),
+                                post.note.title,
+                                post.note.body,
+                                post.note.tags,
+                                &window.favicon_database(),
+                            );
+                            let row = BookmarkRow::from(&item);
+                            imp.recommendation_carousel.append(&row);
+                        }
+                    });
                 });
             }
         }
@@ -283,6 +384,81 @@ impl Toybox {
         self.add_similar(window);
     }
 
+    pub fn add_posts_about(&self, window_opt: &Option<&super::window::Window>) {
+        if let Some(node) = NODE.get() {
+            if let Some(window_obj) = *window_opt {
+                let this = self.clone();
+                let window = window_obj.clone();
+                let uri = window
+                    .get_view()
+                    .ok()
+                    .and_then(|x| x.uri())
+                    .map(|x| x.to_string())
+                    .unwrap_or_default();
+                tokio::spawn(async move {
+                    let mut urls: Vec<_> = Url::parse(&uri).into_iter().collect();
+                    let html =
+                        String::from_utf8_lossy(&window.get_data().await.unwrap_or_default())
+                            .to_string();
+                    if let Some(canonical_url) = urls.first().and_then(|x| canonical_url(&html, x))
+                    {
+                        urls.push(canonical_url);
+                    }
+                    let mut posts: Vec<OkuPost> = Vec::new();
+                    for url in urls {
+                        posts.extend(node.posts_about(&url).await.unwrap_or_else(|e| {
+                            error!("{}", e);
+                            Vec::new()
+                        }));
+                    }
+                    posts.sort_by_key(|x| Reverse(x.entry.timestamp()));
+                    posts.dedup();
+                    let ctx = glib::MainContext::default();
+                    ctx.invoke(move || {
+                        let imp = this.imp();
+                        imp.no_posts_about_label.set_visible(posts.is_empty());
+                        imp.posts_about_scrolled_window
+                            .set_visible(!posts.is_empty());
+                        for post in posts {
+                            let row = PostRow::from(&PostItem::from(post));
+                            imp.posts_about_list.append(&row);
+                        }
+                    });
+                });
+            }
+        }
+    }
+
+    pub fn setup_posts_about(&self, window: &Option<&super::window::Window>) {
+        let imp = self.imp();
+
+        imp.no_posts_about_label
+            .set_label("No posts from your network about this page … ");
+        imp.no_posts_about_label.set_wrap(true);
+        imp.no_posts_about_label.set_margin_top(24);
+        imp.no_posts_about_label.set_margin_bottom(24);
+        imp.no_posts_about_label.add_css_class("title-2");
+        imp.posts_about_list
+            .set_selection_mode(gtk::SelectionMode::None);
+        imp.posts_about_list.add_css_class("boxed-list-separate");
+        imp.posts_about_scrolled_window
+            .set_child(Some(&imp.posts_about_list));
+        imp.posts_about_scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.posts_about_scrolled_window
+            .set_propagate_natural_width(true);
+        imp.posts_about_scrolled_window
+            .set_propagate_natural_height(true);
+        imp.posts_about_scrolled_window.set_max_content_width(300);
+        imp.posts_about_scrolled_window.set_max_content_height(400);
+        imp.posts_about_scrolled_window.set_visible(false);
+        imp.posts_about_box.append(&imp.no_posts_about_label);
+        imp.posts_about_box.append(&imp.posts_about_scrolled_window);
+        imp.posts_about_box
+            .set_orientation(gtk::Orientation::Vertical);
+        self.add_posts_about(window);
+    }
+
     pub fn setup_toolbox(&self) {
         let imp = self.imp();
 
@@ -335,11 +511,14 @@ impl Toybox {
             let uri = &*self.imp().uri.borrow().replacen("oku:", "", 1);
             let parsed_uri = OkuPath::parse(uri);
             match parsed_uri {
-                Ok(OkuPath::Home) => {
-                    if let Err(e) = node.fetch_users().await {
-                        error!("{}", e);
+                Ok(OkuPath::Home(_)) => match node.fetch_users().await {
+                    Ok(report) => {
+                        for (author_id, e) in report.failures {
+                            error!("{}: {}", oku_core::fs::util::fmt(author_id), e);
+                        }
                     }
-                }
+                    Err(e) => error!("{}", e),
+                },
                 Ok(OkuPath::User(author_id, None)) => {
                     if let Err(e) = node.fetch_user(&author_id).await {
                         error!("{}", e);
@@ -0,0 +1,173 @@
+use super::*;
+use crate::bookmark_item::BookmarkItem;
+use crate::database::{Bookmark, DATABASE};
+use crate::NODE;
+use glib::clone;
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use libadwaita::prelude::*;
+use log::error;
+use oku_core::database::posts::core::OkuNote;
+use oku_core::fs::net::posts::OkuNoteConflict;
+use std::collections::HashSet;
+use webkit2gtk::functions::uri_for_display;
+
+impl Window {
+    pub fn bookmarks_publish_conflict(&self) -> OkuNoteConflict {
+        match self.imp().bookmarks_publish_conflict_row.selected() {
+            1 => OkuNoteConflict::Replace,
+            2 => OkuNoteConflict::Merge,
+            _ => OkuNoteConflict::Skip,
+        }
+    }
+
+    pub fn selected_bookmarks(&self) -> Vec<Bookmark> {
+        // Bookmark items hold URLs as they are displayed, so they are matched against bookmarks the same way
+        let selected_urls: HashSet<String> = self
+            .bookmarks_store()
+            .snapshot()
+            .into_iter()
+            .filter_map(|x| x.downcast::<BookmarkItem>().ok())
+            .filter(|x| x.selected())
+            .map(|x| x.url())
+            .collect();
+        DATABASE
+            .get_bookmarks()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| {
+                selected_urls.contains(
+                    &html_escape::encode_text(
+                        &uri_for_display(&x.url).unwrap_or(x.url.clone().into()),
+                    )
+                    .to_string(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn unselect_bookmarks(&self) {
+        for bookmark_item in self
+            .bookmarks_store()
+            .snapshot()
+            .into_iter()
+            .filter_map(|x| x.downcast::<BookmarkItem>().ok())
+        {
+            bookmark_item.set_property("selected", false);
+        }
+    }
+
+    pub fn publish_bookmarks(
+        &self,
+        bookmarks: Vec<Bookmark>,
+        conflict: OkuNoteConflict,
+    ) -> miette::Result<()> {
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("No running Oku node … "))?;
+        let app = self
+            .application()
+            .ok_or(miette::miette!("No running application … "))?;
+        let notes: Vec<_> = bookmarks
+            .into_iter()
+            .filter_map(|x| match OkuNote::try_from(x) {
+                Ok(note) => Some(note),
+                Err(e) => {
+                    error!("{}", e);
+                    None
+                }
+            })
+            .collect();
+        if notes.is_empty() {
+            return Err(miette::miette!("No bookmarks to publish … "));
+        }
+        let ctx = glib::MainContext::default();
+        ctx.spawn_local_with_priority(glib::source::Priority::HIGH, async move {
+            match node.publish_notes(notes, conflict).await {
+                Ok(report) => {
+                    for (url, e) in report.failures.iter() {
+                        error!("{}: {}", url, e);
+                    }
+                    let notification = gio::Notification::new("Bookmarks published");
+                    notification.set_body(Some(&format!(
+                        "{} new posts, {} updated, {} already posted, {} failed.",
+                        report.created.len(),
+                        report.updated.len(),
+                        report.skipped.len(),
+                        report.failures.len()
+                    )));
+                    app.send_notification(None, &notification);
+                }
+                Err(e) => error!("{}", e),
+            }
+        });
+        Ok(())
+    }
+
+    pub fn setup_bookmarks_publishing(&self) {
+        let imp = self.imp();
+
+        imp.bookmarks_publish_conflict_row
+            .set_title("If already posted");
+        imp.bookmarks_publish_conflict_row
+            .set_model(Some(&gtk::StringList::new(&[
+                "Keep the post",
+                "Replace the post",
+                "Merge into the post",
+            ])));
+
+        imp.bookmarks_publish_tag_entry
+            .set_title("Publish bookmarks tagged …");
+        imp.bookmarks_publish_tag_entry.set_show_apply_button(true);
+        imp.bookmarks_publish_tag_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |bookmarks_publish_tag_entry| {
+                let tag = bookmarks_publish_tag_entry.text().trim().to_string();
+                let bookmarks = DATABASE
+                    .get_bookmarks()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|x| x.tags.contains(&tag))
+                    .collect();
+                match this.publish_bookmarks(bookmarks, this.bookmarks_publish_conflict()) {
+                    Ok(_) => bookmarks_publish_tag_entry.set_text(""),
+                    Err(e) => error!("{}", e),
+                }
+            }
+        ));
+
+        imp.bookmarks_publish_selected_row
+            .set_title("Publish selected bookmarks");
+        imp.bookmarks_publish_selected_row
+            .set_start_icon_name(Some("document-send-symbolic"));
+        imp.bookmarks_publish_selected_row.connect_activated(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |_| match this
+                .publish_bookmarks(this.selected_bookmarks(), this.bookmarks_publish_conflict())
+            {
+                Ok(_) => this.unselect_bookmarks(),
+                Err(e) => error!("{}", e),
+            }
+        ));
+
+        imp.bookmarks_publish_expander
+            .set_title("Publish to OkuNet");
+        imp.bookmarks_publish_expander
+            .set_subtitle("Post bookmarks by tag or selection");
+        imp.bookmarks_publish_expander
+            .add_row(&imp.bookmarks_publish_conflict_row);
+        imp.bookmarks_publish_expander
+            .add_row(&imp.bookmarks_publish_tag_entry);
+        imp.bookmarks_publish_expander
+            .add_row(&imp.bookmarks_publish_selected_row);
+        imp.bookmarks_publish_list_box
+            .append(&imp.bookmarks_publish_expander);
+        imp.bookmarks_publish_list_box
+            .set_selection_mode(gtk::SelectionMode::None);
+        imp.bookmarks_publish_list_box.add_css_class("boxed-list");
+        imp.bookmarks_publish_list_box.set_margin_start(8);
+        imp.bookmarks_publish_list_box.set_margin_end(8);
+    }
+}
@@ -32,6 +32,10 @@ pub mod imp {
         pub(crate) bookmarks_sidebar_initialised: Cell<bool>,
         pub(crate) history_sidebar_initialised: Cell<bool>,
         pub(crate) replicas_sidebar_initialised: Cell<bool>,
+        pub(crate) drafts_sidebar_initialised: Cell<bool>,
+        pub(crate) notifications_sidebar_initialised: Cell<bool>,
+        pub(crate) messages_sidebar_initialised: Cell<bool>,
+        pub(crate) feeds_sidebar_initialised: Cell<bool>,
         pub(crate) style_provider: RefCell<gtk::CssProvider>,
         // OkuNet fetch overlay
         pub(crate) okunet_fetch_overlay_box: gtk::Box,
@@ -133,6 +137,11 @@ pub mod imp {
         pub(crate) bookmarks_search_scrolled_window: gtk::ScrolledWindow,
         pub(crate) bookmarks_search_box: gtk::Box,
         pub(crate) bookmarks_search_placeholder: gtk::Label,
+        pub(crate) bookmarks_publish_list_box: gtk::ListBox,
+        pub(crate) bookmarks_publish_expander: libadwaita::ExpanderRow,
+        pub(crate) bookmarks_publish_conflict_row: libadwaita::ComboRow,
+        pub(crate) bookmarks_publish_tag_entry: libadwaita::EntryRow,
+        pub(crate) bookmarks_publish_selected_row: libadwaita::ButtonRow,
         // History
         pub(crate) history_box: gtk::Box,
         pub(crate) history_store: RefCell<Option<Rc<gio::ListStore>>>,
@@ -163,6 +172,48 @@ pub mod imp {
         pub(crate) replicas_scrolled_window: gtk::ScrolledWindow,
         pub(crate) replicas_label: gtk::Label,
         pub(crate) replicas_placeholder: gtk::Label,
+        // Drafts
+        pub(crate) drafts_box: gtk::Box,
+        pub(crate) drafts_store: RefCell<Option<Rc<gio::ListStore>>>,
+        pub(crate) drafts_factory: gtk::SignalListItemFactory,
+        pub(crate) drafts_model: gtk::SingleSelection,
+        pub(crate) drafts_view: gtk::ListView,
+        pub(crate) drafts_scrolled_window: gtk::ScrolledWindow,
+        pub(crate) drafts_label: gtk::Label,
+        pub(crate) drafts_placeholder: gtk::Label,
+        // Notifications
+        pub(crate) notifications_box: gtk::Box,
+        pub(crate) notifications_store: RefCell<Option<Rc<gio::ListStore>>>,
+        pub(crate) notifications_factory: gtk::SignalListItemFactory,
+        pub(crate) notifications_model: gtk::SingleSelection,
+        pub(crate) notifications_view: gtk::ListView,
+        pub(crate) notifications_scrolled_window: gtk::ScrolledWindow,
+        pub(crate) notifications_label: gtk::Label,
+        pub(crate) notifications_read_button: gtk::Button,
+        pub(crate) notifications_placeholder: gtk::Label,
+        // Messages
+        pub(crate) messages_box: gtk::Box,
+        pub(crate) messages_store: RefCell<Option<Rc<gio::ListStore>>>,
+        pub(crate) messages_factory: gtk::SignalListItemFactory,
+        pub(crate) messages_model: gtk::SingleSelection,
+        pub(crate) messages_view: gtk::ListView,
+        pub(crate) messages_scrolled_window: gtk::ScrolledWindow,
+        pub(crate) messages_label: gtk::Label,
+        pub(crate) messages_entry_list_box: gtk::ListBox,
+        pub(crate) messages_entry: libadwaita::EntryRow,
+        pub(crate) messages_placeholder: gtk::Label,
+        // Feeds
+        pub(crate) feeds_box: gtk::Box,
+        pub(crate) feeds_store: RefCell<Option<Rc<gio::ListStore>>>,
+        pub(crate) feeds_factory: gtk::SignalListItemFactory,
+        pub(crate) feeds_model: gtk::SingleSelection,
+        pub(crate) feeds_view: gtk::ListView,
+        pub(crate) feeds_scrolled_window: gtk::ScrolledWindow,
+        pub(crate) feeds_label: gtk::Label,
+        pub(crate) feeds_entry_list_box: gtk::ListBox,
+        pub(crate) feeds_entry: libadwaita::EntryRow,
+        pub(crate) feeds_import_entry: libadwaita::EntryRow,
+        pub(crate) feeds_placeholder: gtk::Label,
         // Downloads
         pub(crate) downloads_box: gtk::Box,
         pub(crate) downloads_store: RefCell<Option<Rc<gio::ListStore>>>,
@@ -730,6 +781,26 @@ impl Window {
         }
     }
 
+    pub async fn watch_drafts(&self) {
+        if let Some(node) = NODE.get() {
+            self.imp().drafts_sidebar_initialised.set(true);
+            let mut draft_rx = node.okunet_draft_sender.subscribe();
+            loop {
+                draft_rx.borrow_and_update();
+                info!("Drafts updated … ");
+                let this = self.clone();
+                tokio::spawn(async move { this.drafts_updated().await });
+                match draft_rx.changed().await {
+                    Ok(_) => continue,
+                    Err(e) => {
+                        error!("{}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
     pub fn setup_okunet_fetch_overlay(&self) {
         let imp = self.imp();
 
@@ -839,6 +910,14 @@ impl Window {
         let this = self.clone();
         tokio::spawn(async move { this.watch_replicas().await });
         let this = self.clone();
+        tokio::spawn(async move { this.watch_drafts().await });
+        let this = self.clone();
+        tokio::spawn(async move { this.watch_notifications().await });
+        let this = self.clone();
+        tokio::spawn(async move { this.watch_messages().await });
+        let this = self.clone();
+        tokio::spawn(async move { this.watch_feeds().await });
+        let this = self.clone();
         tokio::spawn(async move { this.watch_okunet_fetch().await });
     }
 
@@ -0,0 +1,291 @@
+use super::*;
+use crate::database::{Feed, DATABASE};
+use crate::okunet::items::feed_item::FeedItem;
+use crate::scheme_handlers::oku_path::OkuPath;
+use crate::window_util::get_view_stack_page_by_name;
+use crate::NODE;
+use glib::{clone, closure, Object};
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use libadwaita::prelude::*;
+use log::{error, info};
+use oku_core::database::posts::search::OkuPostSearch;
+use std::cell::Ref;
+use std::rc::Rc;
+use webkit2gtk::functions::uri_for_display;
+use webkit2gtk::prelude::WebViewExt;
+use webkit2gtk::WebContext;
+
+impl Window {
+    pub fn feeds_store(&self) -> Ref<'_, gio::ListStore> {
+        let feeds_store = self.imp().feeds_store.borrow();
+
+        Ref::map(feeds_store, |feeds_store| {
+            let feeds_store = feeds_store.as_deref().unwrap();
+            feeds_store
+        })
+    }
+
+    pub async fn feeds_updated(&self) {
+        if let Some(node) = NODE.get() {
+            let mut feeds = Vec::new();
+            for feed in DATABASE.get_feeds().unwrap_or_default() {
+                let unread = match node.count_posts(&feed.unread_search()).await {
+                    Ok(unread) => unread,
+                    Err(e) => {
+                        error!("{}", e);
+                        0
+                    }
+                };
+                feeds.push((feed, unread));
+            }
+            let ctx = glib::MainContext::default();
+            let this = self.clone();
+            ctx.invoke(move || {
+                let imp = this.imp();
+                let feeds_store = this.feeds_store();
+                let old_store = feeds_store.snapshot();
+                feeds_store.remove_all();
+                for (feed, unread) in feeds.iter() {
+                    feeds_store.append(&FeedItem::new(feed, *unread));
+                }
+
+                let items_changed =
+                    imp.feeds_sidebar_initialised.get() && old_store != feeds_store.snapshot();
+                if let Some(feeds_page) =
+                    get_view_stack_page_by_name("feeds".to_string(), &imp.side_view_stack)
+                {
+                    if matches!(get_view_stack_page_by_name(
+                        imp.side_view_stack
+                            .visible_child_name()
+                            .unwrap_or_default()
+                            .to_string(),
+                            &imp.side_view_stack,
+                    ), Some(x) if x == feeds_page)
+                    {
+                        feeds_page.set_needs_attention(
+                            feeds_page.needs_attention()
+                                || (items_changed && feeds.iter().any(|x| x.1 > 0)),
+                        );
+                    }
+                }
+            });
+        }
+    }
+
+    pub async fn watch_feeds(&self) {
+        if let Some(node) = NODE.get() {
+            self.imp().feeds_sidebar_initialised.set(true);
+            let mut feed_rx = DATABASE.feed_sender.subscribe();
+            let mut post_rx = node.okunet_post_sender.subscribe();
+            loop {
+                feed_rx.borrow_and_update();
+                post_rx.borrow_and_update();
+                info!("Feeds updated … ");
+                let this = self.clone();
+                tokio::spawn(async move { this.feeds_updated().await });
+                let changed = tokio::select! {
+                    changed = feed_rx.changed() => changed,
+                    changed = post_rx.changed() => changed,
+                };
+                if let Err(e) = changed {
+                    error!("{}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Save the page in the current tab as a feed, if it is an OkuNet search, tag, or user page.
+    pub fn save_current_page_as_feed(&self, name: String) -> miette::Result<()> {
+        let uri = self.get_view()?.uri().unwrap_or_default().to_string();
+        let uri = uri_for_display(&uri).map(|x| x.to_string()).unwrap_or(uri);
+        let search = match uri.strip_prefix("oku:").map(OkuPath::parse).transpose()? {
+            Some(OkuPath::Search(search)) => search,
+            Some(OkuPath::Tag(tag)) => OkuPostSearch {
+                tags: [tag].into(),
+                ..Default::default()
+            },
+            Some(OkuPath::User(author_id, None)) => OkuPostSearch {
+                authors: [author_id].into(),
+                ..Default::default()
+            },
+            _ => {
+                return Err(miette::miette!(
+                    "Only OkuNet searches, tags, and users can be saved as feeds … "
+                ))
+            }
+        };
+        if DATABASE.get_feed(name.clone())?.is_some() {
+            return Err(miette::miette!("A feed named {:?} already exists … ", name));
+        }
+        DATABASE.upsert_feed(Feed::new(name, &search))?;
+        Ok(())
+    }
+
+    /// Import an RSS or Atom feed, or the page in the current tab if no source is given.
+    pub fn import_external_feed(&self, source: String) -> miette::Result<()> {
+        let node = NODE
+            .get()
+            .ok_or(miette::miette!("No running Oku node … "))?;
+        let source = match source.is_empty() {
+            true => self.get_view()?.uri().unwrap_or_default().to_string(),
+            false => source,
+        };
+        tokio::spawn(async move {
+            match node.import_external_feed(&source).await {
+                Ok((feed, item_count)) => {
+                    info!("Imported {} items from {} … ", item_count, feed.title)
+                }
+                Err(e) => error!("{}", e),
+            }
+        });
+        Ok(())
+    }
+
+    pub fn setup_feeds_page(&self, web_context: &WebContext) {
+        let imp = self.imp();
+
+        let feeds_store = gio::ListStore::new::<FeedItem>();
+        imp.feeds_store.replace(Some(Rc::new(feeds_store)));
+
+        imp.feeds_model.set_model(Some(&self.feeds_store().clone()));
+        imp.feeds_model.set_autoselect(false);
+        imp.feeds_model.set_can_unselect(true);
+        imp.feeds_model.connect_selected_item_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            #[weak]
+            web_context,
+            move |feeds_model| {
+                if let Some(item) = feeds_model.selected_item() {
+                    let feed_item = item.downcast_ref::<FeedItem>().unwrap();
+                    let new_view = this
+                        .new_tab(&Some(&NewTabArguments::Web(&NewWebTabArguments {
+                            web_context: &web_context,
+                            related_view: None,
+                            initial_request: None,
+                        })))
+                        .as_web()
+                        .expect("New tab to be Web tab")
+                        .web_view
+                        .clone();
+                    new_view.load_uri(&format!("oku:{}", OkuPath::feed_path(&feed_item.name(), 1)));
+                    feeds_model.unselect_all();
+                }
+            }
+        ));
+
+        imp.feeds_factory.connect_setup(clone!(move |_, item| {
+            let row = libadwaita::ActionRow::new();
+            row.set_subtitle_lines(1);
+            row.set_activatable(true);
+            let list_item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            let delete_button = gtk::Button::from_icon_name("user-trash-symbolic");
+            delete_button.set_tooltip_text(Some("Delete feed"));
+            delete_button.set_valign(gtk::Align::Center);
+            delete_button.add_css_class("flat");
+            delete_button.connect_clicked(clone!(
+                #[weak]
+                list_item,
+                move |_| {
+                    if let Some(feed_item) = list_item.item().and_downcast::<FeedItem>() {
+                        if let Err(e) = DATABASE.delete_feed(feed_item.name()) {
+                            error!("{}", e);
+                        }
+                    }
+                }
+            ));
+            row.add_suffix(&delete_button);
+            list_item.set_child(Some(&row));
+            list_item
+                .property_expression("item")
+                .chain_property::<FeedItem>("title")
+                .bind(&row, "title", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<FeedItem>("description")
+                .bind(&row, "subtitle", gtk::Widget::NONE);
+        }));
+
+        imp.feeds_view.set_model(Some(&imp.feeds_model));
+        imp.feeds_view.set_factory(Some(&imp.feeds_factory));
+        imp.feeds_view.set_enable_rubberband(false);
+        imp.feeds_view
+            .set_hscroll_policy(gtk::ScrollablePolicy::Minimum);
+        imp.feeds_view
+            .set_vscroll_policy(gtk::ScrollablePolicy::Natural);
+        imp.feeds_view.set_vexpand(true);
+        imp.feeds_view.add_css_class("boxed-list-separate");
+        imp.feeds_view.add_css_class("navigation-sidebar");
+
+        imp.feeds_scrolled_window.set_child(Some(&imp.feeds_view));
+        imp.feeds_scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.feeds_scrolled_window.set_propagate_natural_height(true);
+        imp.feeds_scrolled_window.set_propagate_natural_width(true);
+        self.feeds_store()
+            .property_expression("n-items")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: u32| { x == 0 }))
+            .bind(&imp.feeds_placeholder, "visible", gtk::Widget::NONE);
+        imp.feeds_placeholder
+            .property_expression("visible")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: bool| { !x }))
+            .bind(&imp.feeds_scrolled_window, "visible", gtk::Widget::NONE);
+
+        imp.feeds_label.set_label("Feeds");
+        imp.feeds_label.set_margin_top(24);
+        imp.feeds_label.set_margin_bottom(24);
+        imp.feeds_label.add_css_class("title-1");
+        imp.feeds_placeholder.set_label("No feeds … ");
+        imp.feeds_placeholder.set_margin_top(24);
+        imp.feeds_placeholder.set_margin_bottom(24);
+        imp.feeds_placeholder.add_css_class("title-2");
+
+        imp.feeds_entry
+            .set_title("Save the current search as a feed named …");
+        imp.feeds_entry.set_show_apply_button(true);
+        imp.feeds_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |feeds_entry| {
+                match this.save_current_page_as_feed(feeds_entry.text().trim().to_string()) {
+                    Ok(_) => feeds_entry.set_text(""),
+                    Err(e) => error!("{}", e),
+                }
+            }
+        ));
+        imp.feeds_import_entry
+            .set_title("Import an RSS or Atom feed from …");
+        imp.feeds_import_entry.set_show_apply_button(true);
+        imp.feeds_import_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |feeds_import_entry| {
+                match this.import_external_feed(feeds_import_entry.text().trim().to_string()) {
+                    Ok(_) => feeds_import_entry.set_text(""),
+                    Err(e) => error!("{}", e),
+                }
+            }
+        ));
+        imp.feeds_entry_list_box.append(&imp.feeds_entry);
+        imp.feeds_entry_list_box.append(&imp.feeds_import_entry);
+        imp.feeds_entry_list_box.add_css_class("boxed-list");
+        imp.feeds_entry_list_box.set_margin_start(8);
+        imp.feeds_entry_list_box.set_margin_end(8);
+
+        imp.feeds_box.set_orientation(gtk::Orientation::Vertical);
+        imp.feeds_box.set_spacing(4);
+        imp.feeds_box.append(&imp.feeds_label);
+        imp.feeds_box.append(&imp.feeds_entry_list_box);
+        imp.feeds_box.append(&imp.feeds_placeholder);
+        imp.feeds_box.append(&imp.feeds_scrolled_window);
+
+        imp.side_view_stack.add_titled_with_icon(
+            &imp.feeds_box,
+            Some("feeds"),
+            "Feeds",
+            "application-rss+xml-symbolic",
+        );
+    }
+}
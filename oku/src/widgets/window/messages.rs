@@ -0,0 +1,202 @@
+use super::*;
+use crate::okunet::items::message_item::MessageItem;
+use crate::window_util::get_view_stack_page_by_name;
+use crate::{widgets, NODE};
+use glib::{clone, closure, Object};
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use libadwaita::prelude::*;
+use log::{error, info};
+use oku_core::iroh_docs::AuthorId;
+use std::cell::Ref;
+use std::rc::Rc;
+
+impl Window {
+    pub fn messages_store(&self) -> Ref<'_, gio::ListStore> {
+        let messages_store = self.imp().messages_store.borrow();
+
+        Ref::map(messages_store, |messages_store| {
+            let messages_store = messages_store.as_deref().unwrap();
+            messages_store
+        })
+    }
+
+    pub async fn messages_updated(&self) {
+        if let Some(node) = NODE.get() {
+            let me = node.default_author().await;
+            let conversations = node.conversations().await.unwrap_or_default();
+            let ctx = glib::MainContext::default();
+            let this = self.clone();
+            ctx.invoke(move || {
+                let imp = this.imp();
+                let messages_store = this.messages_store();
+                let old_store = messages_store.snapshot();
+                messages_store.remove_all();
+                for message in conversations.iter() {
+                    messages_store.append(&MessageItem::new(message, &me));
+                }
+
+                let items_changed = imp.messages_sidebar_initialised.get()
+                    && old_store != messages_store.snapshot();
+                if let Some(messages_page) =
+                    get_view_stack_page_by_name("messages".to_string(), &imp.side_view_stack)
+                {
+                    if matches!(get_view_stack_page_by_name(
+                        imp.side_view_stack
+                            .visible_child_name()
+                            .unwrap_or_default()
+                            .to_string(),
+                            &imp.side_view_stack,
+                    ), Some(x) if x == messages_page)
+                    {
+                        messages_page
+                            .set_needs_attention(messages_page.needs_attention() || items_changed);
+                    }
+                }
+            });
+        }
+    }
+
+    pub async fn watch_messages(&self) {
+        if let Some(node) = NODE.get() {
+            self.imp().messages_sidebar_initialised.set(true);
+            let mut message_rx = node.okunet_message_sender.subscribe();
+            loop {
+                message_rx.borrow_and_update();
+                info!("Messages updated … ");
+                let this = self.clone();
+                tokio::spawn(async move { this.messages_updated().await });
+                match message_rx.changed().await {
+                    Ok(_) => continue,
+                    Err(e) => {
+                        error!("{}", e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn setup_messages_page(&self) {
+        let imp = self.imp();
+
+        let messages_store = gio::ListStore::new::<MessageItem>();
+        imp.messages_store.replace(Some(Rc::new(messages_store)));
+
+        imp.messages_model
+            .set_model(Some(&self.messages_store().clone()));
+        imp.messages_model.set_autoselect(false);
+        imp.messages_model.set_can_unselect(true);
+        imp.messages_model.connect_selected_item_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |messages_model| {
+                if let Some(item) = messages_model.selected_item() {
+                    let message_item = item.downcast_ref::<MessageItem>().unwrap();
+                    match oku_core::fs::util::parse_array_hex_or_base32(&message_item.author_id())
+                        .map(AuthorId::from)
+                    {
+                        Ok(author_id) => {
+                            widgets::conversation::ConversationDialog::new(Some(&this), author_id);
+                        }
+                        Err(e) => error!("{}", e),
+                    }
+                    messages_model.unselect_all();
+                }
+            }
+        ));
+
+        imp.messages_factory.connect_setup(clone!(move |_, item| {
+            let row = libadwaita::ActionRow::new();
+            row.set_subtitle_lines(1);
+            row.set_activatable(true);
+            let list_item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            list_item.set_child(Some(&row));
+            list_item
+                .property_expression("item")
+                .chain_property::<MessageItem>("name")
+                .bind(&row, "title", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<MessageItem>("body")
+                .bind(&row, "subtitle", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<MessageItem>("timestamp")
+                .bind(&row, "tooltip-text", gtk::Widget::NONE);
+        }));
+
+        imp.messages_view.set_model(Some(&imp.messages_model));
+        imp.messages_view.set_factory(Some(&imp.messages_factory));
+        imp.messages_view.set_enable_rubberband(false);
+        imp.messages_view
+            .set_hscroll_policy(gtk::ScrollablePolicy::Minimum);
+        imp.messages_view
+            .set_vscroll_policy(gtk::ScrollablePolicy::Natural);
+        imp.messages_view.set_vexpand(true);
+        imp.messages_view.add_css_class("boxed-list-separate");
+        imp.messages_view.add_css_class("navigation-sidebar");
+
+        imp.messages_scrolled_window
+            .set_child(Some(&imp.messages_view));
+        imp.messages_scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.messages_scrolled_window
+            .set_propagate_natural_height(true);
+        imp.messages_scrolled_window
+            .set_propagate_natural_width(true);
+        self.messages_store()
+            .property_expression("n-items")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: u32| { x == 0 }))
+            .bind(&imp.messages_placeholder, "visible", gtk::Widget::NONE);
+        imp.messages_placeholder
+            .property_expression("visible")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: bool| { !x }))
+            .bind(&imp.messages_scrolled_window, "visible", gtk::Widget::NONE);
+
+        imp.messages_label.set_label("Messages");
+        imp.messages_label.set_margin_top(24);
+        imp.messages_label.set_margin_bottom(24);
+        imp.messages_label.add_css_class("title-1");
+        imp.messages_placeholder.set_label("No messages … ");
+        imp.messages_placeholder.set_margin_top(24);
+        imp.messages_placeholder.set_margin_bottom(24);
+        imp.messages_placeholder.add_css_class("title-2");
+
+        imp.messages_entry.set_title("Message an author ID");
+        imp.messages_entry.set_show_apply_button(true);
+        imp.messages_entry.connect_apply(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |messages_entry| {
+                match oku_core::fs::util::parse_array_hex_or_base32(messages_entry.text().trim())
+                    .map(AuthorId::from)
+                {
+                    Ok(author_id) => {
+                        messages_entry.set_text("");
+                        widgets::conversation::ConversationDialog::new(Some(&this), author_id);
+                    }
+                    Err(e) => error!("{}", e),
+                }
+            }
+        ));
+        imp.messages_entry_list_box.append(&imp.messages_entry);
+        imp.messages_entry_list_box.add_css_class("boxed-list");
+        imp.messages_entry_list_box.set_margin_start(8);
+        imp.messages_entry_list_box.set_margin_end(8);
+
+        imp.messages_box.set_orientation(gtk::Orientation::Vertical);
+        imp.messages_box.set_spacing(4);
+        imp.messages_box.append(&imp.messages_label);
+        imp.messages_box.append(&imp.messages_entry_list_box);
+        imp.messages_box.append(&imp.messages_placeholder);
+        imp.messages_box.append(&imp.messages_scrolled_window);
+
+        imp.side_view_stack.add_titled_with_icon(
+            &imp.messages_box,
+            Some("messages"),
+            "Messages",
+            "mail-message-new-symbolic",
+        );
+    }
+}
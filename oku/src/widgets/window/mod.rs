@@ -1,17 +1,23 @@
+mod bookmarks;
 mod colour;
 mod core;
 mod dialogs;
 mod downloads;
+mod feeds;
 mod finding;
 mod headerbar;
 mod menu;
+mod messages;
 mod navigation;
 mod note;
+mod notifications;
 mod sidebar;
 mod suggestions;
 mod tabs;
 mod view;
 #[allow(unused_imports)]
+pub use self::bookmarks::*;
+#[allow(unused_imports)]
 pub use self::colour::*;
 #[allow(unused_imports)]
 pub use self::core::*;
@@ -20,16 +26,22 @@ pub use self::dialogs::*;
 #[allow(unused_imports)]
 pub use self::downloads::*;
 #[allow(unused_imports)]
+pub use self::feeds::*;
+#[allow(unused_imports)]
 pub use self::finding::*;
 #[allow(unused_imports)]
 pub use self::headerbar::*;
 #[allow(unused_imports)]
 pub use self::menu::*;
 #[allow(unused_imports)]
+pub use self::messages::*;
+#[allow(unused_imports)]
 pub use self::navigation::*;
 #[allow(unused_imports)]
 pub use self::note::*;
 #[allow(unused_imports)]
+pub use self::notifications::*;
+#[allow(unused_imports)]
 pub use self::sidebar::*;
 #[allow(unused_imports)]
 pub use self::suggestions::*;
@@ -13,7 +13,7 @@ impl Window {
             #[weak(rename_to = this)]
             self,
             move |_| {
-                widgets::note_editor::NoteEditor::new(Some(&this), None);
+                widgets::note_editor::NoteEditor::new(Some(&this), None, None);
             }
         ));
     }
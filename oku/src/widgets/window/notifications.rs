@@ -0,0 +1,215 @@
+use super::*;
+use crate::database::DATABASE;
+use crate::notification_item::NotificationItem;
+use crate::okunet::notifications::OPEN_NOTIFICATION_ACTION;
+use crate::window_util::get_view_stack_page_by_name;
+use glib::{clone, closure, Object};
+use gtk::subclass::prelude::*;
+use gtk::{gio, glib};
+use libadwaita::prelude::*;
+use log::{error, info};
+use std::cell::Ref;
+use std::rc::Rc;
+
+impl Window {
+    pub fn notifications_store(&self) -> Ref<'_, gio::ListStore> {
+        let notifications_store = self.imp().notifications_store.borrow();
+
+        Ref::map(notifications_store, |notifications_store| {
+            let notifications_store = notifications_store.as_deref().unwrap();
+            notifications_store
+        })
+    }
+
+    pub fn notifications_updated(&self) {
+        let notifications = DATABASE.get_notifications().unwrap_or_default();
+        let ctx = glib::MainContext::default();
+        let this = self.clone();
+        ctx.invoke(move || {
+            let imp = this.imp();
+            let notifications_store = this.notifications_store();
+            let had_unread = notifications_store
+                .snapshot()
+                .iter()
+                .filter_map(|x| x.clone().downcast::<NotificationItem>().ok())
+                .filter(|x| !x.read())
+                .count() as u32;
+            notifications_store.remove_all();
+            let mut unread = 0_u32;
+            for notification in notifications.iter() {
+                if !notification.read {
+                    unread += 1;
+                }
+                notifications_store.append(&NotificationItem::new(notification));
+            }
+            imp.notifications_read_button.set_sensitive(unread > 0);
+
+            let items_changed = imp.notifications_sidebar_initialised.get() && unread > had_unread;
+            if let Some(notifications_page) =
+                get_view_stack_page_by_name("notifications".to_string(), &imp.side_view_stack)
+            {
+                notifications_page.set_badge_number(unread);
+                if matches!(get_view_stack_page_by_name(
+                    imp.side_view_stack
+                        .visible_child_name()
+                        .unwrap_or_default()
+                        .to_string(),
+                        &imp.side_view_stack,
+                ), Some(x) if x == notifications_page)
+                {
+                    notifications_page
+                        .set_needs_attention(notifications_page.needs_attention() || items_changed);
+                }
+            }
+        });
+    }
+
+    pub async fn watch_notifications(&self) {
+        self.imp().notifications_sidebar_initialised.set(true);
+        let mut notification_rx = DATABASE.notification_sender.subscribe();
+        loop {
+            notification_rx.borrow_and_update();
+            info!("Notifications updated … ");
+            let this = self.clone();
+            tokio::task::spawn_blocking(move || this.notifications_updated());
+            match notification_rx.changed().await {
+                Ok(_) => continue,
+                Err(e) => {
+                    error!("{}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn setup_notifications_page(&self) {
+        let imp = self.imp();
+
+        let notifications_store = gio::ListStore::new::<NotificationItem>();
+        imp.notifications_store
+            .replace(Some(Rc::new(notifications_store)));
+
+        imp.notifications_model
+            .set_model(Some(&self.notifications_store().clone()));
+        imp.notifications_model.set_autoselect(false);
+        imp.notifications_model.set_can_unselect(true);
+        imp.notifications_model.connect_selected_item_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            move |notifications_model| {
+                if let Some(item) = notifications_model.selected_item() {
+                    let notification_item = item.downcast_ref::<NotificationItem>().unwrap();
+                    if let Some(application) = this.application() {
+                        application.activate_action(
+                            OPEN_NOTIFICATION_ACTION,
+                            Some(&notification_item.id().to_variant()),
+                        );
+                    }
+                    notifications_model.unselect_all();
+                }
+            }
+        ));
+
+        imp.notifications_factory
+            .connect_setup(clone!(move |_, item| {
+                let row = libadwaita::ActionRow::new();
+                row.set_title_lines(2);
+                row.set_subtitle_lines(2);
+                row.set_activatable(true);
+                let unread_icon = gtk::Image::from_icon_name("mail-unread-symbolic");
+                unread_icon.set_tooltip_text(Some("Unread"));
+                row.add_prefix(&unread_icon);
+                let list_item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                list_item.set_child(Some(&row));
+                list_item
+                    .property_expression("item")
+                    .chain_property::<NotificationItem>("title")
+                    .bind(&row, "title", gtk::Widget::NONE);
+                list_item
+                    .property_expression("item")
+                    .chain_property::<NotificationItem>("body")
+                    .bind(&row, "subtitle", gtk::Widget::NONE);
+                list_item
+                    .property_expression("item")
+                    .chain_property::<NotificationItem>("timestamp")
+                    .bind(&row, "tooltip-text", gtk::Widget::NONE);
+                list_item
+                    .property_expression("item")
+                    .chain_property::<NotificationItem>("read")
+                    .chain_closure::<bool>(closure!(|_: Option<Object>, x: bool| { !x }))
+                    .bind(&unread_icon, "visible", gtk::Widget::NONE);
+            }));
+
+        imp.notifications_view
+            .set_model(Some(&imp.notifications_model));
+        imp.notifications_view
+            .set_factory(Some(&imp.notifications_factory));
+        imp.notifications_view.set_enable_rubberband(false);
+        imp.notifications_view
+            .set_hscroll_policy(gtk::ScrollablePolicy::Minimum);
+        imp.notifications_view
+            .set_vscroll_policy(gtk::ScrollablePolicy::Natural);
+        imp.notifications_view.set_vexpand(true);
+        imp.notifications_view.add_css_class("boxed-list-separate");
+        imp.notifications_view.add_css_class("navigation-sidebar");
+
+        imp.notifications_scrolled_window
+            .set_child(Some(&imp.notifications_view));
+        imp.notifications_scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.notifications_scrolled_window
+            .set_propagate_natural_height(true);
+        imp.notifications_scrolled_window
+            .set_propagate_natural_width(true);
+        self.notifications_store()
+            .property_expression("n-items")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: u32| { x == 0 }))
+            .bind(&imp.notifications_placeholder, "visible", gtk::Widget::NONE);
+        imp.notifications_placeholder
+            .property_expression("visible")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: bool| { !x }))
+            .bind(
+                &imp.notifications_scrolled_window,
+                "visible",
+                gtk::Widget::NONE,
+            );
+
+        imp.notifications_label.set_label("Notifications");
+        imp.notifications_label.set_margin_top(24);
+        imp.notifications_label.set_margin_bottom(24);
+        imp.notifications_label.add_css_class("title-1");
+        imp.notifications_placeholder
+            .set_label("No notifications … ");
+        imp.notifications_placeholder.set_margin_top(24);
+        imp.notifications_placeholder.set_margin_bottom(24);
+        imp.notifications_placeholder.add_css_class("title-2");
+
+        imp.notifications_read_button.set_label("Mark all as read");
+        imp.notifications_read_button.add_css_class("pill");
+        imp.notifications_read_button.set_halign(gtk::Align::Center);
+        imp.notifications_read_button.set_sensitive(false);
+        imp.notifications_read_button.connect_clicked(move |_| {
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = DATABASE.mark_notifications_read() {
+                    error!("{}", e);
+                }
+            });
+        });
+
+        imp.notifications_box
+            .set_orientation(gtk::Orientation::Vertical);
+        imp.notifications_box.set_spacing(4);
+        imp.notifications_box.append(&imp.notifications_label);
+        imp.notifications_box.append(&imp.notifications_read_button);
+        imp.notifications_box.append(&imp.notifications_placeholder);
+        imp.notifications_box
+            .append(&imp.notifications_scrolled_window);
+
+        imp.side_view_stack.add_titled_with_icon(
+            &imp.notifications_box,
+            Some("notifications"),
+            "Notifications",
+            "preferences-system-notifications-symbolic",
+        );
+    }
+}
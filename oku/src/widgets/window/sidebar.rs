@@ -230,6 +230,57 @@ impl Window {
         }
     }
 
+    pub fn drafts_store(&self) -> Ref<'_, gio::ListStore> {
+        let drafts_store = self.imp().drafts_store.borrow();
+
+        Ref::map(drafts_store, |drafts_store| {
+            let drafts_store = drafts_store.as_deref().unwrap();
+            drafts_store
+        })
+    }
+
+    pub async fn drafts_updated(&self) {
+        if let Some(node) = NODE.get() {
+            let drafts = node.drafts().await;
+            let ctx = glib::MainContext::default();
+            let this = self.clone();
+            ctx.invoke(move || {
+                let imp = this.imp();
+                let drafts_store = this.drafts_store();
+                let old_store = drafts_store.snapshot();
+                let favicon_database = this.favicon_database();
+                drafts_store.remove_all();
+                for draft in drafts.into_iter() {
+                    drafts_store.append(&BookmarkItem::new(
+                        draft.note.url.to_string(),
+                        draft.note.title,
+                        draft.note.body,
+                        draft.note.tags,
+                        &favicon_database,
+                    ));
+                }
+
+                let items_changed =
+                    imp.drafts_sidebar_initialised.get() && old_store != drafts_store.snapshot();
+                if let Some(drafts_page) =
+                    get_view_stack_page_by_name("drafts".to_string(), &imp.side_view_stack)
+                {
+                    if matches!(get_view_stack_page_by_name(
+                        imp.side_view_stack
+                            .visible_child_name()
+                            .unwrap_or_default()
+                            .to_string(),
+                            &imp.side_view_stack,
+                    ), Some(x) if x == drafts_page)
+                    {
+                        drafts_page
+                            .set_needs_attention(drafts_page.needs_attention() || items_changed);
+                    }
+                }
+            });
+        }
+    }
+
     pub fn setup_sidebar(&self, web_context: &WebContext) {
         let imp = self.imp();
 
@@ -238,6 +289,10 @@ impl Window {
         self.setup_replicas_page();
         self.setup_history_page(web_context);
         self.setup_bookmarks_page(web_context);
+        self.setup_drafts_page();
+        self.setup_notifications_page();
+        self.setup_messages_page();
+        self.setup_feeds_page(web_context);
         self.setup_downloads_page();
         imp.side_view_stack
             .connect_visible_child_notify(clone!(move |side_view_stack| {
@@ -297,8 +352,28 @@ impl Window {
 
         imp.bookmarks_factory.connect_setup(clone!(move |_, item| {
             let row = widgets::bookmark_row::BookmarkRow::new();
+            row.set_selectable(true);
             let list_item = item.downcast_ref::<gtk::ListItem>().unwrap();
             list_item.set_child(Some(&row));
+            list_item
+                .property_expression("item")
+                .chain_property::<BookmarkItem>("selected")
+                .bind(&row, "selected", gtk::Widget::NONE);
+            row.connect_notify_local(
+                Some("selected"),
+                clone!(
+                    #[weak]
+                    list_item,
+                    move |row, _| {
+                        if let Some(bookmark_item) = list_item.item().and_downcast::<BookmarkItem>()
+                        {
+                            if bookmark_item.selected() != row.selected() {
+                                bookmark_item.set_property("selected", row.selected());
+                            }
+                        }
+                    }
+                ),
+            );
             list_item
                 .property_expression("item")
                 .chain_property::<BookmarkItem>("url")
@@ -364,6 +439,7 @@ impl Window {
         imp.bookmarks_search_placeholder.add_css_class("title-2");
 
         self.setup_bookmarks_stack(web_context);
+        self.setup_bookmarks_publishing();
 
         imp.bookmarks_all_box
             .set_orientation(gtk::Orientation::Vertical);
@@ -376,6 +452,7 @@ impl Window {
         imp.bookmarks_box.set_spacing(4);
         imp.bookmarks_box.append(&imp.bookmarks_label);
         imp.bookmarks_box.append(&imp.bookmarks_search);
+        imp.bookmarks_box.append(&imp.bookmarks_publish_list_box);
         imp.bookmarks_box.append(&imp.bookmarks_stack);
 
         imp.side_view_stack.add_titled_with_icon(
@@ -505,8 +582,29 @@ impl Window {
         imp.bookmarks_search_factory
             .connect_setup(clone!(move |_, item| {
                 let row = widgets::bookmark_row::BookmarkRow::new();
+                row.set_selectable(true);
                 let list_item = item.downcast_ref::<gtk::ListItem>().unwrap();
                 list_item.set_child(Some(&row));
+                list_item
+                    .property_expression("item")
+                    .chain_property::<BookmarkItem>("selected")
+                    .bind(&row, "selected", gtk::Widget::NONE);
+                row.connect_notify_local(
+                    Some("selected"),
+                    clone!(
+                        #[weak]
+                        list_item,
+                        move |row, _| {
+                            if let Some(bookmark_item) =
+                                list_item.item().and_downcast::<BookmarkItem>()
+                            {
+                                if bookmark_item.selected() != row.selected() {
+                                    bookmark_item.set_property("selected", row.selected());
+                                }
+                            }
+                        }
+                    ),
+                );
                 list_item
                     .property_expression("item")
                     .chain_property::<BookmarkItem>("url")
@@ -984,6 +1082,112 @@ impl Window {
         );
     }
 
+    pub fn setup_drafts_page(&self) {
+        let imp = self.imp();
+
+        let drafts_store = gio::ListStore::new::<BookmarkItem>();
+        imp.drafts_store.replace(Some(Rc::new(drafts_store)));
+
+        imp.drafts_model
+            .set_model(Some(&self.drafts_store().clone()));
+        imp.drafts_model.set_autoselect(false);
+        imp.drafts_model.set_can_unselect(true);
+        imp.drafts_model.connect_selected_item_notify(clone!(
+            #[weak(rename_to = this)]
+            self,
+            #[weak]
+            imp,
+            move |drafts_model| {
+                if let Some(item) = drafts_model.selected_item() {
+                    let draft_item = item.downcast_ref::<BookmarkItem>().unwrap();
+                    let draft = url::Url::parse(&draft_item.url()).ok().and_then(|url| {
+                        oku_core::database::core::DATABASE
+                            .get_draft(&url)
+                            .ok()
+                            .flatten()
+                    });
+                    widgets::note_editor::NoteEditor::new(Some(&this), None, draft);
+                    imp.drafts_model.unselect_all();
+                }
+            }
+        ));
+
+        imp.drafts_factory.connect_setup(clone!(move |_, item| {
+            let row = widgets::bookmark_row::BookmarkRow::new();
+            row.set_draft(true);
+            let list_item = item.downcast_ref::<gtk::ListItem>().unwrap();
+            list_item.set_child(Some(&row));
+            list_item
+                .property_expression("item")
+                .chain_property::<BookmarkItem>("url")
+                .bind(&row, "url", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<BookmarkItem>("title")
+                .bind(&row, "title-property", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<BookmarkItem>("body")
+                .bind(&row, "body", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<BookmarkItem>("tags")
+                .bind(&row, "tags", gtk::Widget::NONE);
+            list_item
+                .property_expression("item")
+                .chain_property::<BookmarkItem>("favicon")
+                .bind(&row, "favicon", gtk::Widget::NONE);
+        }));
+
+        imp.drafts_view.set_model(Some(&imp.drafts_model));
+        imp.drafts_view.set_factory(Some(&imp.drafts_factory));
+        imp.drafts_view.set_enable_rubberband(false);
+        imp.drafts_view
+            .set_hscroll_policy(gtk::ScrollablePolicy::Minimum);
+        imp.drafts_view
+            .set_vscroll_policy(gtk::ScrollablePolicy::Natural);
+        imp.drafts_view.set_vexpand(true);
+        imp.drafts_view.add_css_class("boxed-list-separate");
+        imp.drafts_view.add_css_class("navigation-sidebar");
+
+        imp.drafts_scrolled_window.set_child(Some(&imp.drafts_view));
+        imp.drafts_scrolled_window
+            .set_hscrollbar_policy(gtk::PolicyType::Never);
+        imp.drafts_scrolled_window
+            .set_propagate_natural_height(true);
+        imp.drafts_scrolled_window.set_propagate_natural_width(true);
+        self.drafts_store()
+            .property_expression("n-items")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: u32| { x == 0 }))
+            .bind(&imp.drafts_placeholder, "visible", gtk::Widget::NONE);
+        imp.drafts_placeholder
+            .property_expression("visible")
+            .chain_closure::<bool>(closure!(|_: Option<Object>, x: bool| { !x }))
+            .bind(&imp.drafts_scrolled_window, "visible", gtk::Widget::NONE);
+
+        imp.drafts_label.set_label("Drafts");
+        imp.drafts_label.set_margin_top(24);
+        imp.drafts_label.set_margin_bottom(24);
+        imp.drafts_label.add_css_class("title-1");
+        imp.drafts_placeholder.set_label("No drafts … ");
+        imp.drafts_placeholder.set_margin_top(24);
+        imp.drafts_placeholder.set_margin_bottom(24);
+        imp.drafts_placeholder.add_css_class("title-2");
+
+        imp.drafts_box.set_orientation(gtk::Orientation::Vertical);
+        imp.drafts_box.set_spacing(4);
+        imp.drafts_box.append(&imp.drafts_label);
+        imp.drafts_box.append(&imp.drafts_placeholder);
+        imp.drafts_box.append(&imp.drafts_scrolled_window);
+
+        imp.side_view_stack.add_titled_with_icon(
+            &imp.drafts_box,
+            Some("drafts"),
+            "Drafts",
+            "document-edit-symbolic",
+        );
+    }
+
     pub fn setup_sidebar_button_clicked(&self) {
         let imp = self.imp();
 
@@ -2,6 +2,7 @@ use crate::widgets::address_entry::AddressEntry;
 use glib::object::{Cast, IsA};
 use gtk::{prelude::EditableExt, prelude::WidgetExt};
 use log::error;
+use oku_core::fs::util::encode_search_value;
 use oku_core::iroh_docs::DocTicket;
 use std::{path::PathBuf, str::FromStr};
 use webkit2gtk::{functions::uri_for_display, prelude::WebViewExt};
@@ -41,14 +42,14 @@ pub fn connect(nav_entry: &impl IsA<gtk::Editable>, web_view: &webkit2gtk::WebVi
                 // Still not valid, even with protocol
                 Err(e) => {
                     error!("{}", e);
-                    web_view.load_uri(&format!("oku:search/{}", nav_text));
+                    web_view.load_uri(&format!("oku:search/{}", encode_search_value(&nav_text)));
                 }
             }
         }
         // URL is malformed beyond missing a scheme
         Err(e) => {
             error!("{}", e);
-            web_view.load_uri(&format!("oku:search/{}", nav_text));
+            web_view.load_uri(&format!("oku:search/{}", encode_search_value(&nav_text)));
         }
     }
 }